@@ -1,34 +1,210 @@
+use async_trait::async_trait;
 use dotenv::dotenv;
-use reqwest::Client;
+use rand::Rng;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::env;
 use std::error::Error;
 use std::fmt;
-use tracing::{debug, error, info};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 // Using fully qualified path for auth module
 use crate::auth::TencentAuth;
 
-// Define a custom error type to handle different error scenarios
-#[derive(Debug)]
-pub struct TencentApiError {
+/// Error details reported by the Tencent Meeting API
+///
+/// Failed requests carry an `error_info` envelope in the response body, e.g.
+/// `{"error_info": {"error_code": 190303, "new_error_code": 9061, "message": "..."}}`.
+/// The request ID is taken from the `X-TC-Trace` response header when present.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiErrorDetail {
+    pub http_status: u16,
+    pub error_code: Option<i64>,
+    pub new_error_code: Option<i64>,
     pub message: String,
+    pub request_id: Option<String>,
+}
+
+impl fmt::Display for ApiErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HTTP {}", self.http_status)?;
+        if let Some(code) = self.error_code {
+            write!(f, ", error_code {}", code)?;
+        }
+        if let Some(code) = self.new_error_code {
+            write!(f, ", new_error_code {}", code)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request id {})", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+// Body of a failed Tencent Meeting API response
+#[derive(Debug, Deserialize)]
+struct ErrorEnvelope {
+    error_info: ErrorInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorInfo {
+    #[serde(default)]
+    error_code: Option<i64>,
+    #[serde(default)]
+    new_error_code: Option<i64>,
+    #[serde(default)]
+    message: String,
+}
+
+/// Errors returned by `TencentMeetingClient` operations
+#[derive(Debug)]
+pub enum TencentApiError {
+    /// The request never produced an HTTP response (connection, DNS, timeout)
+    ///
+    /// `connect` is set when the connection could not be established at all,
+    /// which guarantees Tencent never saw the request.
+    Transport {
+        message: String,
+        timeout: bool,
+        connect: bool,
+    },
+    /// Credentials or request signature were rejected (HTTP 401/403)
+    Auth(ApiErrorDetail),
+    /// Too many requests (HTTP 429)
+    RateLimited(ApiErrorDetail),
+    /// The meeting, room or operator does not exist (HTTP 404)
+    NotFound(ApiErrorDetail),
+    /// Tencent reported an internal failure (HTTP 5xx)
+    Server(ApiErrorDetail),
+    /// Any other rejection, e.g. invalid parameters or conflicting bookings
+    Business(ApiErrorDetail),
+    /// The response could not be parsed
+    InvalidResponse(String),
+    /// The request could not be built
+    InvalidRequest(String),
+}
+
+impl TencentApiError {
+    /// Build an error from a non-successful HTTP response
+    pub fn from_response(http_status: u16, body: &str, request_id: Option<String>) -> Self {
+        let (error_code, new_error_code, message) =
+            match serde_json::from_str::<ErrorEnvelope>(body) {
+                Ok(envelope) => (
+                    envelope.error_info.error_code,
+                    envelope.error_info.new_error_code,
+                    envelope.error_info.message,
+                ),
+                Err(_) => (None, None, body.trim().to_string()),
+            };
+
+        let detail = ApiErrorDetail {
+            http_status,
+            error_code,
+            new_error_code,
+            message,
+            request_id,
+        };
+
+        match http_status {
+            401 | 403 => TencentApiError::Auth(detail),
+            404 => TencentApiError::NotFound(detail),
+            429 => TencentApiError::RateLimited(detail),
+            500..=599 => TencentApiError::Server(detail),
+            _ => TencentApiError::Business(detail),
+        }
+    }
+
+    /// Details reported by Tencent, if the error came from an API response
+    pub fn detail(&self) -> Option<&ApiErrorDetail> {
+        match self {
+            TencentApiError::Auth(detail)
+            | TencentApiError::RateLimited(detail)
+            | TencentApiError::NotFound(detail)
+            | TencentApiError::Server(detail)
+            | TencentApiError::Business(detail) => Some(detail),
+            _ => None,
+        }
+    }
+
+    /// Whether a failed request may be sent again
+    ///
+    /// Rate-limited requests and requests that never reached Tencent are always
+    /// safe to retry. Timeouts and server errors are only retried for idempotent
+    /// operations, since the first attempt may already have taken effect.
+    pub fn is_retryable(&self, idempotent: bool) -> bool {
+        match self {
+            TencentApiError::RateLimited(_) => true,
+            TencentApiError::Transport { connect: true, .. } => true,
+            TencentApiError::Transport { .. } | TencentApiError::Server(_) => idempotent,
+            _ => false,
+        }
+    }
+
+    /// HTTP status to report to our own callers for this error
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            TencentApiError::Transport { timeout: true, .. } => StatusCode::GATEWAY_TIMEOUT,
+            TencentApiError::Transport { .. } => StatusCode::BAD_GATEWAY,
+            TencentApiError::Auth(_) => StatusCode::BAD_GATEWAY,
+            TencentApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            TencentApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            TencentApiError::Server(_) => StatusCode::BAD_GATEWAY,
+            TencentApiError::Business(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            TencentApiError::InvalidResponse(_) => StatusCode::BAD_GATEWAY,
+            TencentApiError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+        }
+    }
 }
 
 impl fmt::Display for TencentApiError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Tencent API Error: {}", self.message)
+        match self {
+            TencentApiError::Transport { message, .. } => {
+                write!(f, "Tencent API transport error: {}", message)
+            }
+            TencentApiError::Auth(detail) => {
+                write!(f, "Tencent API authentication failed: {}", detail)
+            }
+            TencentApiError::RateLimited(detail) => {
+                write!(f, "Tencent API rate limit exceeded: {}", detail)
+            }
+            TencentApiError::NotFound(detail) => {
+                write!(f, "Tencent API resource not found: {}", detail)
+            }
+            TencentApiError::Server(detail) => write!(f, "Tencent API server error: {}", detail),
+            TencentApiError::Business(detail) => {
+                write!(f, "Tencent API rejected request: {}", detail)
+            }
+            TencentApiError::InvalidResponse(message) => {
+                write!(f, "Invalid Tencent API response: {}", message)
+            }
+            TencentApiError::InvalidRequest(message) => {
+                write!(f, "Invalid Tencent API request: {}", message)
+            }
+        }
     }
 }
 
 impl Error for TencentApiError {}
 
+impl From<reqwest::Error> for TencentApiError {
+    fn from(err: reqwest::Error) -> Self {
+        TencentApiError::Transport {
+            message: err.to_string(),
+            timeout: err.is_timeout(),
+            connect: err.is_connect(),
+        }
+    }
+}
+
 impl From<serde_json::Error> for TencentApiError {
     fn from(err: serde_json::Error) -> Self {
-        TencentApiError {
-            message: format!("JSON parsing error: {}", err),
-        }
+        TencentApiError::InvalidResponse(format!("JSON parsing error: {}", err))
     }
 }
 
@@ -140,6 +316,15 @@ pub struct MeetingInfo {
     pub end_time: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub join_url: Option<String>,
+    // Fields below are only returned when querying a meeting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>, // e.g. "MEETING_STATE_INIT", "MEETING_STATE_CANCELLED"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<User>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<MeetingSettings>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -148,6 +333,70 @@ pub struct CreateMeetingResponse {
     pub meeting_info_list: Vec<MeetingInfo>,
 }
 
+// Meeting query types
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryMeetingResponse {
+    pub meeting_number: i32,
+    pub meeting_info_list: Vec<MeetingInfo>,
+}
+
+// Meeting update types
+//
+// Only the fields that are set are sent; Tencent keeps the rest unchanged.
+// Start and end time must be changed together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateMeetingRequest {
+    pub userid: String,
+    pub instanceid: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+impl UpdateMeetingRequest {
+    /// Check the request before sending it to Tencent
+    pub fn validate(&self) -> Result<(), TencentApiError> {
+        if self.start_time.is_some() != self.end_time.is_some() {
+            return Err(TencentApiError::InvalidRequest(
+                "start_time and end_time must be updated together".to_string(),
+            ));
+        }
+
+        if self.subject.is_none()
+            && self.start_time.is_none()
+            && self.password.is_none()
+            && self.location.is_none()
+        {
+            return Err(TencentApiError::InvalidRequest(
+                "Meeting update contains no changes".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatedMeetingInfo {
+    pub meeting_id: String,
+    pub meeting_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateMeetingResponse {
+    pub meeting_number: i32,
+    pub meeting_info_list: Vec<UpdatedMeetingInfo>,
+}
+
 // Meeting cancellation types
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CancelMeetingRequest {
@@ -182,6 +431,152 @@ pub struct ReleaseRoomsRequest {
 
 use crate::models::meeting::Operator;
 
+/// Retry policy for transient Tencent Meeting API failures
+///
+/// Failed attempts are retried with exponential backoff and jitter: the delay
+/// before attempt `n + 1` is a random value between half and all of
+/// `base_delay * 2^(n - 1)`, capped at `max_delay`.
+///
+/// All attempts and delays of one request share the `budget`: the last
+/// attempt is cut short when it runs out, and no retry is started that
+/// couldn't begin within it. The budget covers a single API call; a webhook
+/// that makes several calls can still take longer than the server's request
+/// timeout.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Time allowed for all attempts of a request, including the delays
+    pub budget: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+            budget: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Load the retry policy from environment variables, falling back to defaults
+    ///
+    /// - `TENCENT_MEETING_RETRY_MAX_ATTEMPTS`
+    /// - `TENCENT_MEETING_RETRY_BASE_DELAY_MS`
+    /// - `TENCENT_MEETING_RETRY_MAX_DELAY_MS`
+    /// - `TENCENT_MEETING_RETRY_BUDGET_MS`
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let read_u64 = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            max_attempts: read_u64("TENCENT_MEETING_RETRY_MAX_ATTEMPTS")
+                .map(|v| v.max(1) as u32)
+                .unwrap_or(default.max_attempts),
+            base_delay: read_u64("TENCENT_MEETING_RETRY_BASE_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.base_delay),
+            max_delay: read_u64("TENCENT_MEETING_RETRY_MAX_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.max_delay),
+            budget: read_u64("TENCENT_MEETING_RETRY_BUDGET_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.budget),
+        }
+    }
+
+    /// Delay to wait after the given failed attempt (1-based)
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self
+            .base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay);
+
+        // Jitter between 50% and 100% of the computed delay
+        let millis = delay.as_millis() as u64;
+        if millis == 0 {
+            return delay;
+        }
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis))
+    }
+}
+
+/// Operations the service needs from a Tencent Meeting backend
+///
+/// `TencentMeetingClient` is the production implementation. Handlers and
+/// services are generic over this trait so tests can substitute an in-memory
+/// backend and exercise the real (non-simulation) webhook paths.
+#[async_trait]
+pub trait MeetingApi: Send + Sync {
+    /// List meeting rooms available to the default operator
+    async fn list_rooms(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<MeetingRoomsResponse, TencentApiError>;
+
+    /// Create a new meeting
+    async fn create_meeting(
+        &self,
+        meeting_request: &CreateMeetingRequest,
+    ) -> Result<CreateMeetingResponse, TencentApiError>;
+
+    /// Query the current details of a meeting
+    async fn get_meeting(
+        &self,
+        meeting_id: &str,
+        userid: &str,
+    ) -> Result<MeetingInfo, TencentApiError>;
+
+    /// Change an existing meeting's time, subject or location
+    async fn update_meeting(
+        &self,
+        meeting_id: &str,
+        update_request: &UpdateMeetingRequest,
+    ) -> Result<UpdateMeetingResponse, TencentApiError>;
+
+    /// Cancel an existing meeting
+    async fn cancel_meeting(
+        &self,
+        meeting_id: &str,
+        cancel_request: &CancelMeetingRequest,
+    ) -> Result<(), TencentApiError>;
+
+    /// Book meeting rooms for a meeting
+    async fn book_rooms(
+        &self,
+        meeting_id: &str,
+        book_request: &BookRoomsRequest,
+    ) -> Result<(), TencentApiError>;
+
+    /// Release meeting rooms booked for a meeting
+    async fn release_rooms(
+        &self,
+        meeting_id: &str,
+        release_request: &ReleaseRoomsRequest,
+    ) -> Result<(), TencentApiError>;
+
+    /// Get the default operator ID used for room operations
+    fn get_operator_id(&self) -> &str;
+
+    /// Get the operator ID for a given operator name, or the default
+    fn get_operator_id_by_name(&self, name: &str) -> String;
+}
+
 /// Client for Tencent Meeting API
 pub struct TencentMeetingClient {
     client: Client,
@@ -192,6 +587,7 @@ pub struct TencentMeetingClient {
     sdk_id: String,
     operators: Vec<Operator>,
     default_operator_id: String,
+    retry_policy: RetryPolicy,
 }
 
 impl Default for TencentMeetingClient {
@@ -214,8 +610,14 @@ impl TencentMeetingClient {
             .map(|op| op.id.clone())
             .unwrap_or_else(|| "admin".to_string());
 
+        // Bound each attempt so a hung request can still be retried
+        let request_timeout = env::var("TENCENT_MEETING_REQUEST_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(5);
+
         Self {
-            client: Client::new(),
+            client: Self::build_http_client(Duration::from_secs(request_timeout)),
             app_id: env::var("TENCENT_MEETING_APP_ID")
                 .expect("TENCENT_MEETING_APP_ID must be set in environment"),
             secret_id: env::var("TENCENT_MEETING_SECRET_ID")
@@ -227,9 +629,59 @@ impl TencentMeetingClient {
             sdk_id: env::var("TENCENT_MEETING_SDK_ID").unwrap_or_default(),
             operators,
             default_operator_id,
+            retry_policy: RetryPolicy::from_env(),
+        }
+    }
+
+    /// Create a client with explicit credentials and endpoint
+    ///
+    /// Uses a single "admin" operator and the default retry policy.
+    pub fn with_credentials(
+        app_id: &str,
+        secret_id: &str,
+        secret_key: &str,
+        endpoint: &str,
+    ) -> Self {
+        Self {
+            client: Self::build_http_client(Duration::from_secs(5)),
+            app_id: app_id.to_string(),
+            secret_id: secret_id.to_string(),
+            secret_key: secret_key.to_string(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            sdk_id: String::new(),
+            operators: vec![Operator {
+                name: "admin".to_string(),
+                id: "admin".to_string(),
+            }],
+            default_operator_id: "admin".to_string(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Replace the retry policy used for API requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Get the retry policy used for API requests
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    fn build_http_client(request_timeout: Duration) -> Client {
+        Client::builder()
+            .timeout(request_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Failed to build HTTP client with timeout, using defaults: {}",
+                    e
+                );
+                Client::new()
+            })
+    }
+
     /// Parse operators from environment variable
     /// Format: "name1:id1,name2:id2,name3:id3"
     fn parse_operators_from_env() -> Vec<Operator> {
@@ -338,60 +790,135 @@ impl TencentMeetingClient {
         request
     }
 
-    /// List meeting rooms from the Tencent Meeting API
-    pub async fn list_rooms(
+    /// Sign and send a request, returning the response body on success
+    ///
+    /// Non-2xx responses are converted into a `TencentApiError` using the
+    /// `error_info` envelope of the response body.
+    async fn send_request(
         &self,
-        page: usize,
-        page_size: usize,
-    ) -> Result<MeetingRoomsResponse, Box<dyn Error + Send + Sync>> {
-        let method = "GET";
-        let uri = "/v1/meeting-rooms";
-        let query = format!(
-            "?page={}&page_size={}&operator_id={}&operator_id_type=1",
-            page, page_size, &self.default_operator_id
-        );
-        let full_uri = format!("{}{}", uri, query);
-        let url = format!("{}{}", self.endpoint, full_uri);
+        method: reqwest::Method,
+        uri: &str,
+        body: Option<String>,
+    ) -> Result<String, TencentApiError> {
+        let url = format!("{}{}", self.endpoint, uri);
 
         let timestamp = TencentAuth::get_timestamp();
         let nonce = TencentAuth::generate_nonce();
-        let request_body = ""; // Empty for GET request
-
-        let signature = self.generate_signature(method, &full_uri, timestamp, &nonce, request_body);
+        let signature = self.generate_signature(
+            method.as_str(),
+            uri,
+            timestamp,
+            &nonce,
+            body.as_deref().unwrap_or(""),
+        );
 
-        info!("Making request to list meeting rooms");
         debug!("API URL: {}", url);
+        if let Some(body_str) = &body {
+            debug!("Request body: {}", body_str);
+        }
 
         // Build and send the request
-        let request = self.build_request(
-            reqwest::Method::GET,
-            &url,
-            timestamp,
-            &nonce,
-            &signature,
-            None,
-        );
+        let request = self.build_request(method, &url, timestamp, &nonce, &signature, body);
 
         let res = request.send().await?;
         info!("Response received with status: {}", res.status());
 
-        // Get response body for logging
         let status = res.status();
+        let request_id = res
+            .headers()
+            .get("X-TC-Trace")
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
         let response_text = res.text().await?;
         debug!("API Response: {}", response_text);
 
-        // For non-successful responses, log the details before trying to parse
         if !status.is_success() {
-            error!("API request failed with status: {}", status);
-            error!("Request URL: {}", url);
-            error!("Response body: {}", response_text);
-            error!("This could be due to incorrect credentials or API parameters");
+            let err = TencentApiError::from_response(status.as_u16(), &response_text, request_id);
+            error!("API request to {} failed: {}", uri, err);
+            return Err(err);
         }
 
-        // Parse the response JSON using the ? operator for cleaner error handling
+        Ok(response_text)
+    }
+
+    /// Send a request, retrying transient failures according to the retry policy
+    ///
+    /// Every attempt is signed again with a fresh timestamp and nonce, so a
+    /// retried request is never rejected as a replay. Set `idempotent` to false
+    /// for operations that must not be repeated if the first attempt may have
+    /// reached Tencent. The request fails with a timeout once the policy's
+    /// budget is spent.
+    async fn send_with_retry(
+        &self,
+        method: reqwest::Method,
+        uri: &str,
+        body: Option<String>,
+        idempotent: bool,
+    ) -> Result<String, TencentApiError> {
+        let deadline = Instant::now() + self.retry_policy.budget;
+        let mut attempt = 1;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let result = match tokio::time::timeout(
+                remaining,
+                self.send_request(method.clone(), uri, body.clone()),
+            )
+            .await
+            {
+                Ok(result) => result,
+                Err(_) => Err(TencentApiError::Transport {
+                    message: format!("retry budget of {:?} exhausted", self.retry_policy.budget),
+                    timeout: true,
+                    connect: false,
+                }),
+            };
+
+            match result {
+                Ok(response_text) => return Ok(response_text),
+                Err(err)
+                    if attempt < self.retry_policy.max_attempts && err.is_retryable(idempotent) =>
+                {
+                    let delay = self.retry_policy.backoff_delay(attempt);
+                    if Instant::now() + delay >= deadline {
+                        warn!(
+                            "Attempt {}/{} for {} {} failed ({}), no retry budget left",
+                            attempt, self.retry_policy.max_attempts, method, uri, err
+                        );
+                        return Err(err);
+                    }
+                    warn!(
+                        "Attempt {}/{} for {} {} failed ({}), retrying in {:?}",
+                        attempt, self.retry_policy.max_attempts, method, uri, err, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// List meeting rooms from the Tencent Meeting API
+    pub async fn list_rooms(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<MeetingRoomsResponse, TencentApiError> {
+        let uri = format!(
+            "/v1/meeting-rooms?page={}&page_size={}&operator_id={}&operator_id_type=1",
+            page, page_size, &self.default_operator_id
+        );
+
+        info!("Making request to list meeting rooms");
+
+        let response_text = self
+            .send_with_retry(reqwest::Method::GET, &uri, None, true)
+            .await?;
+
         serde_json::from_str::<MeetingRoomsResponse>(&response_text).map_err(|e| {
             error!("Failed to parse response JSON: {}", e);
-            Box::new(e) as Box<dyn Error + Send + Sync>
+            TencentApiError::from(e)
         })
     }
 
@@ -399,10 +926,8 @@ impl TencentMeetingClient {
     pub async fn create_meeting(
         &self,
         meeting_request: &CreateMeetingRequest,
-    ) -> Result<CreateMeetingResponse, Box<dyn Error + Send + Sync>> {
-        let method = "POST";
+    ) -> Result<CreateMeetingResponse, TencentApiError> {
         let uri = "/v1/meetings";
-        let url = format!("{}{}", self.endpoint, uri);
 
         // The type field needs proper handling since it's a reserved keyword in Rust
         // We'll use the clone with _type populated correctly from type_ in the request
@@ -410,48 +935,89 @@ impl TencentMeetingClient {
         request_to_send._type = meeting_request.type_; // Copy the value from type_ to _type for serialization
 
         let request_body = serde_json::to_string(&request_to_send).map_err(|e| {
-            Box::<dyn Error + Send + Sync>::from(format!(
-                "Failed to serialize meeting request: {}",
-                e
-            ))
+            TencentApiError::InvalidRequest(format!("Failed to serialize meeting request: {}", e))
         })?;
 
-        let timestamp = TencentAuth::get_timestamp();
-        let nonce = TencentAuth::generate_nonce();
+        info!("Making request to create meeting");
 
-        let signature = self.generate_signature(method, uri, timestamp, &nonce, &request_body);
+        // Creating a meeting is not idempotent: only retry failures where the
+        // request is known not to have been processed
 
-        info!("Making request to create meeting");
-        debug!("API URL: {}", url);
-        debug!("Request body: {}", request_body);
+        let response_text = self
+            .send_with_retry(reqwest::Method::POST, uri, Some(request_body), false)
+            .await?;
 
-        // Build and send the request
-        let request = self.build_request(
-            reqwest::Method::POST,
-            &url,
-            timestamp,
-            &nonce,
-            &signature,
-            Some(request_body),
+        serde_json::from_str::<CreateMeetingResponse>(&response_text).map_err(|e| {
+            error!("Failed to parse response JSON: {}", e);
+            TencentApiError::from(e)
+        })
+    }
+
+    /// Query a meeting's details using the Tencent Meeting API
+    ///
+    /// Returns the meeting's current state, including whether it was cancelled
+    /// directly in the Tencent Meeting app.
+    pub async fn get_meeting(
+        &self,
+        meeting_id: &str,
+        userid: &str,
+    ) -> Result<MeetingInfo, TencentApiError> {
+        let uri = format!(
+            "/v1/meetings/{}?userid={}&instanceid=32",
+            meeting_id, userid
         );
 
-        let res = request.send().await?;
-        info!("Response received with status: {}", res.status());
+        info!("Making request to query meeting {}", meeting_id);
+
+        let response_text = self
+            .send_with_retry(reqwest::Method::GET, &uri, None, true)
+            .await?;
+
+        let response =
+            serde_json::from_str::<QueryMeetingResponse>(&response_text).map_err(|e| {
+                error!("Failed to parse response JSON: {}", e);
+                TencentApiError::from(e)
+            })?;
+
+        response
+            .meeting_info_list
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                TencentApiError::InvalidResponse(format!(
+                    "No meeting info returned for meeting {}",
+                    meeting_id
+                ))
+            })
+    }
 
-        // Get response body for logging
-        let status = res.status();
-        let response_text = res.text().await?;
-        debug!("API Response: {}", response_text);
+    /// Update a meeting using the Tencent Meeting API
+    ///
+    /// Unlike cancel-and-recreate this keeps the meeting ID and meeting code
+    /// that attendees already have.
+    pub async fn update_meeting(
+        &self,
+        meeting_id: &str,
+        update_request: &UpdateMeetingRequest,
+    ) -> Result<UpdateMeetingResponse, TencentApiError> {
+        update_request.validate()?;
 
-        // Log any errors but still try to parse the response
-        if !status.is_success() {
-            error!("Create meeting failed with status: {}", status);
-            error!("Response body: {}", response_text);
-        }
+        let uri = format!("/v1/meetings/{}", meeting_id);
 
-        serde_json::from_str::<CreateMeetingResponse>(&response_text).map_err(|e| {
+        let request_body = serde_json::to_string(update_request).map_err(|e| {
+            TencentApiError::InvalidRequest(format!("Failed to serialize update request: {}", e))
+        })?;
+
+        info!("Making request to update meeting {}", meeting_id);
+
+        // Updating sets absolute values, so repeating it is safe
+        let response_text = self
+            .send_with_retry(reqwest::Method::PUT, &uri, Some(request_body), true)
+            .await?;
+
+        serde_json::from_str::<UpdateMeetingResponse>(&response_text).map_err(|e| {
             error!("Failed to parse response JSON: {}", e);
-            Box::new(e) as Box<dyn Error + Send + Sync>
+            TencentApiError::from(e)
         })
     }
 
@@ -460,51 +1026,22 @@ impl TencentMeetingClient {
         &self,
         meeting_id: &str,
         cancel_request: &CancelMeetingRequest,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let method = "POST";
+    ) -> Result<(), TencentApiError> {
         let uri = format!("/v1/meetings/{}/cancel", meeting_id);
-        let url = format!("{}{}", self.endpoint, uri);
 
         let request_body = serde_json::to_string(&cancel_request).map_err(|e| {
-            Box::<dyn Error + Send + Sync>::from(format!(
+            TencentApiError::InvalidRequest(format!(
                 "Failed to serialize cancellation request: {}",
                 e
             ))
         })?;
 
-        let timestamp = TencentAuth::get_timestamp();
-        let nonce = TencentAuth::generate_nonce();
-        let signature = self.generate_signature(method, &uri, timestamp, &nonce, &request_body);
-
         info!("Making request to cancel meeting {}", meeting_id);
-        debug!("API URL: {}", url);
-        debug!("Request body: {}", request_body);
-
-        // Build and send the request
-        let request = self.build_request(
-            reqwest::Method::POST,
-            &url,
-            timestamp,
-            &nonce,
-            &signature,
-            Some(request_body),
-        );
-
-        let res = request.send().await?;
-        info!("Response received with status: {}", res.status());
-
-        // Get response body for logging
-        let status = res.status();
-        let response_text = res.text().await?;
-        debug!("API Response: {}", response_text);
-
-        // Log any errors but let the JSON parsing handle failures
-        if !status.is_success() {
-            error!("Cancel meeting failed with status: {}", status);
-            error!("Response body: {}", response_text);
-        }
 
         // For successful cancellation, the response body is empty
+        self.send_with_retry(reqwest::Method::POST, &uri, Some(request_body), true)
+            .await?;
+
         Ok(())
     }
 
@@ -513,51 +1050,23 @@ impl TencentMeetingClient {
         &self,
         meeting_id: &str,
         book_request: &BookRoomsRequest,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let method = "POST";
+    ) -> Result<(), TencentApiError> {
         let uri = format!("/v1/meetings/{}/book-rooms", meeting_id);
-        let url = format!("{}{}", self.endpoint, uri);
 
         let request_body = serde_json::to_string(&book_request).map_err(|e| {
-            Box::<dyn Error + Send + Sync>::from(format!(
+            TencentApiError::InvalidRequest(format!(
                 "Failed to serialize book rooms request: {}",
                 e
             ))
         })?;
 
-        let timestamp = TencentAuth::get_timestamp();
-        let nonce = TencentAuth::generate_nonce();
-        let signature = self.generate_signature(method, &uri, timestamp, &nonce, &request_body);
-
         info!("Making request to book rooms for meeting {}", meeting_id);
-        debug!("API URL: {}", url);
-        debug!("Request body: {}", request_body);
-
-        // Build and send the request
-        let request = self.build_request(
-            reqwest::Method::POST,
-            &url,
-            timestamp,
-            &nonce,
-            &signature,
-            Some(request_body),
-        );
-
-        let res = request.send().await?;
-        info!("Response received with status: {}", res.status());
 
-        // Get response body for logging
-        let status = res.status();
-        let response_text = res.text().await?;
-        debug!("API Response: {}", response_text);
+        // Booking again after a timeout could book the room twice: only retry
+        // failures where the request is known not to have been processed
+        self.send_with_retry(reqwest::Method::POST, &uri, Some(request_body), false)
+            .await?;
 
-        // Log any errors but let the JSON parsing handle failures
-        if !status.is_success() {
-            error!("Book rooms failed with status: {}", status);
-            error!("Response body: {}", response_text);
-        }
-
-        // For successful booking, the response body is typically empty
         Ok(())
     }
 
@@ -566,51 +1075,155 @@ impl TencentMeetingClient {
         &self,
         meeting_id: &str,
         release_request: &ReleaseRoomsRequest,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let method = "POST";
+    ) -> Result<(), TencentApiError> {
         let uri = format!("/v1/meetings/{}/release-rooms", meeting_id);
-        let url = format!("{}{}", self.endpoint, uri);
 
         let request_body = serde_json::to_string(&release_request).map_err(|e| {
-            Box::<dyn Error + Send + Sync>::from(format!(
+            TencentApiError::InvalidRequest(format!(
                 "Failed to serialize release rooms request: {}",
                 e
             ))
         })?;
 
-        let timestamp = TencentAuth::get_timestamp();
-        let nonce = TencentAuth::generate_nonce();
-        let signature = self.generate_signature(method, &uri, timestamp, &nonce, &request_body);
-
         info!("Making request to release rooms for meeting {}", meeting_id);
-        debug!("API URL: {}", url);
-        debug!("Request body: {}", request_body);
 
-        // Build and send the request
-        let request = self.build_request(
-            reqwest::Method::POST,
-            &url,
-            timestamp,
-            &nonce,
-            &signature,
-            Some(request_body),
-        );
+        // For successful room release, the response body is typically empty
+        self.send_with_retry(reqwest::Method::POST, &uri, Some(request_body), true)
+            .await?;
 
-        let res = request.send().await?;
-        info!("Response received with status: {}", res.status());
+        Ok(())
+    }
+}
 
-        // Get response body for logging
-        let status = res.status();
-        let response_text = res.text().await?;
-        debug!("API Response: {}", response_text);
+#[async_trait]
+impl MeetingApi for TencentMeetingClient {
+    async fn list_rooms(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<MeetingRoomsResponse, TencentApiError> {
+        TencentMeetingClient::list_rooms(self, page, page_size).await
+    }
 
-        // Log any errors but let the JSON parsing handle failures
-        if !status.is_success() {
-            error!("Release rooms failed with status: {}", status);
-            error!("Response body: {}", response_text);
-        }
+    async fn create_meeting(
+        &self,
+        meeting_request: &CreateMeetingRequest,
+    ) -> Result<CreateMeetingResponse, TencentApiError> {
+        TencentMeetingClient::create_meeting(self, meeting_request).await
+    }
 
-        // For successful room release, the response body is typically empty
-        Ok(())
+    async fn get_meeting(
+        &self,
+        meeting_id: &str,
+        userid: &str,
+    ) -> Result<MeetingInfo, TencentApiError> {
+        TencentMeetingClient::get_meeting(self, meeting_id, userid).await
+    }
+
+    async fn update_meeting(
+        &self,
+        meeting_id: &str,
+        update_request: &UpdateMeetingRequest,
+    ) -> Result<UpdateMeetingResponse, TencentApiError> {
+        TencentMeetingClient::update_meeting(self, meeting_id, update_request).await
+    }
+
+    async fn cancel_meeting(
+        &self,
+        meeting_id: &str,
+        cancel_request: &CancelMeetingRequest,
+    ) -> Result<(), TencentApiError> {
+        TencentMeetingClient::cancel_meeting(self, meeting_id, cancel_request).await
+    }
+
+    async fn book_rooms(
+        &self,
+        meeting_id: &str,
+        book_request: &BookRoomsRequest,
+    ) -> Result<(), TencentApiError> {
+        TencentMeetingClient::book_rooms(self, meeting_id, book_request).await
+    }
+
+    async fn release_rooms(
+        &self,
+        meeting_id: &str,
+        release_request: &ReleaseRoomsRequest,
+    ) -> Result<(), TencentApiError> {
+        TencentMeetingClient::release_rooms(self, meeting_id, release_request).await
+    }
+
+    fn get_operator_id(&self) -> &str {
+        TencentMeetingClient::get_operator_id(self)
+    }
+
+    fn get_operator_id_by_name(&self, name: &str) -> String {
+        TencentMeetingClient::get_operator_id_by_name(self, name)
+    }
+}
+
+// Allow a shared backend to be used wherever a `MeetingApi` is expected
+#[async_trait]
+impl<T: MeetingApi + ?Sized> MeetingApi for Arc<T> {
+    async fn list_rooms(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<MeetingRoomsResponse, TencentApiError> {
+        (**self).list_rooms(page, page_size).await
+    }
+
+    async fn create_meeting(
+        &self,
+        meeting_request: &CreateMeetingRequest,
+    ) -> Result<CreateMeetingResponse, TencentApiError> {
+        (**self).create_meeting(meeting_request).await
+    }
+
+    async fn get_meeting(
+        &self,
+        meeting_id: &str,
+        userid: &str,
+    ) -> Result<MeetingInfo, TencentApiError> {
+        (**self).get_meeting(meeting_id, userid).await
+    }
+
+    async fn update_meeting(
+        &self,
+        meeting_id: &str,
+        update_request: &UpdateMeetingRequest,
+    ) -> Result<UpdateMeetingResponse, TencentApiError> {
+        (**self).update_meeting(meeting_id, update_request).await
+    }
+
+    async fn cancel_meeting(
+        &self,
+        meeting_id: &str,
+        cancel_request: &CancelMeetingRequest,
+    ) -> Result<(), TencentApiError> {
+        (**self).cancel_meeting(meeting_id, cancel_request).await
+    }
+
+    async fn book_rooms(
+        &self,
+        meeting_id: &str,
+        book_request: &BookRoomsRequest,
+    ) -> Result<(), TencentApiError> {
+        (**self).book_rooms(meeting_id, book_request).await
+    }
+
+    async fn release_rooms(
+        &self,
+        meeting_id: &str,
+        release_request: &ReleaseRoomsRequest,
+    ) -> Result<(), TencentApiError> {
+        (**self).release_rooms(meeting_id, release_request).await
+    }
+
+    fn get_operator_id(&self) -> &str {
+        (**self).get_operator_id()
+    }
+
+    fn get_operator_id_by_name(&self, name: &str) -> String {
+        (**self).get_operator_id_by_name(name)
     }
 }
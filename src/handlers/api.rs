@@ -1,48 +1,75 @@
 use axum::{
     extract::{Json as ExtractJson, Path, Query, State},
     http::StatusCode,
-    response::Json,
+    response::{IntoResponse, Json, Response},
 };
 use serde::Deserialize;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
 use crate::client::{
     BookRoomsRequest, CancelMeetingRequest, CreateMeetingRequest, CreateMeetingResponse,
-    ReleaseRoomsRequest, TencentMeetingClient,
+    MeetingApi, MeetingInfo, ReleaseRoomsRequest, TencentApiError, TencentMeetingClient,
+    UpdateMeetingRequest, UpdateMeetingResponse,
 };
+use crate::handlers::cancellations::{cancel_tencent_meeting, complete_cancellation};
+use crate::models::booking_policy::BookingPolicyConfig;
 use crate::models::common::PaginationParams;
 use crate::models::form::FormSubmission;
-use crate::models::meeting::{MeetingResult, WebhookResponse};
+use crate::models::meeting::{
+    BlackoutResponse, BookingFailurePolicy, BookingPolicyResponse, InvalidTimeSlotResponse,
+    MeetingAction, MeetingResult, RoomBooking, RoomConflict, RoomConflictPolicy, TimeSlot,
+    WebhookResponse,
+};
+use crate::models::reservation::{ReservationStatus, StatusMapping};
+use crate::models::time_zone::TimeZoneConfig;
 
 // Query parameters for webhook authentication
 #[derive(Debug, Deserialize)]
 pub struct WebhookQueryParams {
     pub auth: Option<String>,
 }
-use crate::services::database::DatabaseService;
+use crate::services::async_store::AsyncMeetingStore;
+use crate::services::blackouts::BlackoutCalendars;
+use crate::services::booking_policy::check_booking_policies;
+use crate::services::database::MeetingRecord;
+use crate::services::idempotency::SubmissionLocks;
+use crate::services::jobs::{JobAccepted, JobQueue};
+use crate::services::reconcile::{plan_reconciliation, DesiredMeeting, ReconcileStep};
 use crate::services::time_slots::{
-    create_meeting_with_time_slot, create_merged_meeting, find_mergeable_groups, get_operator_info,
-    get_room_id_for_form, parse_time_slot,
+    create_meeting_with_time_slot, create_merged_meeting, find_mergeable_groups,
+    format_scheduled_label, get_operator_info, get_room_id_for_form, parse_time_slot,
 };
 
 // AppState struct containing shared resources
-pub struct AppState {
-    pub client: TencentMeetingClient,
+//
+// Generic over the Tencent backend so tests can use an in-memory `MeetingApi`
+pub struct AppState<C = TencentMeetingClient> {
+    pub client: C,
     pub user_field_name: String, // Used to identify the operator
     pub dept_field_name: String,
-    pub database: Arc<DatabaseService>,
-    pub xa_room_id: String,                 // Xi'an meeting room ID
-    pub cd_room_id: String,                 // Chengdu meeting room ID
-    pub skip_meeting_creation: bool,        // Toggle to only store in CSV without creating meetings
-    pub skip_room_booking: bool,            // Toggle to create meetings but not book rooms
+    pub database: AsyncMeetingStore,
+    pub xa_room_id: String,                           // Xi'an meeting room ID
+    pub cd_room_id: String,                           // Chengdu meeting room ID
+    pub skip_meeting_creation: bool, // Toggle to only store in CSV without creating meetings
+    pub skip_room_booking: bool,     // Toggle to create meetings but not book rooms
     pub webhook_auth_token: Option<String>, // Authentication token for webhook endpoints
+    pub calendar_feed_token: Option<String>, // Token calendar subscribers pass as ?auth=
+    pub submission_locks: SubmissionLocks, // Serializes webhook processing per entry token
+    pub room_locks: SubmissionLocks, // Serializes conflict checks and bookings per room
+    pub job_queue: Option<Arc<JobQueue>>, // Set when webhooks are processed asynchronously
+    pub booking_failure_policy: BookingFailurePolicy, // What to do with meetings whose room can't be booked
+    pub status_mapping: StatusMapping, // Maps the form's status strings to reservation statuses
+    pub time_zones: TimeZoneConfig,    // Time zones of rooms and forms
+    pub room_conflict_policy: RoomConflictPolicy, // What to do with slots whose room is already booked
+    pub booking_policies: BookingPolicyConfig, // Working hours, duration and advance limits by room or form
+    pub blackout_calendars: Arc<BlackoutCalendars>, // Holidays and blackout windows by room or location
 }
 
 // List meeting rooms endpoint
-#[axum::debug_handler]
-pub async fn list_meeting_rooms(
-    State(state): State<Arc<AppState>>,
+pub async fn list_meeting_rooms<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
     Query(params): Query<PaginationParams>,
 ) -> Result<Json<crate::client::MeetingRoomsResponse>, StatusCode> {
     info!(
@@ -62,15 +89,14 @@ pub async fn list_meeting_rooms(
         }
         Err(e) => {
             error!("Failed to retrieve meeting rooms: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.status_code())
         }
     }
 }
 
 // Create a new meeting endpoint
-#[axum::debug_handler]
-pub async fn create_meeting(
-    State(state): State<Arc<AppState>>,
+pub async fn create_meeting<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
     ExtractJson(request): ExtractJson<CreateMeetingRequest>,
 ) -> Result<Json<CreateMeetingResponse>, StatusCode> {
     info!(
@@ -86,15 +112,141 @@ pub async fn create_meeting(
         }
         Err(e) => {
             error!("Failed to create meeting: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.status_code())
+        }
+    }
+}
+
+// Query parameters for meeting lookup
+#[derive(Debug, Deserialize)]
+pub struct GetMeetingParams {
+    pub userid: Option<String>, // Defaults to the configured operator
+}
+
+// Get meeting details endpoint
+pub async fn get_meeting<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(meeting_id): Path<String>,
+    Query(params): Query<GetMeetingParams>,
+) -> Result<Json<MeetingInfo>, StatusCode> {
+    info!("Received request to get meeting: {}", meeting_id);
+
+    let userid = params
+        .userid
+        .unwrap_or_else(|| state.client.get_operator_id().to_string());
+
+    // Call the Tencent Meeting API to query the meeting
+    match state.client.get_meeting(&meeting_id, &userid).await {
+        Ok(meeting) => {
+            info!(
+                "Retrieved meeting {} with status {:?}",
+                meeting_id, meeting.status
+            );
+            Ok(Json(meeting))
+        }
+        Err(e) => {
+            error!("Failed to get meeting: {}", e);
+            Err(e.status_code())
         }
     }
 }
 
+// Update an existing meeting endpoint
+//
+// Moves the meeting in Tencent Meeting (keeping its meeting code) and records
+// the new time in the database
+pub async fn update_meeting<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(meeting_id): Path<String>,
+    ExtractJson(request): ExtractJson<UpdateMeetingRequest>,
+) -> Result<Json<UpdateMeetingResponse>, StatusCode> {
+    info!("Received request to update meeting: {}", meeting_id);
+
+    // Work out the new schedule before touching the meeting
+    let new_schedule = match (&request.start_time, &request.end_time) {
+        (Some(start), Some(end)) => {
+            let parse = |ts: &str| {
+                ts.parse::<i64>()
+                    .ok()
+                    .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            };
+            match (parse(start), parse(end)) {
+                (Some(start_time), Some(end_time)) if start_time < end_time => {
+                    Some((start_time, end_time))
+                }
+                _ => {
+                    error!("Invalid meeting time range: {} - {}", start, end);
+                    return Err(StatusCode::BAD_REQUEST);
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Call the Tencent Meeting API to update the meeting
+    let response = match state.client.update_meeting(&meeting_id, &request).await {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Failed to update meeting: {}", e);
+            return Err(e.status_code());
+        }
+    };
+
+    info!("Successfully updated meeting {}", meeting_id);
+
+    // Keep the database in step with Tencent
+    let db_result = match new_schedule {
+        Some((start_time, end_time)) => {
+            // Moved outside the form's slots, so the new range is the only
+            // label, in the zone the meeting was booked in
+            let time_zone = match state.database.find_meetings_by_id(&meeting_id).await {
+                Ok(records) => records
+                    .first()
+                    .map(MeetingRecord::zone)
+                    .unwrap_or(state.time_zones.default_zone()),
+                Err(e) => {
+                    warn!("Failed to look up the time zone of {}: {}", meeting_id, e);
+                    state.time_zones.default_zone()
+                }
+            };
+            let scheduled_label = format_scheduled_label(start_time, end_time, time_zone);
+            state
+                .database
+                .reschedule_meeting(
+                    &meeting_id,
+                    start_time,
+                    end_time,
+                    &scheduled_label,
+                    std::slice::from_ref(&scheduled_label),
+                    request.subject.as_deref(),
+                )
+                .await
+        }
+        None => match &request.subject {
+            Some(subject) => {
+                state
+                    .database
+                    .update_meeting_subject(&meeting_id, subject)
+                    .await
+            }
+            None => Ok(0),
+        },
+    };
+
+    if let Err(e) = db_result {
+        // The meeting itself was updated, so report success but log the mismatch
+        error!(
+            "Meeting {} was updated but the database record could not be: {}",
+            meeting_id, e
+        );
+    }
+
+    Ok(Json(response))
+}
+
 // Cancel an existing meeting endpoint
-#[axum::debug_handler]
-pub async fn cancel_meeting(
-    State(state): State<Arc<AppState>>,
+pub async fn cancel_meeting<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
     Path(meeting_id): Path<String>,
     ExtractJson(request): ExtractJson<CancelMeetingRequest>,
 ) -> Result<StatusCode, StatusCode> {
@@ -108,15 +260,14 @@ pub async fn cancel_meeting(
         }
         Err(e) => {
             error!("Failed to cancel meeting: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.status_code())
         }
     }
 }
 
 // Book meeting rooms for a meeting
-#[axum::debug_handler]
-pub async fn book_rooms(
-    State(state): State<Arc<AppState>>,
+pub async fn book_rooms<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
     Path(meeting_id): Path<String>,
     ExtractJson(request): ExtractJson<BookRoomsRequest>,
 ) -> Result<StatusCode, StatusCode> {
@@ -130,15 +281,14 @@ pub async fn book_rooms(
         }
         Err(e) => {
             error!("Failed to book rooms: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.status_code())
         }
     }
 }
 
 // Release meeting rooms for a meeting
-#[axum::debug_handler]
-pub async fn release_rooms(
-    State(state): State<Arc<AppState>>,
+pub async fn release_rooms<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
     Path(meeting_id): Path<String>,
     ExtractJson(request): ExtractJson<ReleaseRoomsRequest>,
 ) -> Result<StatusCode, StatusCode> {
@@ -155,19 +305,16 @@ pub async fn release_rooms(
         }
         Err(e) => {
             error!("Failed to release rooms: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.status_code())
         }
     }
 }
 
-// Form webhook endpoint for meeting creation
-#[axum::debug_handler]
-pub async fn handle_form_submission(
-    State(state): State<Arc<AppState>>,
-    Query(params): Query<WebhookQueryParams>,
-    ExtractJson(form_submission): ExtractJson<FormSubmission>,
-) -> Result<Json<WebhookResponse>, StatusCode> {
-    // Validate webhook auth token
+// Check the `auth` query parameter against the webhook auth token, if one is set
+pub fn check_webhook_auth<C>(
+    state: &AppState<C>,
+    params: &WebhookQueryParams,
+) -> Result<(), StatusCode> {
     if let Some(expected_token) = &state.webhook_auth_token {
         match &params.auth {
             Some(token) if token == expected_token => {
@@ -183,22 +330,246 @@ pub async fn handle_form_submission(
             }
         }
     }
-    // Get the appropriate room ID for this form
-    let form_specific_room_id = get_room_id(&state, &form_submission);
-    // Check if this is a cancellation request
-    if form_submission
-        .entry
-        .reservation_status_fsf_field
-        .to_lowercase()
-        .contains("取消")
+    Ok(())
+}
+
+// Form webhook endpoint for meeting creation
+pub async fn handle_form_submission<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Query(params): Query<WebhookQueryParams>,
+    ExtractJson(form_submission): ExtractJson<FormSubmission>,
+) -> Result<Response, StatusCode> {
+    // Validate webhook auth token
+    check_webhook_auth(&state, &params)?;
+
+    // Reject a submission with a slot that can't be parsed, saying which and why
+    if let Some(rejection) =
+        find_invalid_time_slot(&state.status_mapping, &state.time_zones, &form_submission)
+    {
+        return Ok((StatusCode::BAD_REQUEST, Json(rejection)).into_response());
+    }
+
+    // Reject a submission with slots on a holiday or in a blackout window
+    if let Some(rejection) = find_blackouts(&state, &form_submission) {
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(rejection)).into_response());
+    }
+
+    // Reject a submission with slots that break their room's booking policy
+    if let Some(rejection) = find_policy_violations(&state, &form_submission) {
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(rejection)).into_response());
+    }
+
+    // Asynchronous mode: persist a job and let the worker process it
+    if let Some(job_queue) = &state.job_queue {
+        let job = job_queue.enqueue(form_submission).map_err(|e| {
+            error!("Failed to queue form submission: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(JobAccepted {
+                job_id: job.id,
+                status: job.status,
+            }),
+        )
+            .into_response());
+    }
+
+    process_form_submission(&state, &form_submission)
+        .await
+        .map(|response| Json(response).into_response())
+}
+
+// Check a submission before processing it or accepting it into the job queue
+//
+// Only reservations carry time slots; every slot must parse. Returns the
+// response for the first slot that doesn't.
+fn find_invalid_time_slot(
+    status_mapping: &StatusMapping,
+    time_zones: &TimeZoneConfig,
+    form_submission: &FormSubmission,
+) -> Option<InvalidTimeSlotResponse> {
+    if status_mapping
+        .map(&form_submission.entry.reservation_status_fsf_field)
+        .is_cancellation()
+    {
+        return None;
+    }
+
+    for reservation in &form_submission.entry.field_1 {
+        let time_zone = time_zones.zone_for(&form_submission.form_name, &reservation.item_name);
+        if let Err(e) = parse_time_slot(reservation, time_zone) {
+            error!("Rejecting form submission with invalid time slot: {}", e);
+            return Some(InvalidTimeSlotResponse {
+                success: false,
+                message: format!(
+                    "Time slot {} ({}, '{}') is invalid: {}",
+                    reservation.number, reservation.item_name, reservation.scheduled_label, e
+                ),
+                number: reservation.number,
+                item_name: reservation.item_name.clone(),
+                scheduled_label: reservation.scheduled_label.clone(),
+                error: e,
+            });
+        }
+    }
+
+    None
+}
+
+// Time slots of a reservation to check before processing it
+//
+// Returns nothing for cancellations. Every slot parses, find_invalid_time_slot
+// has checked.
+fn slots_to_check<C>(state: &AppState<C>, form_submission: &FormSubmission) -> Vec<TimeSlot> {
+    if state
+        .status_mapping
+        .map(&form_submission.entry.reservation_status_fsf_field)
+        .is_cancellation()
     {
+        return Vec::new();
+    }
+
+    form_submission
+        .entry
+        .field_1
+        .iter()
+        .filter_map(|reservation| {
+            let time_zone = state
+                .time_zones
+                .zone_for(&form_submission.form_name, &reservation.item_name);
+            parse_time_slot(reservation, time_zone).ok()
+        })
+        .collect()
+}
+
+// Check a submission's slots against the holidays and blackout windows of
+// their rooms and locations
+//
+// Returns the response listing every slot that can't be booked, if any.
+// Cancellations are not checked.
+fn find_blackouts<C>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+) -> Option<BlackoutResponse> {
+    let time_slots = slots_to_check(state, form_submission);
+    let slots = state
+        .blackout_calendars
+        .find_blackouts(&form_submission.form_name, &time_slots);
+    if slots.is_empty() {
+        return None;
+    }
+
+    let details: Vec<String> = slots
+        .iter()
+        .map(|slot| {
+            let reasons: Vec<String> = slot.unavailable.iter().map(|u| u.to_string()).collect();
+            format!(
+                "{} ({}): {}",
+                slot.scheduled_label,
+                slot.item_name,
+                reasons.join(", ")
+            )
+        })
+        .collect();
+    error!(
+        "Rejecting form submission with {} time slots in blackout periods: {}",
+        slots.len(),
+        details.join("; ")
+    );
+
+    Some(BlackoutResponse {
+        success: false,
+        message: format!(
+            "{} time slots fall on a holiday or in a blackout window: {}",
+            slots.len(),
+            details.join("; ")
+        ),
+        slots,
+    })
+}
+
+// Check a submission's slots against the booking policies of their rooms
+//
+// Returns the response listing every slot that breaks a rule, if any does.
+// Cancellations are not checked.
+fn find_policy_violations<C>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+) -> Option<BookingPolicyResponse> {
+    let time_slots = slots_to_check(state, form_submission);
+    let slots = check_booking_policies(
+        &state.booking_policies,
+        &state.blackout_calendars,
+        &form_submission.form_name,
+        &time_slots,
+        chrono::Utc::now(),
+    );
+    if slots.is_empty() {
+        return None;
+    }
+
+    let details: Vec<String> = slots
+        .iter()
+        .map(|slot| {
+            let violations: Vec<String> = slot.violations.iter().map(|v| v.to_string()).collect();
+            format!(
+                "{} ({}): {}",
+                slot.scheduled_label,
+                slot.item_name,
+                violations.join(", ")
+            )
+        })
+        .collect();
+    error!(
+        "Rejecting form submission with {} time slots that break the booking policy: {}",
+        slots.len(),
+        details.join("; ")
+    );
+
+    Some(BookingPolicyResponse {
+        success: false,
+        message: format!(
+            "{} time slots break the booking policy: {}",
+            slots.len(),
+            details.join("; ")
+        ),
+        slots,
+    })
+}
+
+/// Process a form submission: create, update or cancel its meetings
+///
+/// Runs inline for the webhook, or from the job worker in asynchronous mode.
+pub async fn process_form_submission<C: MeetingApi>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+) -> Result<WebhookResponse, StatusCode> {
+    // Process one submission per token at a time, so a retried webhook waits
+    // for the original and then finds its meetings instead of creating more
+    let _submission_guard = state
+        .submission_locks
+        .acquire(&form_submission.entry.token)
+        .await;
+
+    let form_status = state
+        .status_mapping
+        .map(&form_submission.entry.reservation_status_fsf_field);
+
+    // Check if this is a cancellation request
+    if form_status.is_cancellation() {
         info!(
             "Form submission with token {} is a cancellation request",
             form_submission.entry.token
         );
 
         // Look up meeting IDs and room IDs in database
-        match state.database.cancel_meeting(&form_submission.entry.token) {
+        match state
+            .database
+            .request_cancellation(&form_submission.entry.token)
+            .await
+        {
             Ok(cancelled_meetings) if !cancelled_meetings.is_empty() => {
                 info!(
                     "Found {} meetings to cancel with token: {}",
@@ -216,12 +587,17 @@ pub async fn handle_form_submission(
                         .iter()
                         .map(|(id, _)| id.clone())
                         .collect();
+                    for meeting_id in &meeting_ids {
+                        if let Err(e) = state.database.finalize_cancellation(meeting_id).await {
+                            error!("Failed to mark meeting {} cancelled: {}", meeting_id, e);
+                        }
+                    }
                     info!(
                         "Simulation mode: {} meetings marked as cancelled in database: {:?}",
                         cancelled_meetings.len(),
                         meeting_ids
                     );
-                    return Ok(Json(WebhookResponse {
+                    return Ok(WebhookResponse {
                         success: true,
                         message: format!(
                             "Simulation: {} meetings cancelled successfully",
@@ -229,7 +605,7 @@ pub async fn handle_form_submission(
                         ),
                         meetings_count: 0,
                         meetings: Vec::new(),
-                    }));
+                    });
                 }
 
                 // Track cancellation results
@@ -238,59 +614,22 @@ pub async fn handle_form_submission(
 
                 // Process each meeting that needs to be cancelled
                 for (meeting_id, room_id) in &cancelled_meetings {
-                    // Step 1: Release the meeting room
-                    let release_request = ReleaseRoomsRequest {
-                        operator_id: state.client.get_operator_id().to_string(),
-                        operator_id_type: 1,
-                        meeting_room_id_list: vec![room_id.clone()],
-                    };
-
-                    // Call the Tencent Meeting API to release the room
-                    match state
-                        .client
-                        .release_rooms(meeting_id, &release_request)
-                        .await
-                    {
-                        Ok(_) => {
-                            info!(
-                                "Successfully released room {} for meeting {}",
-                                room_id, meeting_id
-                            );
-
-                            // Step 2: Cancel the meeting
-                            let cancel_request = CancelMeetingRequest {
-                                userid: state.client.get_operator_id().to_string(),
-                                instanceid: 32,
-                                reason_code: 1, // Cancellation reason code
-                                meeting_type: None,
-                                sub_meeting_id: None,
-                                reason_detail: Some("Form submission cancelled".to_string()),
-                            };
-
-                            // Call the Tencent Meeting API to cancel the meeting
-                            match state
-                                .client
-                                .cancel_meeting(meeting_id, &cancel_request)
-                                .await
-                            {
-                                Ok(_) => {
-                                    info!("Successfully cancelled meeting with ID: {}", meeting_id);
-                                    successful_cancellations += 1;
-                                }
-                                Err(err) => {
-                                    error!("Failed to cancel meeting {}: {}", meeting_id, err);
-                                    failed_cancellations += 1;
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            error!(
-                                "Failed to release room {} for meeting {}: {}",
-                                room_id, meeting_id, err
-                            );
-                            failed_cancellations += 1;
+                    // Cancel as the operator that created the meeting
+                    let operator_id = match state.database.find_meetings_by_id(meeting_id).await {
+                        Ok(records) => records
+                            .first()
+                            .map(|record| record_operator_id(state, record)),
+                        Err(e) => {
+                            error!("Failed to look up meeting {}: {}", meeting_id, e);
+                            None
                         }
                     }
+                    .unwrap_or_else(|| state.client.get_operator_id().to_string());
+
+                    match complete_cancellation(state, meeting_id, room_id, &operator_id).await {
+                        Ok(_) => successful_cancellations += 1,
+                        Err(_) => failed_cancellations += 1,
+                    }
                 }
 
                 // Return summary of all cancellations
@@ -299,7 +638,7 @@ pub async fn handle_form_submission(
                         "Successfully cancelled all {} meetings",
                         successful_cancellations
                     );
-                    return Ok(Json(WebhookResponse {
+                    return Ok(WebhookResponse {
                         success: true,
                         message: format!(
                             "Successfully cancelled {} meetings",
@@ -307,13 +646,13 @@ pub async fn handle_form_submission(
                         ),
                         meetings_count: 0,
                         meetings: Vec::new(),
-                    }));
+                    });
                 } else {
                     warn!(
                         "Cancelled {} meetings, but {} failed",
                         successful_cancellations, failed_cancellations
                     );
-                    return Ok(Json(WebhookResponse {
+                    return Ok(WebhookResponse {
                         success: successful_cancellations > 0,
                         message: format!(
                             "Cancelled {} meetings, but {} failed",
@@ -321,7 +660,7 @@ pub async fn handle_form_submission(
                         ),
                         meetings_count: 0,
                         meetings: Vec::new(),
-                    }));
+                    });
                 }
             }
             Ok(_) => {
@@ -329,7 +668,7 @@ pub async fn handle_form_submission(
                     "No active meetings found with token: {}",
                     form_submission.entry.token
                 );
-                return Ok(Json(WebhookResponse {
+                return Ok(WebhookResponse {
                     success: false,
                     message: format!(
                         "No active meetings found with token: {}",
@@ -337,7 +676,7 @@ pub async fn handle_form_submission(
                     ),
                     meetings_count: 0,
                     meetings: Vec::new(),
-                }));
+                });
             }
             Err(e) => {
                 error!("Failed to lookup meetings for cancellation: {}", e);
@@ -346,6 +685,24 @@ pub async fn handle_form_submission(
         }
     }
 
+    // Submissions that are not confirmed (or were rejected) by the form
+    // service don't create meetings yet
+    if matches!(
+        form_status,
+        ReservationStatus::Pending | ReservationStatus::Failed
+    ) {
+        info!(
+            "Form submission with token {} has status {}, no meetings created",
+            form_submission.entry.token, form_status
+        );
+        return Ok(WebhookResponse {
+            success: true,
+            message: format!("Submission status is {}, no meetings created", form_status),
+            meetings_count: 0,
+            meetings: Vec::new(),
+        });
+    }
+
     // This is a reservation request, not a cancellation
     info!("Processing form submission for new meeting creation");
 
@@ -363,8 +720,11 @@ pub async fn handle_form_submission(
             reservation.scheduled_label
         );
 
-        // Parse the time slot
-        match parse_time_slot(reservation) {
+        // Parse the time slot in its room's zone
+        let time_zone = state
+            .time_zones
+            .zone_for(&form_submission.form_name, &reservation.item_name);
+        match parse_time_slot(reservation, time_zone) {
             Ok(slot) => time_slots.push(slot),
             Err(e) => {
                 error!("Failed to parse time slot from reservation: {}", e);
@@ -378,12 +738,120 @@ pub async fn handle_form_submission(
         time_slots.len()
     );
 
+    // Hold the slots' rooms until the meetings are stored, so a submission
+    // from another entry can't pass the conflict check in the meantime.
+    // Rooms are locked in name order, so two submissions can't deadlock.
+    let rooms: BTreeSet<&str> = time_slots
+        .iter()
+        .map(|slot| slot.item_name.as_str())
+        .collect();
+    let mut _room_guards = Vec::with_capacity(rooms.len());
+    for room in rooms {
+        _room_guards.push(state.room_locks.acquire(room).await);
+    }
+
+    // Other entries' reservations holding a slot's room at the same time
+    let conflicts = find_room_conflicts(state, form_submission, &time_slots).await?;
+    let mut skipped_results = Vec::new();
+    if !conflicts.is_empty() {
+        warn!(
+            "{} time slots of token {} overlap other reservations in the same room ({:?} policy)",
+            conflicts.len(),
+            form_submission.entry.token,
+            state.room_conflict_policy
+        );
+
+        match state.room_conflict_policy {
+            RoomConflictPolicy::RejectSubmission => {
+                let meeting_results = conflicting_slot_results(&time_slots, &conflicts);
+                return Ok(WebhookResponse {
+                    success: false,
+                    message: format!(
+                        "Submission rejected: {} time slots conflict with existing reservations",
+                        meeting_results.len()
+                    ),
+                    meetings_count: meeting_results.len(),
+                    meetings: meeting_results,
+                });
+            }
+            RoomConflictPolicy::SkipSlots => {
+                skipped_results = conflicting_slot_results(&time_slots, &conflicts);
+                time_slots.retain(|slot| slot_conflicts(slot, &conflicts).is_empty());
+            }
+            RoomConflictPolicy::Allow => {}
+        }
+    }
+
+    let mut response = create_or_reconcile_meetings(state, form_submission, &time_slots).await?;
+
+    // Flag slots booked despite a conflict, and report the skipped ones
+    for result in &mut response.meetings {
+        if result.action != MeetingAction::Cancelled {
+            result.conflicts = conflicts
+                .iter()
+                .filter(|conflict| {
+                    conflict.room_name == result.room_name
+                        && result.time_slots.contains(&conflict.scheduled_label)
+                })
+                .cloned()
+                .collect();
+        }
+    }
+    if !skipped_results.is_empty() {
+        response.success = false;
+        response.message = format!(
+            "{}; {} time slots skipped because their room is already booked",
+            response.message,
+            skipped_results.len()
+        );
+        response.meetings.extend(skipped_results);
+        response.meetings_count = response.meetings.len();
+    }
+
+    Ok(response)
+}
+
+// Create the meetings for a submission's slots, or reconcile them with the
+// token's meetings if the submission edits an earlier one
+async fn create_or_reconcile_meetings<C: MeetingApi>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+    time_slots: &[TimeSlot],
+) -> Result<WebhookResponse, StatusCode> {
+    // A token that already has active meetings is an edit of an earlier submission
+    let existing_meetings: Vec<MeetingRecord> = match state
+        .database
+        .find_all_meetings_by_token(&form_submission.entry.token)
+        .await
+    {
+        Ok(records) => records
+            .into_iter()
+            .filter(|record| record.status.is_active())
+            .collect(),
+        Err(e) => {
+            error!("Failed to look up existing meetings: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if !existing_meetings.is_empty() {
+        info!(
+            "Token {} already has {} active meetings, reconciling the edit",
+            form_submission.entry.token,
+            existing_meetings.len()
+        );
+        return Ok(
+            reconcile_form_edit(state, form_submission, existing_meetings, time_slots).await,
+        );
+    }
+
     // Try to find mergeable groups
-    let mergeable_groups = find_mergeable_groups(&time_slots);
+    let mergeable_groups = find_mergeable_groups(time_slots);
 
     // Results storage
     let mut meeting_results = Vec::new();
     let mut all_successful = true;
+    let mut failures: Vec<String> = Vec::new();
 
     // If there's only one group and it includes all slots, we can fully merge
     if mergeable_groups.len() == 1 && mergeable_groups[0].len() == time_slots.len() {
@@ -409,23 +877,32 @@ pub async fn handle_form_submission(
                 room_name: time_slots[0].item_name.clone(),
                 time_slots: time_slot_labels,
                 success: true,
+                action: MeetingAction::Created,
+                room_booking: RoomBooking::NotAttempted,
+                error: None,
+                join_url: None,
+                conflicts: Vec::new(),
             };
 
             // Store directly in database with merged time slot info
-            let room_id = get_room_id(&state, &form_submission);
+            let room_id = get_room_id(state, form_submission);
             // Get operator information
             let (operator_name, operator_id) =
-                get_operator_info(&state.client, &form_submission, &state.user_field_name);
-
-            if let Err(e) = state.database.store_merged_meeting(
-                &form_submission,
-                "simulation-merged-meeting",
-                &time_slots[0].item_name,
-                &room_id,
-                &time_slots,
-                &operator_name,
-                &operator_id,
-            ) {
+                get_operator_info(&state.client, form_submission, &state.user_field_name);
+
+            if let Err(e) = state
+                .database
+                .store_merged_meeting(
+                    form_submission,
+                    "simulation-merged-meeting",
+                    &time_slots[0].item_name,
+                    &room_id,
+                    time_slots,
+                    &operator_name,
+                    &operator_id,
+                )
+                .await
+            {
                 error!("Failed to store simulated meeting record: {}", e);
             }
 
@@ -435,105 +912,56 @@ pub async fn handle_form_submission(
             match create_merged_meeting(
                 &state.client,
                 &state.dept_field_name,
-                &form_submission,
-                &time_slots,
+                form_submission,
+                time_slots,
                 &state.user_field_name,
             )
             .await
             {
-                Ok(result) => {
-                    all_successful = all_successful && result.success;
-
+                Ok(mut result) => {
                     // Check if we're in simulation mode (skip meeting creation)
                     if state.skip_meeting_creation {
                         // In simulation mode, store directly in database without creating a meeting
                         info!("Simulation mode: Storing form submission in database without creating a meeting");
-                        let room_id = get_room_id(&state, &form_submission);
+                        let room_id = get_room_id(state, form_submission);
                         // Get operator information
                         let (operator_name, operator_id) = get_operator_info(
                             &state.client,
-                            &form_submission,
+                            form_submission,
                             &state.user_field_name,
                         );
 
-                        if let Err(e) = state.database.store_meeting(
-                            &form_submission,
-                            "simulation-meeting-id",
-                            &result.room_name,
-                            &room_id,
-                            &operator_name,
-                            &operator_id,
-                        ) {
+                        if let Err(e) = state
+                            .database
+                            .store_meeting(
+                                form_submission,
+                                "simulation-meeting-id",
+                                &result.room_name,
+                                &room_id,
+                                &operator_name,
+                                &operator_id,
+                            )
+                            .await
+                        {
                             error!("Failed to store simulated meeting record: {}", e);
                         }
                     }
-                    // Store in database if we have a meeting ID
-                    else if let Some(meeting_id) = &result.meeting_id {
-                        // Check if we should book rooms
-                        if !state.skip_room_booking {
-                            // Get the appropriate room ID based on the form name
-                            let room_id = get_room_id_for_form(
-                                &form_submission.form_name,
-                                &state.xa_room_id,
-                                &state.cd_room_id,
-                            );
-
-                            // Book meeting room
-                            let book_request = BookRoomsRequest {
-                                operator_id: state.client.get_operator_id().to_string(),
-                                operator_id_type: 1,
-                                meeting_room_id_list: vec![room_id.clone()],
-                                subject_visible: Some(true),
-                            };
-
-                            match state.client.book_rooms(meeting_id, &book_request).await {
-                                Ok(_) => {
-                                    info!(
-                                        "Successfully booked room {} for meeting {}",
-                                        form_specific_room_id, meeting_id
-                                    );
-                                }
-                                Err(err) => {
-                                    error!("Failed to book room for meeting: {}", err);
-                                    // Continue with other operations, don't fail completely
-                                }
-                            }
-                        } else {
-                            info!(
-                                "Room booking disabled: Skipping room booking for meeting {}",
-                                meeting_id
-                            );
-                        }
-
-                        // Store meeting info in database with room ID (whether or not room was booked)
-                        let room_id = get_room_id(&state, &form_submission);
-                        // Get operator information
-                        let (operator_name, operator_id) = get_operator_info(
-                            &state.client,
-                            &form_submission,
-                            &state.user_field_name,
-                        );
-
-                        if let Err(e) = state.database.store_merged_meeting(
-                            &form_submission,
-                            meeting_id,
-                            &result.room_name,
-                            &room_id,
-                            &time_slots,
-                            &operator_name,
-                            &operator_id,
-                        ) {
-                            error!("Failed to store meeting record: {}", e);
-                            // Continue processing even if database storage fails
-                        }
+                    // Book the room and store the meeting if we have a meeting ID
+                    else {
+                        book_and_store_meeting(state, form_submission, &mut result, time_slots)
+                            .await;
                     }
 
+                    all_successful = all_successful && result.success;
+                    if let Some(error) = &result.error {
+                        failures.push(error.clone());
+                    }
                     meeting_results.push(result);
                 }
                 Err(e) => {
-                    error!("Failed to create merged meeting: {:?}", e);
+                    error!("Failed to create merged meeting: {}", e);
                     // No need to set all_successful since we're returning immediately
-                    return Err(e);
+                    return Err(e.status_code());
                 }
             }
         }
@@ -570,114 +998,54 @@ pub async fn handle_form_submission(
                             room_name: group[0].item_name.clone(),
                             time_slots: time_slot_labels,
                             success: true,
+                            action: MeetingAction::Created,
+                            room_booking: RoomBooking::NotAttempted,
+                            error: None,
+                            join_url: None,
+                            conflicts: Vec::new(),
                         };
 
                         // Store directly in database with merged time slot info
-                        let room_id = get_room_id(&state, &form_submission);
+                        let room_id = get_room_id(state, form_submission);
                         // Get operator information
                         let (operator_name, operator_id) = get_operator_info(
                             &state.client,
-                            &form_submission,
+                            form_submission,
                             &state.user_field_name,
                         );
 
-                        if let Err(e) = state.database.store_merged_meeting(
-                            &form_submission,
-                            &format!("simulation-merged-meeting-{}", i),
-                            &group[0].item_name,
-                            &room_id,
-                            group,
-                            &operator_name,
-                            &operator_id,
-                        ) {
+                        if let Err(e) = state
+                            .database
+                            .store_merged_meeting(
+                                form_submission,
+                                &format!("simulation-merged-meeting-{}", i),
+                                &group[0].item_name,
+                                &room_id,
+                                group,
+                                &operator_name,
+                                &operator_id,
+                            )
+                            .await
+                        {
                             error!("Failed to store simulated merged meeting record: {}", e);
                         }
 
                         meeting_results.push(result);
                     } else {
-                        // Normal flow - create the merged meeting
-                        match create_merged_meeting(
-                            &state.client,
-                            &state.dept_field_name,
-                            &form_submission,
-                            group,
-                            &state.user_field_name,
-                        )
-                        .await
-                        {
+                        // Normal flow - create the merged meeting, book its room and store it
+                        match create_meeting_for_group(state, form_submission, group).await {
                             Ok(result) => {
                                 all_successful = all_successful && result.success;
-
-                                // Store in database if we have a meeting ID
-                                if let Some(meeting_id) = &result.meeting_id {
-                                    // Only book rooms if not skipped
-                                    if !state.skip_room_booking {
-                                        // Step 2: Book the meeting room
-                                        // Get the appropriate room ID
-
-                                        let book_request = BookRoomsRequest {
-                                            operator_id: state.client.get_operator_id().to_string(),
-                                            operator_id_type: 1,
-                                            meeting_room_id_list: vec![
-                                                form_specific_room_id.clone()
-                                            ],
-                                            subject_visible: Some(true),
-                                        };
-
-                                        match state
-                                            .client
-                                            .book_rooms(meeting_id, &book_request)
-                                            .await
-                                        {
-                                            Ok(_) => {
-                                                info!(
-                                                    "Successfully booked room {} for meeting {}",
-                                                    form_specific_room_id, meeting_id
-                                                );
-                                            }
-                                            Err(err) => {
-                                                error!("Failed to book room for meeting: {}", err);
-                                                // Continue with other operations, don't fail completely
-                                            }
-                                        }
-                                    } else {
-                                        info!("Room booking disabled: Skipping room booking for meeting {}", meeting_id);
-                                    }
-
-                                    // Always store meeting in database with merged time slot info
-                                    // Get the appropriate room ID
-                                    let room_id = get_room_id(&state, &form_submission);
-
-                                    // Get operator information
-                                    let (operator_name, operator_id) = get_operator_info(
-                                        &state.client,
-                                        &form_submission,
-                                        &state.user_field_name,
-                                    );
-
-                                    if let Err(e) = state.database.store_merged_meeting(
-                                        &form_submission,
-                                        meeting_id,
-                                        &result.room_name,
-                                        &room_id,
-                                        group,
-                                        &operator_name,
-                                        &operator_id,
-                                    ) {
-                                        error!("Failed to store meeting record: {}", e);
-                                        // Continue processing even if database storage fails
-                                    }
+                                if let Some(error) = &result.error {
+                                    failures.push(format!("group {}: {}", i + 1, error));
                                 }
-
                                 meeting_results.push(result);
                             }
                             Err(e) => {
-                                error!(
-                                    "Failed to create merged meeting in group {}: {:?}",
-                                    i + 1,
-                                    e
-                                );
+                                error!("Failed to create merged meeting in group {}: {}", i + 1, e);
+                                failures.push(format!("group {}: {}", i + 1, e));
                                 all_successful = false;
+                                meeting_results.push(failed_group_result(group, true, &e));
                                 // Continue processing other groups even if one fails
                             }
                         }
@@ -699,113 +1067,54 @@ pub async fn handle_form_submission(
                             room_name: group[0].item_name.clone(),
                             time_slots: vec![group[0].scheduled_label.clone()],
                             success: true,
+                            action: MeetingAction::Created,
+                            room_booking: RoomBooking::NotAttempted,
+                            error: None,
+                            join_url: None,
+                            conflicts: Vec::new(),
                         };
 
                         // Store directly in database with specific time slot
-                        let room_id = get_room_id(&state, &form_submission);
+                        let room_id = get_room_id(state, form_submission);
                         // Get operator information
                         let (operator_name, operator_id) = get_operator_info(
                             &state.client,
-                            &form_submission,
+                            form_submission,
                             &state.user_field_name,
                         );
 
-                        if let Err(e) = state.database.store_meeting_with_time_slot(
-                            &form_submission,
-                            &format!("simulation-meeting-id-{}", i),
-                            &group[0].item_name,
-                            &room_id,
-                            &group[0],
-                            &operator_name,
-                            &operator_id,
-                        ) {
+                        if let Err(e) = state
+                            .database
+                            .store_meeting_with_time_slot(
+                                form_submission,
+                                &format!("simulation-meeting-id-{}", i),
+                                &group[0].item_name,
+                                &room_id,
+                                &group[0],
+                                &operator_name,
+                                &operator_id,
+                            )
+                            .await
+                        {
                             error!("Failed to store simulated meeting record: {}", e);
                         }
 
                         meeting_results.push(result);
                     } else {
-                        // Normal flow - create the meeting
-                        match create_meeting_with_time_slot(
-                            &state.client,
-                            &state.dept_field_name,
-                            &form_submission,
-                            &group[0],
-                            &state.user_field_name,
-                        )
-                        .await
-                        {
+                        // Normal flow - create the meeting, book its room and store it
+                        match create_meeting_for_group(state, form_submission, group).await {
                             Ok(result) => {
                                 all_successful = all_successful && result.success;
-
-                                // Store in database if we have a meeting ID
-                                if let Some(meeting_id) = &result.meeting_id {
-                                    // Only book rooms if not skipped
-                                    if !state.skip_room_booking {
-                                        // Step 2: Book the meeting room
-                                        // Get the appropriate room ID
-
-                                        let book_request = BookRoomsRequest {
-                                            operator_id: state.client.get_operator_id().to_string(),
-                                            operator_id_type: 1,
-                                            meeting_room_id_list: vec![
-                                                form_specific_room_id.clone()
-                                            ],
-                                            subject_visible: Some(true),
-                                        };
-
-                                        match state
-                                            .client
-                                            .book_rooms(meeting_id, &book_request)
-                                            .await
-                                        {
-                                            Ok(_) => {
-                                                info!(
-                                                    "Successfully booked room {} for meeting {}",
-                                                    form_specific_room_id, meeting_id
-                                                );
-                                            }
-                                            Err(err) => {
-                                                error!("Failed to book room for meeting: {}", err);
-                                                // Continue with other operations, don't fail completely
-                                            }
-                                        }
-                                    } else {
-                                        info!("Room booking disabled: Skipping room booking for meeting {}", meeting_id);
-                                    }
-
-                                    // Always store in database with specific time slot
-                                    let room_id = get_room_id(&state, &form_submission);
-
-                                    // Get operator information
-                                    let (operator_name, operator_id) = get_operator_info(
-                                        &state.client,
-                                        &form_submission,
-                                        &state.user_field_name,
-                                    );
-
-                                    if let Err(e) = state.database.store_meeting_with_time_slot(
-                                        &form_submission,
-                                        meeting_id,
-                                        &result.room_name,
-                                        &room_id,
-                                        &group[0],
-                                        &operator_name,
-                                        &operator_id,
-                                    ) {
-                                        error!("Failed to store meeting record: {}", e);
-                                        // Continue processing even if database storage fails
-                                    }
+                                if let Some(error) = &result.error {
+                                    failures.push(format!("group {}: {}", i + 1, error));
                                 }
-
                                 meeting_results.push(result);
                             }
                             Err(e) => {
-                                error!(
-                                    "Failed to create single meeting in group {}: {:?}",
-                                    i + 1,
-                                    e
-                                );
+                                error!("Failed to create single meeting in group {}: {}", i + 1, e);
+                                failures.push(format!("group {}: {}", i + 1, e));
                                 all_successful = false;
+                                meeting_results.push(failed_group_result(group, false, &e));
                                 // Continue processing other groups even if one fails
                             }
                         }
@@ -822,12 +1131,12 @@ pub async fn handle_form_submission(
     // Generate summary message
     let successful_count = meeting_results
         .iter()
-        .filter(|r| r.meeting_id.is_some())
+        .filter(|r| r.meeting_id.is_some() && r.room_booking != RoomBooking::RolledBack)
         .count();
 
     let merged_count = meeting_results.iter().filter(|r| r.merged).count();
 
-    let message = if merged_count > 0 {
+    let mut message = if merged_count > 0 {
         format!(
             "Created {} meetings ({} merged) from {} time slots",
             successful_count,
@@ -842,20 +1151,749 @@ pub async fn handle_form_submission(
         )
     };
 
+    // Report why any groups could not be created
+    if !failures.is_empty() {
+        message = format!(
+            "{}; {} failed: {}",
+            message,
+            failures.len(),
+            failures.join("; ")
+        );
+    }
+
     // Return complete response with all meeting results
-    Ok(Json(WebhookResponse {
+    Ok(WebhookResponse {
         success: all_successful && successful_count > 0,
         message,
         meetings_count: meeting_results.len(),
         meetings: meeting_results,
-    }))
+    })
+}
+
+// Active reservations of other entries that overlap the slots in the same room
+async fn find_room_conflicts<C>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+    time_slots: &[TimeSlot],
+) -> Result<Vec<RoomConflict>, StatusCode> {
+    let mut conflicts = Vec::new();
+    for slot in time_slots {
+        let records = state
+            .database
+            .find_overlapping_meetings(
+                &slot.item_name,
+                slot.start_time,
+                slot.end_time,
+                slot.time_zone,
+            )
+            .await
+            .map_err(|e| {
+                error!(
+                    "Failed to look up reservations for {}: {}",
+                    slot.item_name, e
+                );
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+        // The token's own meetings are edits, not conflicts
+        conflicts.extend(
+            records
+                .into_iter()
+                .filter(|record| record.entry_token != form_submission.entry.token)
+                .map(|record| RoomConflict {
+                    room_name: slot.item_name.clone(),
+                    scheduled_label: slot.scheduled_label.clone(),
+                    meeting_id: record.meeting_id,
+                    reserved_label: record.scheduled_label,
+                    operator_name: record.operator_name,
+                }),
+        );
+    }
+    Ok(conflicts)
+}
+
+// Conflicts of one slot
+fn slot_conflicts<'a>(slot: &TimeSlot, conflicts: &'a [RoomConflict]) -> Vec<&'a RoomConflict> {
+    conflicts
+        .iter()
+        .filter(|conflict| {
+            conflict.room_name == slot.item_name && conflict.scheduled_label == slot.scheduled_label
+        })
+        .collect()
+}
+
+// Results for the slots that were not booked because of a conflict
+fn conflicting_slot_results(
+    time_slots: &[TimeSlot],
+    conflicts: &[RoomConflict],
+) -> Vec<MeetingResult> {
+    time_slots
+        .iter()
+        .filter_map(|slot| {
+            let slot_conflicts = slot_conflicts(slot, conflicts);
+            let reserved_labels: Vec<&str> = slot_conflicts
+                .iter()
+                .map(|conflict| conflict.reserved_label.as_str())
+                .collect();
+            (!slot_conflicts.is_empty()).then(|| MeetingResult {
+                meeting_id: None,
+                merged: false,
+                room_name: slot.item_name.clone(),
+                time_slots: vec![slot.scheduled_label.clone()],
+                success: false,
+                action: MeetingAction::Rejected,
+                room_booking: RoomBooking::NotAttempted,
+                error: Some(format!(
+                    "{} is already booked at {}",
+                    slot.item_name,
+                    reserved_labels.join(", ")
+                )),
+                join_url: None,
+                conflicts: slot_conflicts.into_iter().cloned().collect(),
+            })
+        })
+        .collect()
 }
 
 // Helper function to get the room ID to use for a form submission
-fn get_room_id(state: &AppState, form_submission: &FormSubmission) -> String {
+fn get_room_id<C>(state: &AppState<C>, form_submission: &FormSubmission) -> String {
     get_room_id_for_form(
         &form_submission.form_name,
         &state.xa_room_id,
         &state.cd_room_id,
     )
 }
+
+// Result for a group whose meeting could not be created
+fn failed_group_result(group: &[TimeSlot], merged: bool, err: &TencentApiError) -> MeetingResult {
+    MeetingResult {
+        meeting_id: None,
+        merged,
+        room_name: group[0].item_name.clone(),
+        time_slots: group
+            .iter()
+            .map(|slot| slot.scheduled_label.clone())
+            .collect(),
+        success: false,
+        action: MeetingAction::Created,
+        room_booking: RoomBooking::NotAttempted,
+        error: Some(err.to_string()),
+        join_url: None,
+        conflicts: Vec::new(),
+    }
+}
+
+// Create the meeting for one mergeable group, book the form's room and store it
+async fn create_meeting_for_group<C: MeetingApi>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+    group: &[TimeSlot],
+) -> Result<MeetingResult, TencentApiError> {
+    let mut result = if group.len() > 1 {
+        create_merged_meeting(
+            &state.client,
+            &state.dept_field_name,
+            form_submission,
+            group,
+            &state.user_field_name,
+        )
+        .await?
+    } else {
+        let time_slot = group
+            .first()
+            .ok_or_else(|| TencentApiError::InvalidRequest("No time slots in group".to_string()))?;
+        create_meeting_with_time_slot(
+            &state.client,
+            &state.dept_field_name,
+            form_submission,
+            time_slot,
+            &state.user_field_name,
+        )
+        .await?
+    };
+
+    book_and_store_meeting(state, form_submission, &mut result, group).await;
+
+    Ok(result)
+}
+
+// Book the form's room for a newly created meeting and store the meeting
+//
+// If the room cannot be booked, the booking failure policy decides whether the
+// meeting is cancelled again (and not stored) or kept and stored with the
+// "room not booked" status. The outcome is recorded on `result`.
+async fn book_and_store_meeting<C: MeetingApi>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+    result: &mut MeetingResult,
+    time_slots: &[TimeSlot],
+) {
+    let Some(meeting_id) = result.meeting_id.clone() else {
+        return;
+    };
+    let room_id = get_room_id(state, form_submission);
+    let (operator_name, operator_id) =
+        get_operator_info(&state.client, form_submission, &state.user_field_name);
+
+    // Only book rooms if not skipped
+    if !state.skip_room_booking {
+        let book_request = BookRoomsRequest {
+            operator_id: state.client.get_operator_id().to_string(),
+            operator_id_type: 1,
+            meeting_room_id_list: vec![room_id.clone()],
+            subject_visible: Some(true),
+        };
+
+        match state.client.book_rooms(&meeting_id, &book_request).await {
+            Ok(_) => {
+                info!(
+                    "Successfully booked room {} for meeting {}",
+                    room_id, meeting_id
+                );
+                result.room_booking = RoomBooking::Booked;
+            }
+            Err(err) => {
+                error!(
+                    "Failed to book room {} for meeting {}: {}",
+                    room_id, meeting_id, err
+                );
+                result.success = false;
+                result.room_booking = RoomBooking::Failed;
+                result.error = Some(format!("room booking failed: {}", err));
+
+                if state.booking_failure_policy == BookingFailurePolicy::CancelMeeting {
+                    match cancel_tencent_meeting(
+                        state,
+                        &meeting_id,
+                        &operator_id,
+                        "Room booking failed",
+                    )
+                    .await
+                    {
+                        Ok(()) => {
+                            // Nothing left in Tencent Meeting, so nothing to store
+                            info!(
+                                "Rolled back meeting {} after its room booking failed",
+                                meeting_id
+                            );
+                            result.room_booking = RoomBooking::RolledBack;
+                            return;
+                        }
+                        Err(cancel_err) => {
+                            // Keep the record so the meeting can be cleaned up by hand
+                            error!("Failed to roll back meeting {}: {}", meeting_id, cancel_err);
+                            result.error = Some(format!(
+                                "room booking failed: {}; cancelling the meeting also failed: {}",
+                                err, cancel_err
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        info!(
+            "Room booking disabled: Skipping room booking for meeting {}",
+            meeting_id
+        );
+    }
+
+    let stored = state
+        .database
+        .store_created_meeting(
+            form_submission,
+            &meeting_id,
+            &result.room_name,
+            &room_id,
+            time_slots,
+            result.join_url.as_deref().unwrap_or(""),
+            &operator_name,
+            &operator_id,
+        )
+        .await;
+
+    if let Err(e) = stored {
+        error!("Failed to store meeting record: {}", e);
+        // Continue processing even if database storage fails
+        return;
+    }
+
+    // Flag meetings that exist without their room
+    if result.room_booking == RoomBooking::Failed {
+        if let Err(e) = state
+            .database
+            .set_meeting_status(&meeting_id, ReservationStatus::RoomNotBooked)
+            .await
+        {
+            error!(
+                "Failed to mark meeting {} as room not booked: {}",
+                meeting_id, e
+            );
+        }
+    }
+}
+
+// Bring the stored meetings for a re-posted token in line with the form
+//
+// Removed slots are cancelled, added slots are created, and meetings whose
+// time or subject changed are updated in place so they keep their meeting code.
+// Every action is reported in the response.
+async fn reconcile_form_edit<C: MeetingApi>(
+    state: &AppState<C>,
+    form_submission: &FormSubmission,
+    existing_meetings: Vec<MeetingRecord>,
+    time_slots: &[TimeSlot],
+) -> WebhookResponse {
+    let subject = &form_submission.entry.field_8;
+    let desired: Vec<DesiredMeeting> = find_mergeable_groups(time_slots)
+        .iter()
+        .filter_map(|group| DesiredMeeting::from_group(group))
+        .collect();
+
+    let steps = plan_reconciliation(&existing_meetings, desired, subject);
+
+    // Nothing changed: this is a repeat of a submission that was already processed
+    let already_processed = steps.iter().all(|step| match step {
+        ReconcileStep::Keep { record, .. } => record.status != ReservationStatus::RoomNotBooked,
+        _ => false,
+    });
+    if already_processed {
+        info!(
+            "Submission with token {} was already processed, returning its meetings without calling Tencent",
+            form_submission.entry.token
+        );
+    }
+
+    let mut meeting_results = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+
+    for step in steps {
+        match step {
+            ReconcileStep::Cancel { record } => {
+                // Record the request first, so the sweeper retries it if Tencent fails
+                let room_id = match state
+                    .database
+                    .request_cancellation_by_id(&record.meeting_id)
+                    .await
+                {
+                    Ok(requested) => requested
+                        .into_iter()
+                        .next()
+                        .map(|(_, room_id)| room_id)
+                        .unwrap_or_default(),
+                    Err(e) => {
+                        error!(
+                            "Failed to record cancellation request for {}: {}",
+                            record.meeting_id, e
+                        );
+                        if record.status == ReservationStatus::RoomNotBooked {
+                            String::new()
+                        } else {
+                            record.room_id.clone()
+                        }
+                    }
+                };
+
+                let cancelled = if is_simulated(state, &record) {
+                    if let Err(e) = state
+                        .database
+                        .finalize_cancellation(&record.meeting_id)
+                        .await
+                    {
+                        error!(
+                            "Failed to mark meeting {} cancelled: {}",
+                            record.meeting_id, e
+                        );
+                    }
+                    Ok(())
+                } else {
+                    let operator_id = record_operator_id(state, &record);
+                    complete_cancellation(state, &record.meeting_id, &room_id, &operator_id).await
+                };
+
+                let error = match cancelled {
+                    Ok(_) => None,
+                    Err(e) => {
+                        failures.push(format!("cancel {}: {}", record.scheduled_label, e));
+                        Some(e.to_string())
+                    }
+                };
+
+                meeting_results.push(MeetingResult {
+                    meeting_id: Some(record.meeting_id),
+                    merged: false,
+                    room_name: record.room_name,
+                    time_slots: vec![record.scheduled_label],
+                    success: error.is_none(),
+                    action: MeetingAction::Cancelled,
+                    room_booking: RoomBooking::NotAttempted,
+                    error,
+                    join_url: None,
+                    conflicts: Vec::new(),
+                });
+            }
+            ReconcileStep::Keep { record, desired } => {
+                let mut result =
+                    stored_meeting_result(state, &record, &desired, MeetingAction::Unchanged);
+
+                // A meeting kept without its room gets another booking attempt
+                if record.status == ReservationStatus::RoomNotBooked {
+                    rebook_room(state, &record, &mut result).await;
+                    if let Some(error) = &result.error {
+                        failures.push(format!("book room {}: {}", desired.scheduled_label, error));
+                    }
+                }
+
+                meeting_results.push(result);
+            }
+            ReconcileStep::UpdateSubject { record, desired } => {
+                let update_request = UpdateMeetingRequest {
+                    subject: Some(subject.clone()),
+                    ..meeting_update_request(state, &record)
+                };
+
+                let updated = if is_simulated(state, &record) {
+                    Ok(())
+                } else {
+                    state
+                        .client
+                        .update_meeting(&record.meeting_id, &update_request)
+                        .await
+                        .map(|_| ())
+                };
+
+                let error = match updated {
+                    Ok(_) => {
+                        if let Err(e) = state
+                            .database
+                            .update_meeting_subject(&record.meeting_id, subject)
+                            .await
+                        {
+                            error!(
+                                "Failed to update meeting {} subject: {}",
+                                record.meeting_id, e
+                            );
+                        }
+                        None
+                    }
+                    Err(e) => {
+                        error!("Failed to update meeting {}: {}", record.meeting_id, e);
+                        failures.push(format!("update {}: {}", desired.scheduled_label, e));
+                        Some(e.to_string())
+                    }
+                };
+
+                let mut result =
+                    stored_meeting_result(state, &record, &desired, MeetingAction::Updated);
+                result.success = error.is_none();
+                result.error = error;
+                meeting_results.push(result);
+            }
+            ReconcileStep::Reschedule { record, desired } => {
+                let update_request = UpdateMeetingRequest {
+                    subject: Some(subject.clone()),
+                    start_time: Some(desired.start_time.timestamp().to_string()),
+                    end_time: Some(desired.end_time.timestamp().to_string()),
+                    time_zone: Some(desired.time_zone(&record).name().to_string()),
+                    ..meeting_update_request(state, &record)
+                };
+
+                let updated = if is_simulated(state, &record) {
+                    Ok(())
+                } else {
+                    state
+                        .client
+                        .update_meeting(&record.meeting_id, &update_request)
+                        .await
+                        .map(|_| ())
+                };
+
+                let error = match updated {
+                    Ok(_) => {
+                        info!(
+                            "Rescheduled meeting {} from {} to {}",
+                            record.meeting_id, record.scheduled_label, desired.scheduled_label
+                        );
+                        if let Err(e) = state
+                            .database
+                            .reschedule_meeting(
+                                &record.meeting_id,
+                                desired.start_time,
+                                desired.end_time,
+                                &desired.scheduled_label,
+                                &desired.slot_labels(),
+                                Some(subject),
+                            )
+                            .await
+                        {
+                            error!("Failed to record new time for {}: {}", record.meeting_id, e);
+                        }
+                        None
+                    }
+                    Err(e) => {
+                        error!("Failed to reschedule meeting {}: {}", record.meeting_id, e);
+                        failures.push(format!("reschedule {}: {}", desired.scheduled_label, e));
+                        Some(e.to_string())
+                    }
+                };
+
+                let mut result =
+                    stored_meeting_result(state, &record, &desired, MeetingAction::Rescheduled);
+                result.success = error.is_none();
+                result.error = error;
+                meeting_results.push(result);
+            }
+            ReconcileStep::Create { desired } => {
+                if state.skip_meeting_creation {
+                    // Simulation mode: store without creating a meeting
+                    let meeting_id = format!(
+                        "simulation-{}-{}",
+                        form_submission.entry.token,
+                        desired.start_time.timestamp()
+                    );
+                    let room_id = get_room_id(state, form_submission);
+                    let (operator_name, operator_id) =
+                        get_operator_info(&state.client, form_submission, &state.user_field_name);
+
+                    let stored = if desired.is_merged() {
+                        state
+                            .database
+                            .store_merged_meeting(
+                                form_submission,
+                                &meeting_id,
+                                &desired.room_name,
+                                &room_id,
+                                &desired.time_slots,
+                                &operator_name,
+                                &operator_id,
+                            )
+                            .await
+                    } else {
+                        state
+                            .database
+                            .store_meeting_with_time_slot(
+                                form_submission,
+                                &meeting_id,
+                                &desired.room_name,
+                                &room_id,
+                                &desired.time_slots[0],
+                                &operator_name,
+                                &operator_id,
+                            )
+                            .await
+                    };
+
+                    if let Err(e) = stored {
+                        error!("Failed to store simulated meeting record: {}", e);
+                    }
+
+                    meeting_results.push(MeetingResult {
+                        meeting_id: Some(meeting_id),
+                        merged: desired.is_merged(),
+                        room_name: desired.room_name.clone(),
+                        time_slots: desired.slot_labels(),
+                        success: true,
+                        action: MeetingAction::Created,
+                        room_booking: RoomBooking::NotAttempted,
+                        error: None,
+                        join_url: None,
+                        conflicts: Vec::new(),
+                    });
+                    continue;
+                }
+
+                match create_meeting_for_group(state, form_submission, &desired.time_slots).await {
+                    Ok(result) => {
+                        if let Some(error) = &result.error {
+                            failures.push(format!("create {}: {}", desired.scheduled_label, error));
+                        }
+                        meeting_results.push(result);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to create meeting for {}: {}",
+                            desired.scheduled_label, e
+                        );
+                        failures.push(format!("create {}: {}", desired.scheduled_label, e));
+                        meeting_results.push(MeetingResult {
+                            meeting_id: None,
+                            merged: desired.is_merged(),
+                            room_name: desired.room_name.clone(),
+                            time_slots: desired.slot_labels(),
+                            success: false,
+                            action: MeetingAction::Created,
+                            room_booking: RoomBooking::NotAttempted,
+                            error: Some(e.to_string()),
+                            join_url: None,
+                            conflicts: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Summarise what changed
+    let count = |action: MeetingAction| {
+        meeting_results
+            .iter()
+            .filter(|r| r.action == action && r.success)
+            .count()
+    };
+    let mut message = if already_processed {
+        format!(
+            "Submission already processed: {} existing meetings",
+            meeting_results.len()
+        )
+    } else {
+        format!(
+        "Reconciled form edit: {} created, {} rescheduled, {} updated, {} cancelled, {} unchanged",
+        count(MeetingAction::Created),
+        count(MeetingAction::Rescheduled),
+        count(MeetingAction::Updated),
+        count(MeetingAction::Cancelled),
+        count(MeetingAction::Unchanged)
+        )
+    };
+
+    if !failures.is_empty() {
+        message = format!(
+            "{}; {} failed: {}",
+            message,
+            failures.len(),
+            failures.join("; ")
+        );
+    }
+
+    info!("{}", message);
+
+    WebhookResponse {
+        success: failures.is_empty(),
+        message,
+        meetings_count: meeting_results.len(),
+        meetings: meeting_results,
+    }
+}
+
+// Result for a stored meeting that a form edit keeps, with its stored room
+// booking state and join URL
+//
+// A meeting still without its room is reported as unsuccessful.
+fn stored_meeting_result<C>(
+    state: &AppState<C>,
+    record: &MeetingRecord,
+    desired: &DesiredMeeting,
+    action: MeetingAction,
+) -> MeetingResult {
+    let (room_booking, error) = if record.status == ReservationStatus::RoomNotBooked {
+        (
+            RoomBooking::Failed,
+            Some("the meeting's room is not booked".to_string()),
+        )
+    } else if state.skip_room_booking || is_simulated(state, record) {
+        (RoomBooking::NotAttempted, None)
+    } else {
+        (RoomBooking::Booked, None)
+    };
+
+    MeetingResult {
+        meeting_id: Some(record.meeting_id.clone()),
+        merged: desired.is_merged(),
+        room_name: desired.room_name.clone(),
+        time_slots: desired.slot_labels(),
+        success: error.is_none(),
+        action,
+        room_booking,
+        error,
+        join_url: Some(record.join_url.clone()).filter(|url| !url.is_empty()),
+        conflicts: Vec::new(),
+    }
+}
+
+// Book the room of a stored meeting that was kept without it
+//
+// On success the record becomes reserved again; otherwise it stays "room not
+// booked". The outcome is recorded on `result`.
+async fn rebook_room<C: MeetingApi>(
+    state: &AppState<C>,
+    record: &MeetingRecord,
+    result: &mut MeetingResult,
+) {
+    if state.skip_room_booking || is_simulated(state, record) {
+        return;
+    }
+
+    let book_request = BookRoomsRequest {
+        operator_id: record_operator_id(state, record),
+        operator_id_type: 1,
+        meeting_room_id_list: vec![record.room_id.clone()],
+        subject_visible: Some(true),
+    };
+
+    match state
+        .client
+        .book_rooms(&record.meeting_id, &book_request)
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "Booked room {} for meeting {} on retry",
+                record.room_id, record.meeting_id
+            );
+            result.success = true;
+            result.room_booking = RoomBooking::Booked;
+            result.error = None;
+            if let Err(e) = state
+                .database
+                .set_meeting_status(&record.meeting_id, ReservationStatus::Reserved)
+                .await
+            {
+                error!(
+                    "Failed to mark meeting {} as reserved: {}",
+                    record.meeting_id, e
+                );
+            }
+        }
+        Err(err) => {
+            error!(
+                "Failed again to book room {} for meeting {}: {}",
+                record.room_id, record.meeting_id, err
+            );
+            result.success = false;
+            result.room_booking = RoomBooking::Failed;
+            result.error = Some(format!("room booking failed: {}", err));
+        }
+    }
+}
+
+// The operator that created a stored meeting, for calls made on its behalf
+pub fn record_operator_id<C: MeetingApi>(state: &AppState<C>, record: &MeetingRecord) -> String {
+    if record.operator_id.is_empty() {
+        state.client.get_operator_id().to_string()
+    } else {
+        record.operator_id.clone()
+    }
+}
+
+// Base update request for an existing meeting, with no changes set
+fn meeting_update_request<C: MeetingApi>(
+    state: &AppState<C>,
+    record: &MeetingRecord,
+) -> UpdateMeetingRequest {
+    UpdateMeetingRequest {
+        // Update as the operator that created the meeting
+        userid: record_operator_id(state, record),
+        instanceid: 32,
+        subject: None,
+        start_time: None,
+        end_time: None,
+        password: None,
+        time_zone: None,
+        location: None,
+    }
+}
+
+// Whether a stored meeting only exists in the database (simulation mode)
+pub fn is_simulated<C>(state: &AppState<C>, record: &MeetingRecord) -> bool {
+    state.skip_meeting_creation || record.meeting_id.starts_with("simulation-")
+}
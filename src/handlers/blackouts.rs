@@ -0,0 +1,127 @@
+use axum::{
+    extract::{Json as ExtractJson, Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::client::MeetingApi;
+use crate::handlers::api::AppState;
+use crate::models::blackout::{BlackoutCalendar, BlackoutWindow, BlackoutWindowRequest};
+use crate::services::blackouts::is_valid_calendar_name;
+
+// List every holiday and blackout calendar, by room or location
+pub async fn list_blackout_calendars<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+) -> Json<BTreeMap<String, BlackoutCalendar>> {
+    info!("Received request to list blackout calendars");
+    Json(state.blackout_calendars.list())
+}
+
+// Get the calendar of one room or location
+pub async fn get_blackout_calendar<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(calendar): Path<String>,
+) -> Result<Json<BlackoutCalendar>, StatusCode> {
+    info!("Received request to get blackout calendar {}", calendar);
+
+    match state.blackout_calendars.get(&calendar) {
+        Some(calendar) => Ok(Json(calendar)),
+        None => {
+            warn!("Blackout calendar {} not found", calendar);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+// Add a blackout window to a room's or location's calendar
+pub async fn add_blackout_window<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(calendar): Path<String>,
+    ExtractJson(request): ExtractJson<BlackoutWindowRequest>,
+) -> Result<(StatusCode, Json<BlackoutWindow>), StatusCode> {
+    info!("Received request to add a blackout window to {}", calendar);
+
+    validate_request(&calendar, &request)?;
+    match state.blackout_calendars.add_window(&calendar, request) {
+        Ok(window) => Ok((StatusCode::CREATED, Json(window))),
+        Err(e) => {
+            error!("Failed to add blackout window to {}: {}", calendar, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Replace the times and reason of a blackout window
+pub async fn update_blackout_window<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path((calendar, window_id)): Path<(String, String)>,
+    ExtractJson(request): ExtractJson<BlackoutWindowRequest>,
+) -> Result<Json<BlackoutWindow>, StatusCode> {
+    info!(
+        "Received request to update blackout window {} of {}",
+        window_id, calendar
+    );
+
+    validate_request(&calendar, &request)?;
+    match state
+        .blackout_calendars
+        .update_window(&calendar, &window_id, request)
+    {
+        Ok(Some(window)) => Ok(Json(window)),
+        Ok(None) => {
+            warn!("Blackout window {} of {} not found", window_id, calendar);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            error!(
+                "Failed to update blackout window {} of {}: {}",
+                window_id, calendar, e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Remove a blackout window
+pub async fn delete_blackout_window<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path((calendar, window_id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    info!(
+        "Received request to delete blackout window {} of {}",
+        window_id, calendar
+    );
+
+    match state
+        .blackout_calendars
+        .remove_window(&calendar, &window_id)
+    {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => {
+            warn!("Blackout window {} of {} not found", window_id, calendar);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            error!(
+                "Failed to delete blackout window {} of {}: {}",
+                window_id, calendar, e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Refuse calendar names that aren't file names and windows that don't end after they start
+fn validate_request(calendar: &str, request: &BlackoutWindowRequest) -> Result<(), StatusCode> {
+    if !is_valid_calendar_name(calendar) {
+        warn!("Invalid blackout calendar name '{}'", calendar);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    request.validate().map_err(|e| {
+        warn!("Invalid blackout window for {}: {}", calendar, e);
+        StatusCode::BAD_REQUEST
+    })
+}
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::client::MeetingApi;
+use crate::handlers::api::{AppState, WebhookQueryParams};
+use crate::services::calendar::{render_calendar, CALENDAR_CONTENT_TYPE};
+use crate::services::reservations::ReservationQuery;
+
+// Calendar feed of one room, e.g. /calendar/大会议室.ics
+pub async fn get_room_calendar<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(file_name): Path<String>,
+    Query(params): Query<WebhookQueryParams>,
+) -> Result<Response, StatusCode> {
+    info!("Received request for room calendar {}", file_name);
+    check_feed_auth(&state, &params)?;
+
+    let room = feed_name(&file_name)?;
+    let query = ReservationQuery {
+        room: Some(room.to_string()),
+        ..Default::default()
+    };
+    calendar_response(&state, room, &query).await
+}
+
+// Calendar feed of one operator, by name or ID, e.g. /calendar/operators/张三.ics
+pub async fn get_operator_calendar<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(file_name): Path<String>,
+    Query(params): Query<WebhookQueryParams>,
+) -> Result<Response, StatusCode> {
+    info!("Received request for operator calendar {}", file_name);
+    check_feed_auth(&state, &params)?;
+
+    let operator = feed_name(&file_name)?;
+    let query = ReservationQuery {
+        operator: Some(operator.to_string()),
+        ..Default::default()
+    };
+    calendar_response(&state, operator, &query).await
+}
+
+// Check the feed token, if one is configured
+//
+// Calendar clients can't send headers, so the token goes in the feed URL like
+// the webhook's.
+fn check_feed_auth<C>(state: &AppState<C>, params: &WebhookQueryParams) -> Result<(), StatusCode> {
+    match (&state.calendar_feed_token, &params.auth) {
+        (None, _) => Ok(()),
+        (Some(expected_token), Some(token)) if token == expected_token => Ok(()),
+        (Some(_), Some(_)) => {
+            warn!("Invalid calendar feed token provided");
+            Err(StatusCode::UNAUTHORIZED)
+        }
+        (Some(_), None) => {
+            warn!("No calendar feed token provided");
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+// Feed name from a file name, which has to end in ".ics"
+fn feed_name(file_name: &str) -> Result<&str, StatusCode> {
+    match file_name.strip_suffix(".ics") {
+        Some(name) if !name.is_empty() => Ok(name),
+        _ => {
+            warn!(
+                "Calendar requested without an .ics file name: {}",
+                file_name
+            );
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+// Render the records matching a query as a calendar
+//
+// A name without reservations gets an empty calendar, so subscriptions keep
+// working before the first booking.
+async fn calendar_response<C: MeetingApi>(
+    state: &AppState<C>,
+    name: &str,
+    query: &ReservationQuery,
+) -> Result<Response, StatusCode> {
+    match state.database.find_reservations(query).await {
+        Ok(records) => Ok((
+            [(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)],
+            render_calendar(name, &records, Utc::now()),
+        )
+            .into_response()),
+        Err(e) => {
+            error!("Failed to query reservations for calendar {}: {}", name, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::client::{CancelMeetingRequest, MeetingApi, ReleaseRoomsRequest, TencentApiError};
+use crate::handlers::api::{is_simulated, record_operator_id, AppState};
+use crate::models::reservation::ReservationStatus;
+
+// Cancel a meeting in Tencent Meeting as the operator that created it
+//
+// Failures are only warned about here; callers decide whether they are final.
+pub async fn cancel_tencent_meeting<C: MeetingApi>(
+    state: &AppState<C>,
+    meeting_id: &str,
+    operator_id: &str,
+    reason: &str,
+) -> Result<(), TencentApiError> {
+    let cancel_request = CancelMeetingRequest {
+        userid: operator_id.to_string(),
+        instanceid: 32,
+        reason_code: 1, // Cancellation reason code
+        meeting_type: None,
+        sub_meeting_id: None,
+        reason_detail: Some(reason.to_string()),
+    };
+
+    match state
+        .client
+        .cancel_meeting(meeting_id, &cancel_request)
+        .await
+    {
+        Ok(_) => {
+            info!("Successfully cancelled meeting with ID: {}", meeting_id);
+            Ok(())
+        }
+        Err(err) => {
+            warn!("Failed to cancel meeting {}: {}", meeting_id, err);
+            Err(err)
+        }
+    }
+}
+
+/// Finish a requested cancellation: release the room, then cancel the meeting
+///
+/// Each confirmed step is recorded in the database, so a failed cancellation
+/// is retried from where it stopped. An empty `room_id` means there is no
+/// room left to release. The meeting is only marked cancelled once Tencent
+/// Meeting has confirmed it, or reports that it no longer exists. Both calls
+/// are made as `operator_id`, the operator that created the meeting.
+pub async fn complete_cancellation<C: MeetingApi>(
+    state: &AppState<C>,
+    meeting_id: &str,
+    room_id: &str,
+    operator_id: &str,
+) -> Result<(), TencentApiError> {
+    // Step 1: Release the meeting room
+    if room_id.is_empty() {
+        info!("Meeting {} has no booked room to release", meeting_id);
+    } else {
+        let release_request = ReleaseRoomsRequest {
+            operator_id: operator_id.to_string(),
+            operator_id_type: 1,
+            meeting_room_id_list: vec![room_id.to_string()],
+        };
+
+        if let Err(err) = state
+            .client
+            .release_rooms(meeting_id, &release_request)
+            .await
+        {
+            warn!(
+                "Failed to release room {} for meeting {}: {}",
+                room_id, meeting_id, err
+            );
+            if let Err(e) = state.database.mark_cancellation_failed(meeting_id).await {
+                error!(
+                    "Failed to record failed cancellation of {}: {}",
+                    meeting_id, e
+                );
+            }
+            return Err(err);
+        }
+
+        info!(
+            "Successfully released room {} for meeting {}",
+            room_id, meeting_id
+        );
+        if let Err(e) = state.database.mark_room_released(meeting_id).await {
+            error!("Failed to record room release for {}: {}", meeting_id, e);
+        }
+    }
+
+    // Step 2: Cancel the meeting; on failure it stays room-released and is retried
+    match cancel_tencent_meeting(state, meeting_id, operator_id, "Form submission cancelled").await
+    {
+        Ok(()) => {}
+        Err(TencentApiError::NotFound(_)) => {
+            info!(
+                "Meeting {} no longer exists in Tencent Meeting, counting it as cancelled",
+                meeting_id
+            );
+        }
+        Err(err) => return Err(err),
+    }
+
+    if let Err(e) = state.database.finalize_cancellation(meeting_id).await {
+        error!("Failed to mark meeting {} cancelled: {}", meeting_id, e);
+    }
+
+    Ok(())
+}
+
+/// Failed attempts of the cancellations the sweeper is retrying
+///
+/// Kept in memory by the sweeper, so a restart gives every pending
+/// cancellation a fresh set of attempts.
+#[derive(Debug)]
+pub struct CancellationRetries {
+    max_attempts: u32,
+    attempts: HashMap<String, u32>, // By meeting ID
+}
+
+impl CancellationRetries {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            attempts: HashMap::new(),
+        }
+    }
+
+    // Count a failed attempt; returns whether the cancellation may be retried
+    fn record_failure(&mut self, meeting_id: &str, err: &TencentApiError) -> bool {
+        let attempts = self.attempts.entry(meeting_id.to_string()).or_insert(0);
+        *attempts += 1;
+        // Cancelling and releasing again is harmless, so both count as idempotent
+        err.is_retryable(true) && *attempts < self.max_attempts
+    }
+
+    fn forget(&mut self, meeting_id: &str) {
+        self.attempts.remove(meeting_id);
+    }
+}
+
+/// Retry cancellations that were requested at least `min_age` ago
+///
+/// Each meeting is retried while holding its entry token's submission lock,
+/// so a webhook for the same token can't work on it at the same time.
+/// Cancellations that fail with a permanent error, or still fail after the
+/// last attempt, are marked `CancelAbandoned` and no longer retried.
+/// Returns the number of meetings whose cancellation was completed.
+pub async fn sweep_pending_cancellations<C: MeetingApi>(
+    state: &AppState<C>,
+    min_age: Duration,
+    retries: &mut CancellationRetries,
+) -> usize {
+    let requested_before = Utc::now()
+        - chrono::Duration::from_std(min_age).unwrap_or_else(|_| chrono::Duration::zero());
+
+    let pending = match state
+        .database
+        .find_pending_cancellations(requested_before)
+        .await
+    {
+        Ok(pending) => pending,
+        Err(e) => {
+            error!("Failed to look up pending cancellations: {}", e);
+            return 0;
+        }
+    };
+
+    if pending.is_empty() {
+        return 0;
+    }
+
+    info!("Retrying {} pending cancellations", pending.len());
+
+    let mut completed = 0;
+    let mut seen = HashSet::new();
+    for record in pending {
+        // Merged meetings can span several records
+        if !seen.insert(record.meeting_id.clone()) {
+            continue;
+        }
+
+        // Wait for any webhook working on the token, then check the
+        // cancellation is still pending
+        let _submission_guard = state.submission_locks.acquire(&record.entry_token).await;
+        let record = match state.database.find_meetings_by_id(&record.meeting_id).await {
+            Ok(records) => match records
+                .into_iter()
+                .find(|record| record.status.is_cancellation_pending())
+            {
+                Some(record) => record,
+                None => continue,
+            },
+            Err(e) => {
+                error!("Failed to look up meeting {}: {}", record.meeting_id, e);
+                continue;
+            }
+        };
+
+        if is_simulated(state, &record) {
+            if let Err(e) = state
+                .database
+                .finalize_cancellation(&record.meeting_id)
+                .await
+            {
+                error!(
+                    "Failed to mark meeting {} cancelled: {}",
+                    record.meeting_id, e
+                );
+                continue;
+            }
+            completed += 1;
+            continue;
+        }
+
+        // Resume from the last confirmed step
+        let room_id = if record.status == ReservationStatus::RoomReleased {
+            ""
+        } else {
+            &record.room_id
+        };
+
+        let operator_id = record_operator_id(state, &record);
+        match complete_cancellation(state, &record.meeting_id, room_id, &operator_id).await {
+            Ok(()) => {
+                retries.forget(&record.meeting_id);
+                completed += 1;
+            }
+            Err(e) if retries.record_failure(&record.meeting_id, &e) => warn!(
+                "Cancellation of meeting {} is still pending: {}",
+                record.meeting_id, e
+            ),
+            Err(e) => {
+                retries.forget(&record.meeting_id);
+                error!(
+                    "Giving up cancellation of meeting {}, it has to be cancelled by hand: {}",
+                    record.meeting_id, e
+                );
+                if let Err(e) = state
+                    .database
+                    .mark_cancellation_abandoned(&record.meeting_id)
+                    .await
+                {
+                    error!(
+                        "Failed to record abandoned cancellation of {}: {}",
+                        record.meeting_id, e
+                    );
+                }
+            }
+        }
+    }
+
+    completed
+}
+
+/// Start the background task that retries unfinished cancellations
+///
+/// Runs every `interval` and retries cancellations requested at least one
+/// interval ago, so requests still being handled by the webhook are left
+/// alone. Each cancellation gets up to `max_attempts` retries.
+pub fn spawn_cancellation_sweeper<C: MeetingApi + 'static>(
+    state: Arc<AppState<C>>,
+    interval: Duration,
+    max_attempts: u32,
+) -> JoinHandle<()> {
+    info!(
+        "Starting cancellation sweeper (every {:?}, up to {} attempts)",
+        interval, max_attempts
+    );
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut retries = CancellationRetries::new(max_attempts);
+        loop {
+            ticker.tick().await;
+            let completed = sweep_pending_cancellations(&state, interval, &mut retries).await;
+            if completed > 0 {
+                info!("Cancellation sweeper completed {} cancellations", completed);
+            }
+        }
+    })
+}
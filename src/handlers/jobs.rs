@@ -0,0 +1,128 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::client::MeetingApi;
+use crate::handlers::api::{
+    check_webhook_auth, process_form_submission, AppState, WebhookQueryParams,
+};
+use crate::models::meeting::{MeetingAction, WebhookResponse};
+use crate::services::jobs::JobStatusResponse;
+
+// Get the status of a queued webhook submission
+//
+// Protected by the webhook auth token, since job IDs can be guessed.
+pub async fn get_job<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(job_id): Path<String>,
+    Query(params): Query<WebhookQueryParams>,
+) -> Result<Json<JobStatusResponse>, StatusCode> {
+    check_webhook_auth(&state, &params)?;
+
+    let job_queue = state.job_queue.as_ref().ok_or_else(|| {
+        warn!("Job {} requested but asynchronous mode is disabled", job_id);
+        StatusCode::NOT_FOUND
+    })?;
+
+    match job_queue.get(&job_id) {
+        Some(job) => Ok(Json(job.into())),
+        None => {
+            warn!("Job {} not found", job_id);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+/// Start the background worker that processes queued webhook submissions
+///
+/// Jobs are processed one at a time. Failures that may succeed later (rate
+/// limits, Tencent or transport errors, and meetings that failed to be
+/// created, booked or updated) are retried with backoff until the queue's
+/// attempt limit; a stored meeting whose room is still not booked gets
+/// another booking attempt on each retry. Returns `None` when asynchronous mode is disabled
+/// or a worker is already running.
+pub fn spawn_job_worker<C: MeetingApi + 'static>(
+    state: Arc<AppState<C>>,
+) -> Option<JoinHandle<()>> {
+    let job_queue = Arc::clone(state.job_queue.as_ref()?);
+    let mut receiver = job_queue.take_receiver()?;
+
+    info!("Starting job worker");
+
+    Some(tokio::spawn(async move {
+        while let Some(job_id) = receiver.recv().await {
+            // Skip stale IDs, e.g. a job that already finished
+            let Some(job) = job_queue.start(&job_id) else {
+                continue;
+            };
+
+            info!(
+                "Processing job {} for token {} (attempt {})",
+                job.id, job.entry_token, job.attempts
+            );
+
+            let retry_after = match process_form_submission(&state, &job.payload).await {
+                Ok(response) if response.success || !is_retryable_response(&response) => {
+                    info!("Job {} finished: {}", job.id, response.message);
+                    job_queue.succeed(&job.id, response);
+                    None
+                }
+                Ok(response) => {
+                    // A meeting failed to be created, booked or updated; processing
+                    // again reconciles with what was stored and retries the rest
+                    job_queue.fail_with_response(&job.id, response)
+                }
+                Err(status) => {
+                    let message = format!("Processing failed with status {}", status);
+                    job_queue.fail(&job.id, &message, is_retryable(status))
+                }
+            };
+
+            if let Some(delay) = retry_after {
+                // Put the job back in the queue once the delay has passed
+                let retry_queue = Arc::clone(&job_queue);
+                let retry_id = job.id.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    retry_queue.requeue(&retry_id);
+                });
+            }
+        }
+
+        error!("Job queue closed, job worker stopping");
+    }))
+}
+
+// Whether an unsuccessful response may turn out better if processed again
+//
+// Only meetings that failed to be created, booked or updated are retried:
+// slots rejected because another entry holds their rooms stay rejected, and
+// failed cancellations are left to the cancellation sweeper. Responses
+// without meeting results (e.g. a cancellation for a token with no active
+// meetings) are final.
+fn is_retryable_response(response: &WebhookResponse) -> bool {
+    response.meetings.iter().any(|result| {
+        !result.success
+            && !matches!(
+                result.action,
+                MeetingAction::Rejected | MeetingAction::Cancelled
+            )
+    })
+}
+
+// Whether a failed submission may succeed if processed again
+fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
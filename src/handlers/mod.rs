@@ -1,2 +1,7 @@
 pub mod api;
+pub mod blackouts;
+pub mod calendar;
+pub mod cancellations;
+pub mod jobs;
+pub mod reservations;
 pub mod test;
@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::client::MeetingApi;
+use crate::handlers::api::AppState;
+use crate::models::common::PaginationParams;
+use crate::services::database::MeetingRecord;
+use crate::services::export::ExportFormat;
+use crate::services::reservations::{ReservationPage, ReservationQuery};
+
+// List stored reservations, filtered and paged
+pub async fn list_reservations<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Query(query): Query<ReservationQuery>,
+    Query(params): Query<PaginationParams>,
+) -> Result<Json<ReservationPage>, StatusCode> {
+    info!(
+        "Received request to list reservations ({:?}) with page={}, page_size={}",
+        query, params.page, params.page_size
+    );
+
+    match state.database.find_reservations(&query).await {
+        Ok(records) => Ok(Json(ReservationPage::new(records, &params))),
+        Err(e) => {
+            error!("Failed to query reservations: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Get every stored record for a form submission
+pub async fn get_reservations_by_token<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Path(entry_token): Path<String>,
+) -> Result<Json<Vec<MeetingRecord>>, StatusCode> {
+    info!(
+        "Received request to get reservations for token {}",
+        entry_token
+    );
+
+    match state
+        .database
+        .find_all_meetings_by_token(&entry_token)
+        .await
+    {
+        Ok(records) if records.is_empty() => {
+            warn!("No reservations found for token {}", entry_token);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Ok(records) => Ok(Json(records)),
+        Err(e) => {
+            error!("Failed to query reservations for {}: {}", entry_token, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Query parameters for reservation exports
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    #[serde(default)]
+    pub format: ExportFormat, // csv (default), jsonl or xlsx
+}
+
+// Download the reservations matching the filters as a file
+pub async fn export_reservations<C: MeetingApi>(
+    State(state): State<Arc<AppState<C>>>,
+    Query(query): Query<ReservationQuery>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response, StatusCode> {
+    info!(
+        "Received request to export reservations ({:?}) as {:?}",
+        query, params.format
+    );
+
+    match state
+        .database
+        .export_reservations(&query, params.format)
+        .await
+    {
+        Ok(body) => {
+            let disposition = format!(
+                "attachment; filename=\"reservations.{}\"",
+                params.format.file_extension()
+            );
+            Ok((
+                [
+                    (
+                        header::CONTENT_TYPE,
+                        params.format.content_type().to_string(),
+                    ),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                body,
+            )
+                .into_response())
+        }
+        Err(e) => {
+            error!("Failed to export reservations: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
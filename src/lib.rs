@@ -30,8 +30,10 @@ mod tests;
 // Re-export the main API types for ease of use
 pub use auth::TencentAuth;
 pub use client::{
-    CancelMeetingRequest, CreateMeetingRequest, CreateMeetingResponse, MeetingInfo,
-    MeetingRoomItem, MeetingRoomsResponse, MeetingSettings, TencentMeetingClient, User,
+    ApiErrorDetail, CancelMeetingRequest, CreateMeetingRequest, CreateMeetingResponse, MeetingApi,
+    MeetingInfo, MeetingRoomItem, MeetingRoomsResponse, MeetingSettings, QueryMeetingResponse,
+    RetryPolicy, TencentApiError, TencentMeetingClient, UpdateMeetingRequest,
+    UpdateMeetingResponse, User,
 };
 pub use handlers::api::AppState;
 pub use models::common::PaginationParams;
@@ -15,9 +15,24 @@ use tracing::{info, Level};
 use sentry;
 
 use tencent_meeting_service::{
-    create_router, services::database::create_database_service, AppState, TencentMeetingClient,
+    create_router,
+    handlers::{cancellations::spawn_cancellation_sweeper, jobs::spawn_job_worker},
+    models::{
+        booking_policy::BookingPolicyConfig,
+        meeting::{BookingFailurePolicy, RoomConflictPolicy},
+        reservation::StatusMapping,
+        time_zone::{parse_time_zone, TimeZoneConfig, DEFAULT_TIME_ZONE},
+    },
+    services::{
+        async_store::AsyncMeetingStore, blackouts::load_blackout_calendars,
+        database::create_database_service, idempotency::SubmissionLocks, jobs::create_job_queue,
+    },
+    AppState, TencentMeetingClient,
 };
 
+// Time allowed for handling a request; Tencent API retries have to fit within it
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
 // Error handler
 async fn handle_error(error: BoxError) -> (StatusCode, String) {
     // Capture error to Sentry if enabled
@@ -89,7 +104,7 @@ async fn main() {
     info!("Using form field mappings and city-specific room IDs from environment variables");
 
     // Initialize the database service
-    let database = create_database_service();
+    let database = AsyncMeetingStore::new(create_database_service());
     info!("Database service initialized");
 
     // Load toggle settings from environment or default to false
@@ -110,17 +125,91 @@ async fn main() {
     // Load webhook auth token from environment if provided
     let webhook_auth_token = env::var("WEBHOOK_AUTH_TOKEN").ok();
 
+    // What to do with a new meeting whose room can't be booked (default: cancel it)
+    let booking_failure_policy = match env::var("ROOM_BOOKING_FAILURE_POLICY") {
+        Ok(val) => BookingFailurePolicy::parse(&val).unwrap_or_else(|| {
+            panic!(
+                "Invalid ROOM_BOOKING_FAILURE_POLICY '{}': expected 'cancel' or 'keep'",
+                val
+            )
+        }),
+        Err(_) => BookingFailurePolicy::default(),
+    };
+    info!("Room booking failure policy: {:?}", booking_failure_policy);
+
+    // What to do with slots whose room another entry already holds (default: reject the submission)
+    let room_conflict_policy = match env::var("ROOM_CONFLICT_POLICY") {
+        Ok(val) => RoomConflictPolicy::parse(&val).unwrap_or_else(|| {
+            panic!(
+                "Invalid ROOM_CONFLICT_POLICY '{}': expected 'reject', 'skip' or 'allow'",
+                val
+            )
+        }),
+        Err(_) => RoomConflictPolicy::default(),
+    };
+    info!("Room conflict policy: {:?}", room_conflict_policy);
+
+    // Working hours, duration and advance limits by room or form (default: none)
+    let booking_policies = match env::var("BOOKING_POLICY_FILE") {
+        Ok(path) => BookingPolicyConfig::load(&path)
+            .unwrap_or_else(|e| panic!("Invalid BOOKING_POLICY_FILE: {}", e)),
+        Err(_) => BookingPolicyConfig::default(),
+    };
+
+    // Holidays, adjusted workdays and blackout windows by room or location
+    let blackout_calendars = load_blackout_calendars();
+
+    // Extra form status strings, e.g. "待审核=pending,已退订=cancelled"
+    let status_mapping = match env::var("FORM_STATUS_MAPPING") {
+        Ok(val) => StatusMapping::parse(&val)
+            .unwrap_or_else(|e| panic!("Invalid FORM_STATUS_MAPPING: {}", e)),
+        Err(_) => StatusMapping::default(),
+    };
+
+    // Zone of rooms and forms without their own, and zones by room or form name,
+    // e.g. "London Room=Europe/London,成都会议室预约=Asia/Shanghai"
+    let default_time_zone = match env::var("DEFAULT_TIME_ZONE") {
+        Ok(val) => {
+            parse_time_zone(&val).unwrap_or_else(|e| panic!("Invalid DEFAULT_TIME_ZONE: {}", e))
+        }
+        Err(_) => DEFAULT_TIME_ZONE,
+    };
+    let time_zones = TimeZoneConfig::parse(
+        default_time_zone,
+        &env::var("TIME_ZONE_MAPPING").unwrap_or_default(),
+    )
+    .unwrap_or_else(|e| panic!("Invalid TIME_ZONE_MAPPING: {}", e));
+    info!("Default time zone: {}", time_zones.default_zone());
+
     if webhook_auth_token.is_some() {
         info!("Webhook authentication enabled with provided token");
     } else {
         info!("No webhook authentication token provided - authentication disabled");
     }
 
+    // Open the persistent job queue if webhooks should be processed asynchronously
+    let job_queue = create_job_queue();
+
+    if job_queue.is_some() {
+        info!("Asynchronous webhook mode enabled: submissions are queued and processed in the background");
+    }
+
     // Check if running in production mode
     let is_production = env::var("ENVIRONMENT")
         .map(|val| val.to_lowercase() == "production")
         .unwrap_or(false);
 
+    // Serve the calendar feeds in production mode too
+    let calendar_feeds = env::var("CALENDAR_FEEDS_ENABLED")
+        .map(|val| val.to_lowercase() == "true")
+        .unwrap_or(false);
+
+    // Token calendar subscribers have to pass as ?auth= (required for feeds in production)
+    let calendar_feed_token = env::var("CALENDAR_FEED_TOKEN").ok();
+    if is_production && calendar_feeds && calendar_feed_token.is_none() {
+        panic!("CALENDAR_FEEDS_ENABLED requires CALENDAR_FEED_TOKEN in production");
+    }
+
     if is_production {
         info!("Running in PRODUCTION mode - restricting available endpoints");
     } else {
@@ -138,15 +227,47 @@ async fn main() {
         skip_meeting_creation,
         skip_room_booking,
         webhook_auth_token,
+        calendar_feed_token,
+        submission_locks: SubmissionLocks::new(),
+        room_locks: SubmissionLocks::new(),
+        job_queue,
+        booking_failure_policy,
+        status_mapping,
+        time_zones,
+        room_conflict_policy,
+        booking_policies,
+        blackout_calendars,
     });
 
+    // Start processing queued submissions, including any left from a previous run
+    spawn_job_worker(Arc::clone(&app_state));
+
+    // Retry cancellations that Tencent has not confirmed (0 disables the sweeper)
+    let sweep_interval_secs = env::var("CANCELLATION_SWEEP_INTERVAL_SECS")
+        .ok()
+        .and_then(|val| val.parse::<u64>().ok())
+        .unwrap_or(60);
+    let sweep_max_attempts = env::var("CANCELLATION_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|val| val.parse::<u32>().ok())
+        .unwrap_or(10);
+    if sweep_interval_secs > 0 {
+        spawn_cancellation_sweeper(
+            Arc::clone(&app_state),
+            Duration::from_secs(sweep_interval_secs),
+            sweep_max_attempts,
+        );
+    } else {
+        info!("Cancellation sweeper disabled");
+    }
+
     // Create router with appropriate routes based on environment
-    let app = create_router(app_state, is_production).layer(
+    let app = create_router(app_state, is_production, calendar_feeds).layer(
         ServiceBuilder::new()
             .layer(HandleErrorLayer::new(handle_error))
             .load_shed()
             .concurrency_limit(64)
-            .timeout(Duration::from_secs(10))
+            .timeout(REQUEST_TIMEOUT)
             .layer(TraceLayer::new_for_http())
             .layer(CorsLayer::new().allow_origin(Any)),
     );
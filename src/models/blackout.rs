@@ -0,0 +1,210 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Holidays and blackout windows of one room or location
+///
+/// Loaded from `<room or location>.json` in the calendar directory:
+///
+/// ```json
+/// {
+///   "holidays": [
+///     { "date": "2035-10-01", "name": "国庆节" },
+///     { "date": "2035-10-02", "name": "国庆节" }
+///   ],
+///   "adjusted_workdays": [
+///     { "date": "2035-09-29", "name": "国庆节调休" }
+///   ],
+///   "blackouts": [
+///     {
+///       "id": "aircon-maintenance",
+///       "start": "2035-04-02T08:00:00",
+///       "end": "2035-04-02T12:00:00",
+///       "reason": "空调检修"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Dates and times are local wall-clock time in the slot's zone. Holidays
+/// close the room for the whole day. Adjusted workdays (调休) are weekend
+/// days that are worked to make up for a holiday, so working hours apply to
+/// them like to a weekday.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlackoutCalendar {
+    #[serde(default)]
+    pub holidays: Vec<CalendarDay>,
+    #[serde(default)]
+    pub adjusted_workdays: Vec<CalendarDay>,
+    #[serde(default)]
+    pub blackouts: Vec<BlackoutWindow>,
+}
+
+/// A named day of a calendar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub name: String, // e.g. "春节"
+}
+
+/// A period a room can't be booked, e.g. for maintenance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlackoutWindow {
+    pub id: String,
+    pub start: NaiveDateTime, // e.g. "2035-04-02T08:00:00"
+    pub end: NaiveDateTime,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Request body for adding or replacing a blackout window
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlackoutWindowRequest {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl BlackoutWindowRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.end <= self.start {
+            return Err("Blackout window must end after it starts".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Why a slot can't be booked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Unavailability {
+    Holiday {
+        calendar: String, // Room or location the calendar belongs to
+        date: NaiveDate,
+        name: String,
+    },
+    Blackout {
+        calendar: String,
+        window: BlackoutWindow,
+    },
+}
+
+impl fmt::Display for Unavailability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Holiday {
+                calendar,
+                date,
+                name,
+            } => write!(f, "{} is closed on {} ({})", calendar, date, name),
+            Self::Blackout { calendar, window } => write!(
+                f,
+                "{} is unavailable {} to {} ({})",
+                calendar,
+                window.start.format("%Y-%m-%d %H:%M"),
+                window.end.format("%Y-%m-%d %H:%M"),
+                window.reason
+            ),
+        }
+    }
+}
+
+/// The holidays and blackout windows one slot falls into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlotBlackout {
+    pub number: i32, // The slot's number on the form
+    pub item_name: String,
+    pub scheduled_label: String,
+    pub unavailable: Vec<Unavailability>,
+}
+
+impl BlackoutCalendar {
+    /// Parse a calendar file, checking its dates and windows
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let calendar: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let holidays: HashSet<NaiveDate> = calendar.holidays.iter().map(|day| day.date).collect();
+        for day in &calendar.adjusted_workdays {
+            if holidays.contains(&day.date) {
+                return Err(format!(
+                    "{} is both a holiday and an adjusted workday",
+                    day.date
+                ));
+            }
+            if !matches!(day.date.weekday(), Weekday::Sat | Weekday::Sun) {
+                return Err(format!("Adjusted workday {} is not on a weekend", day.date));
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for window in &calendar.blackouts {
+            if !ids.insert(window.id.as_str()) {
+                return Err(format!("Duplicate blackout window id '{}'", window.id));
+            }
+            if window.end <= window.start {
+                return Err(format!(
+                    "Blackout window '{}' doesn't end after it starts",
+                    window.id
+                ));
+            }
+        }
+
+        Ok(calendar)
+    }
+
+    /// Whether a date is a working day by this calendar
+    ///
+    /// Holidays are not, adjusted workdays are; for any other date the
+    /// calendar has no say (`None`) and the day of the week decides.
+    pub fn working_day(&self, date: NaiveDate) -> Option<bool> {
+        if self.holidays.iter().any(|day| day.date == date) {
+            Some(false)
+        } else if self.adjusted_workdays.iter().any(|day| day.date == date) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Holidays and blackout windows a local time range falls into
+    pub fn unavailability(
+        &self,
+        calendar_name: &str,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Vec<Unavailability> {
+        // The range covers the dates from its start up to the instant before its end
+        let last_date = if end > start {
+            (end - chrono::Duration::nanoseconds(1)).date()
+        } else {
+            start.date()
+        };
+
+        let holidays = self
+            .holidays
+            .iter()
+            .filter(|day| day.date >= start.date() && day.date <= last_date)
+            .map(|day| Unavailability::Holiday {
+                calendar: calendar_name.to_string(),
+                date: day.date,
+                name: day.name.clone(),
+            });
+        let blackouts = self
+            .blackouts
+            .iter()
+            .filter(|window| window.start < end && start < window.end)
+            .map(|window| Unavailability::Blackout {
+                calendar: calendar_name.to_string(),
+                window: window.clone(),
+            });
+
+        holidays.chain(blackouts).collect()
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Rules a room's bookings have to follow
+///
+/// Every rule is optional; a policy without rules allows everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BookingPolicy {
+    #[serde(default)]
+    pub working_hours: Option<WorkingHours>,
+    #[serde(default)]
+    pub max_duration_minutes: Option<i64>, // Longest meeting, after merging slots
+    #[serde(default)]
+    pub max_advance_days: Option<i64>, // Furthest date ahead of today that can be booked
+}
+
+/// Days and times of day bookings have to fall within, in the slot's zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkingHours {
+    #[serde(default = "weekdays")]
+    pub days: Vec<Weekday>, // e.g. ["mon", "tue"]; Monday to Friday if left out
+    pub start: NaiveTime, // e.g. "08:00"
+    pub end: NaiveTime,   // e.g. "21:00"
+}
+
+fn weekdays() -> Vec<Weekday> {
+    vec![
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+    ]
+}
+
+impl fmt::Display for WorkingHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days: Vec<String> = self.days.iter().map(|day| day.to_string()).collect();
+        write!(
+            f,
+            "{} {}-{}",
+            days.join(","),
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// A rule of a booking policy that a slot breaks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum PolicyViolation {
+    OutsideWorkingHours { working_hours: WorkingHours },
+    TooLong { minutes: i64, max_minutes: i64 }, // Minutes of the merged meeting
+    TooFarAhead { days: i64, max_days: i64 },   // Days between today and the slot's date
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutsideWorkingHours { working_hours } => {
+                write!(f, "outside working hours ({})", working_hours)
+            }
+            Self::TooLong {
+                minutes,
+                max_minutes,
+            } => write!(
+                f,
+                "meeting lasts {} minutes, at most {} allowed",
+                minutes, max_minutes
+            ),
+            Self::TooFarAhead { days, max_days } => {
+                write!(f, "{} days in advance, at most {} allowed", days, max_days)
+            }
+        }
+    }
+}
+
+/// The booking policy rules one slot breaks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlotPolicyViolations {
+    pub number: i32, // The slot's number on the form
+    pub item_name: String,
+    pub scheduled_label: String,
+    pub policy: String, // Name of the policy the slot follows
+    pub violations: Vec<PolicyViolation>,
+}
+
+/// Booking policies and the rooms and forms they apply to
+///
+/// Loaded from the JSON file named by `BOOKING_POLICY_FILE`:
+///
+/// ```json
+/// {
+///   "policies": {
+///     "office_hours": {
+///       "working_hours": { "days": ["mon", "tue", "wed", "thu", "fri"], "start": "08:00", "end": "21:00" },
+///       "max_duration_minutes": 240,
+///       "max_advance_days": 30
+///     },
+///     "unrestricted": {}
+///   },
+///   "default": "office_hours",
+///   "rooms": { "Board Room": "unrestricted" },
+///   "forms": { "成都会议室预约": "office_hours" }
+/// }
+/// ```
+///
+/// A slot follows the policy of its room if one is assigned, else that of
+/// its form, else the default policy. Without any, bookings are unrestricted.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BookingPolicyConfig {
+    #[serde(default)]
+    policies: HashMap<String, BookingPolicy>,
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    rooms: HashMap<String, String>, // Room name -> policy name
+    #[serde(default)]
+    forms: HashMap<String, String>, // Form name -> policy name
+}
+
+impl BookingPolicyConfig {
+    /// Parse a policy file, checking that every assignment names a policy
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let config: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let assigned = config
+            .default
+            .iter()
+            .chain(config.rooms.values())
+            .chain(config.forms.values());
+        for name in assigned {
+            if !config.policies.contains_key(name) {
+                return Err(format!("Unknown booking policy '{}'", name));
+            }
+        }
+
+        for (name, policy) in &config.policies {
+            if let Some(working_hours) = &policy.working_hours {
+                if working_hours.start >= working_hours.end {
+                    return Err(format!(
+                        "Booking policy '{}' has working hours that don't end after they start",
+                        name
+                    ));
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Read and parse a policy file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&json)
+    }
+
+    /// Name and rules of the policy for a room booked through a form
+    pub fn policy_for(&self, form_name: &str, room_name: &str) -> Option<(&str, &BookingPolicy)> {
+        let name = self
+            .rooms
+            .get(room_name)
+            .or_else(|| self.forms.get(form_name))
+            .or(self.default.as_ref())?;
+        self.policies
+            .get(name)
+            .map(|policy| (name.as_str(), policy))
+    }
+}
@@ -11,7 +11,7 @@ pub struct FormField1Item {
     pub api_code: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct FormEntry {
     pub token: String,
     pub field_1: Vec<FormField1Item>,
@@ -21,7 +21,7 @@ pub struct FormEntry {
     pub reservation_status_fsf_field: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct FormSubmission {
     pub form: String,
     pub form_name: String,
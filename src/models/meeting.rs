@@ -1,5 +1,11 @@
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::models::blackout::SlotBlackout;
+use crate::models::booking_policy::SlotPolicyViolations;
+use crate::models::scheduled_label::ScheduledLabelError;
 
 // Structure to represent a parsed time slot
 #[derive(Debug, Clone, Serialize)]
@@ -10,20 +16,145 @@ pub struct TimeSlot {
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
     pub api_code: String,
+    pub time_zone: Tz, // Zone the label is in (the room's or form's zone)
+}
+
+// Why a form slot could not be parsed into a time slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimeSlotError {
+    InvalidLabel {
+        reason: ScheduledLabelError,
+    },
+    InvalidScheduledAt {
+        value: String,
+        reason: String,
+    },
+    ScheduledAtMismatch {
+        scheduled_at: String,
+        label_start: String,
+    }, // Start on the label, "YYYY-MM-DD HH:MM"
+    InPast, // Both start and end are in the past
+}
+
+impl fmt::Display for TimeSlotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLabel { reason } => write!(f, "Invalid scheduled label: {}", reason),
+            Self::InvalidScheduledAt { value, reason } => {
+                write!(
+                    f,
+                    "Failed to parse scheduled_at time '{}': {}",
+                    value, reason
+                )
+            }
+            Self::ScheduledAtMismatch {
+                scheduled_at,
+                label_start,
+            } => write!(
+                f,
+                "scheduled_at time '{}' does not match the label's start {}",
+                scheduled_at, label_start
+            ),
+            Self::InPast => f.write_str(
+                "Time slot is entirely in the past. Cannot create a meeting for past times.",
+            ),
+        }
+    }
+}
+
+// What a webhook did to a meeting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeetingAction {
+    Created,     // New meeting for slots that were not booked before
+    Unchanged,   // Slots and subject match the existing meeting
+    Updated,     // Same time, new subject
+    Rescheduled, // Existing meeting moved to a new time
+    Cancelled,   // Slots were removed from the form
+    Rejected,    // Slots not booked because another reservation holds the room
+}
+
+// Outcome of booking the form's room for a meeting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomBooking {
+    NotAttempted, // Booking disabled, simulation, or no new meeting in this request
+    Booked,
+    Failed,     // Meeting kept without its room (stored as "会议室未预约")
+    RolledBack, // Meeting cancelled again because its room could not be booked
+}
+
+// What to do with a new meeting whose room cannot be booked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookingFailurePolicy {
+    #[default]
+    CancelMeeting, // Cancel the meeting so no meeting exists without its room
+    KeepMeeting, // Keep the meeting and record it as room not booked
+}
+
+impl BookingFailurePolicy {
+    // Parse the ROOM_BOOKING_FAILURE_POLICY setting ("cancel" or "keep")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "cancel" => Some(Self::CancelMeeting),
+            "keep" => Some(Self::KeepMeeting),
+            _ => None,
+        }
+    }
+}
+
+// What to do with slots whose room another entry already holds at that time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoomConflictPolicy {
+    #[default]
+    RejectSubmission, // Book nothing if any slot conflicts
+    SkipSlots, // Book the other slots and leave the conflicting ones out
+    Allow,     // Book everything and only report the conflicts
+}
+
+impl RoomConflictPolicy {
+    // Parse the ROOM_CONFLICT_POLICY setting ("reject", "skip" or "allow")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "reject" => Some(Self::RejectSubmission),
+            "skip" => Some(Self::SkipSlots),
+            "allow" => Some(Self::Allow),
+            _ => None,
+        }
+    }
+}
+
+// Another entry's active reservation that overlaps a slot in the same room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomConflict {
+    pub room_name: String,
+    pub scheduled_label: String, // The slot that conflicts
+    pub meeting_id: String,      // The reservation holding the room
+    pub reserved_label: String,  // That reservation's scheduled label
+    pub operator_name: String,
 }
 
 // Response structure for meeting results
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeetingResult {
     pub meeting_id: Option<String>,
     pub merged: bool,
     pub room_name: String,
     pub time_slots: Vec<String>,
     pub success: bool,
+    pub action: MeetingAction,
+    pub room_booking: RoomBooking,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>, // Why this meeting did not fully succeed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_url: Option<String>, // Join URL of a newly created meeting
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<RoomConflict>, // Other reservations overlapping these slots
 }
 
 // Response structure for webhook endpoint
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookResponse {
     pub success: bool,
     pub message: String,
@@ -31,6 +162,33 @@ pub struct WebhookResponse {
     pub meetings: Vec<MeetingResult>,
 }
 
+// Response body for a webhook rejected because of one of its time slots (400)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidTimeSlotResponse {
+    pub success: bool, // Always false
+    pub message: String,
+    pub number: i32, // The slot's number on the form
+    pub item_name: String,
+    pub scheduled_label: String,
+    pub error: TimeSlotError,
+}
+
+// Response body for a webhook rejected because slots break their room's booking policy (422)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingPolicyResponse {
+    pub success: bool, // Always false
+    pub message: String,
+    pub slots: Vec<SlotPolicyViolations>,
+}
+
+// Response body for a webhook rejected because slots fall on a holiday or into a blackout window (422)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlackoutResponse {
+    pub success: bool, // Always false
+    pub message: String,
+    pub slots: Vec<SlotBlackout>,
+}
+
 // Test data structure for mock responses
 #[derive(Debug, Serialize)]
 pub struct TestMeetingResponse {
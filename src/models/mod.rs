@@ -1,3 +1,8 @@
+pub mod blackout;
+pub mod booking_policy;
 pub mod common;
 pub mod form;
 pub mod meeting;
+pub mod reservation;
+pub mod scheduled_label;
+pub mod time_zone;
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use tracing::warn;
+
+// Status of a stored reservation
+//
+// Stored in the database as its Chinese label. Cancellation is recorded in
+// two phases, following the Tencent API calls:
+//
+//   Reserved -> CancelRequested -> RoomReleased -> Cancelled
+//                      \-> CancelFailed (release failed; retried from CancelRequested)
+//
+// A cancellation that fails permanently, or keeps failing, is given up as
+// CancelAbandoned; requesting the cancellation again starts over.
+//
+// Until a meeting is Cancelled, `cancelled_at` holds the time it was requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReservationStatus {
+    Pending,         // Accepted by the form service, not confirmed yet
+    Reserved,        // Meeting created and room booked
+    RoomNotBooked,   // Meeting exists but its room could not be booked
+    Rescheduled,     // Meeting moved to a new time by a form edit or update
+    Failed,          // The meeting could not be created
+    CancelRequested, // Cancellation requested; the room has not been released yet
+    RoomReleased,    // Room released (or never booked); the meeting is not cancelled yet
+    CancelFailed,    // Releasing the room failed; the cancellation will be retried
+    CancelAbandoned, // The cancellation failed for good and is no longer retried
+    Cancelled,       // Cancelled in Tencent Meeting
+}
+
+impl ReservationStatus {
+    pub const ALL: [Self; 10] = [
+        Self::Pending,
+        Self::Reserved,
+        Self::RoomNotBooked,
+        Self::Rescheduled,
+        Self::Failed,
+        Self::CancelRequested,
+        Self::RoomReleased,
+        Self::CancelFailed,
+        Self::CancelAbandoned,
+        Self::Cancelled,
+    ];
+
+    /// Label stored in the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "待确认",
+            Self::Reserved => "已预约",
+            Self::RoomNotBooked => "会议室未预约",
+            Self::Rescheduled => "已改期",
+            Self::Failed => "预约失败",
+            Self::CancelRequested => "取消中",
+            Self::RoomReleased => "取消中-会议室已释放",
+            Self::CancelFailed => "取消失败",
+            Self::CancelAbandoned => "取消已放弃",
+            Self::Cancelled => "已取消",
+        }
+    }
+
+    /// Name used in configuration (e.g. `FORM_STATUS_MAPPING`)
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Reserved => "reserved",
+            Self::RoomNotBooked => "room_not_booked",
+            Self::Rescheduled => "rescheduled",
+            Self::Failed => "failed",
+            Self::CancelRequested => "cancel_requested",
+            Self::RoomReleased => "room_released",
+            Self::CancelFailed => "cancel_failed",
+            Self::CancelAbandoned => "cancel_abandoned",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Parse a stored label or configuration name
+    ///
+    /// Also accepts the English "Reserved" and "Cancelled" written by older
+    /// versions.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value {
+            "Reserved" => return Some(Self::Reserved),
+            "Cancelled" => return Some(Self::Cancelled),
+            _ => {}
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value || status.name() == value)
+    }
+
+    /// Parse a status read from the database
+    ///
+    /// Older versions stored the form's raw status string, so values that are
+    /// not a known label are mapped like a form status without a mapping
+    /// (see `StatusMapping::map`) instead of making the row unreadable.
+    pub fn parse_stored(value: &str) -> Self {
+        Self::parse(value).unwrap_or_else(|| StatusMapping::default().map(value))
+    }
+
+    /// Whether the meeting holds its time slot
+    pub fn is_active(self) -> bool {
+        matches!(
+            self,
+            Self::Pending | Self::Reserved | Self::RoomNotBooked | Self::Rescheduled
+        )
+    }
+
+    /// Whether a cancellation has been requested but not confirmed yet
+    pub fn is_cancellation_pending(self) -> bool {
+        matches!(
+            self,
+            Self::CancelRequested | Self::RoomReleased | Self::CancelFailed
+        )
+    }
+
+    /// Whether the reservation is being or has been cancelled
+    pub fn is_cancellation(self) -> bool {
+        matches!(self, Self::Cancelled | Self::CancelAbandoned) || self.is_cancellation_pending()
+    }
+
+    /// Status after a cancellation is requested, or `None` if there is
+    /// nothing to cancel
+    ///
+    /// Meetings without a booked room skip the room release.
+    pub fn cancellation_request(self) -> Option<Self> {
+        match self {
+            Self::Pending
+            | Self::Reserved
+            | Self::Rescheduled
+            | Self::CancelFailed
+            | Self::CancelAbandoned => Some(Self::CancelRequested),
+            Self::RoomNotBooked | Self::RoomReleased => Some(Self::RoomReleased),
+            Self::Failed | Self::CancelRequested | Self::Cancelled => None,
+        }
+    }
+}
+
+impl fmt::Display for ReservationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ReservationStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ReservationStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).ok_or_else(|| {
+            serde::de::Error::custom(format!("unknown reservation status: {}", value))
+        })
+    }
+}
+
+/// Mapping from the form service's status strings to reservation statuses
+///
+/// The defaults cover "已预约"/"Reserved" and "已取消"/"Cancelled"; more can be
+/// added with `FORM_STATUS_MAPPING`. Strings without a mapping fall back to
+/// cancellation if they contain "取消", and to a reservation otherwise.
+#[derive(Debug, Clone)]
+pub struct StatusMapping {
+    statuses: HashMap<String, ReservationStatus>,
+}
+
+impl Default for StatusMapping {
+    fn default() -> Self {
+        let statuses = [
+            ("已预约", ReservationStatus::Reserved),
+            ("Reserved", ReservationStatus::Reserved),
+            ("已取消", ReservationStatus::Cancelled),
+            ("Cancelled", ReservationStatus::Cancelled),
+        ]
+        .into_iter()
+        .map(|(value, status)| (value.to_string(), status))
+        .collect();
+
+        Self { statuses }
+    }
+}
+
+impl StatusMapping {
+    /// Parse the `FORM_STATUS_MAPPING` setting on top of the defaults
+    ///
+    /// The setting is a comma-separated list of `form value=status` pairs,
+    /// e.g. `待审核=pending,已退订=cancelled`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut mapping = Self::default();
+
+        for pair in spec.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (value, status) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'form value=status', got '{}'", pair.trim()))?;
+            let status = ReservationStatus::parse(status)
+                .ok_or_else(|| format!("Unknown reservation status '{}'", status.trim()))?;
+            mapping.statuses.insert(value.trim().to_string(), status);
+        }
+
+        Ok(mapping)
+    }
+
+    /// Status for a form submission's status string
+    pub fn map(&self, form_status: &str) -> ReservationStatus {
+        if let Some(status) = self.statuses.get(form_status.trim()) {
+            return *status;
+        }
+
+        let status = if form_status.contains("取消") {
+            ReservationStatus::Cancelled
+        } else {
+            ReservationStatus::Reserved
+        };
+        warn!(
+            "No status mapping for form status '{}', treating it as {}",
+            form_status, status
+        );
+        status
+    }
+}
@@ -0,0 +1,216 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A slot's scheduled label, e.g. "2025-03-30 09:00-10:00"
+///
+/// Labels are local wall-clock time in the zone of the slot's room or form.
+/// The grammar is
+///
+/// ```text
+/// label = date " " time "-" [date " "] time
+/// date  = YYYY "-" M[M] "-" D[D]
+/// time  = H[H] ":" MM
+/// ```
+///
+/// Full-width digits, colons, dashes and spaces are read like their ASCII
+/// forms, and `~`, `～`, `–`, `—` and `至` also separate the start and end.
+/// Spaces around the separator are allowed. An end time of 24:00 is midnight
+/// at the end of the day. Without an end date, an end time before the start
+/// (e.g. "23:00-01:00") runs past midnight into the next day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledLabel {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+/// Why a scheduled label could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduledLabelError {
+    Empty,                            // Nothing but whitespace
+    InvalidDate { value: String },    // Not a YYYY-MM-DD calendar date
+    MissingTimeRange,                 // A date without a time range
+    InvalidTime { value: String },    // Not an HH:MM time of day
+    MissingEndTime,                   // A start time without an end
+    UnexpectedText { value: String }, // Anything after the time range
+    EmptyRange,                       // Ends when it starts
+    EndBeforeStart,                   // End date and time before the start
+}
+
+impl fmt::Display for ScheduledLabelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("scheduled label is empty"),
+            Self::InvalidDate { value } => {
+                write!(f, "'{}' is not a valid date (expected YYYY-MM-DD)", value)
+            }
+            Self::MissingTimeRange => {
+                f.write_str("no time range after the date (expected HH:MM-HH:MM)")
+            }
+            Self::InvalidTime { value } => {
+                write!(f, "'{}' is not a valid time (expected HH:MM)", value)
+            }
+            Self::MissingEndTime => f.write_str("time range has no end time"),
+            Self::UnexpectedText { value } => {
+                write!(f, "unexpected text '{}' after the time range", value)
+            }
+            Self::EmptyRange => f.write_str("time range starts and ends at the same time"),
+            Self::EndBeforeStart => f.write_str("time range ends before it starts"),
+        }
+    }
+}
+
+impl std::error::Error for ScheduledLabelError {}
+
+impl ScheduledLabel {
+    pub fn parse(label: &str) -> Result<Self, ScheduledLabelError> {
+        let label = normalize(label);
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(ScheduledLabelError::Empty);
+        }
+
+        let (date, range) = split_word(label);
+        let date = parse_date(date)?;
+        if range.is_empty() {
+            return Err(ScheduledLabelError::MissingTimeRange);
+        }
+
+        let midnight = date.and_time(NaiveTime::MIN);
+        let Some((start, end)) = range.split_once('-') else {
+            parse_time(range, false)?;
+            return Err(ScheduledLabelError::MissingEndTime);
+        };
+        let start = midnight + parse_time(start.trim(), false)?;
+
+        let end = end.trim();
+        if end.is_empty() {
+            return Err(ScheduledLabelError::MissingEndTime);
+        }
+
+        // The end is a time, or a date and a time
+        let (first, rest) = split_word(end);
+        let (end_date, end_time, rest) = if first.contains('-') && !first.contains(':') {
+            let (end_time, rest) = split_word(rest);
+            if end_time.is_empty() {
+                return Err(ScheduledLabelError::MissingEndTime);
+            }
+            (Some(parse_date(first)?), end_time, rest)
+        } else {
+            (None, first, rest)
+        };
+        if !rest.is_empty() {
+            return Err(ScheduledLabelError::UnexpectedText {
+                value: rest.to_string(),
+            });
+        }
+
+        let mut end =
+            end_date.unwrap_or(date).and_time(NaiveTime::MIN) + parse_time(end_time, true)?;
+
+        if end == start {
+            return Err(ScheduledLabelError::EmptyRange);
+        }
+        if end < start {
+            if end_date.is_some() {
+                return Err(ScheduledLabelError::EndBeforeStart);
+            }
+            end += Duration::days(1);
+        }
+
+        Ok(Self { start, end })
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Formats the label like the form service does, e.g. "2025-03-30 09:00-10:00"
+///
+/// The end date is only included if the range ends on a later day, other than
+/// at midnight right after the start day, e.g. "2025-03-30 23:00-2025-03-31 01:00".
+impl fmt::Display for ScheduledLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start_date = self.start.date();
+        let end_date = self.end.date();
+        let ends_at_midnight =
+            self.end.time() == NaiveTime::MIN && start_date.succ_opt() == Some(end_date);
+
+        write!(f, "{}-", self.start.format("%Y-%m-%d %H:%M"))?;
+        if end_date == start_date || ends_at_midnight {
+            write!(f, "{}", self.end.format("%H:%M"))
+        } else {
+            write!(f, "{}", self.end.format("%Y-%m-%d %H:%M"))
+        }
+    }
+}
+
+// Replace full-width and alternative characters with the ASCII ones the grammar uses
+fn normalize(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+            '：' => ':',
+            '－' | '‐' | '–' | '—' | '~' | '～' | '〜' | '至' => '-',
+            '\u{3000}' | '\t' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+// First whitespace-separated word and the trimmed rest
+fn split_word(text: &str) -> (&str, &str) {
+    match text.split_once(' ') {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+// YYYY-MM-DD, with one- or two-digit months and days
+fn parse_date(value: &str) -> Result<NaiveDate, ScheduledLabelError> {
+    let invalid = || ScheduledLabelError::InvalidDate {
+        value: value.to_string(),
+    };
+
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts[..] else {
+        return Err(invalid());
+    };
+    if !is_number(year, 4, 4) || !is_number(month, 1, 2) || !is_number(day, 1, 2) {
+        return Err(invalid());
+    }
+
+    NaiveDate::from_ymd_opt(
+        year.parse().map_err(|_| invalid())?,
+        month.parse().map_err(|_| invalid())?,
+        day.parse().map_err(|_| invalid())?,
+    )
+    .ok_or_else(invalid)
+}
+
+// H:MM or HH:MM, as the time since midnight; 24:00 is only allowed as an end time
+fn parse_time(value: &str, is_end: bool) -> Result<Duration, ScheduledLabelError> {
+    let invalid = || ScheduledLabelError::InvalidTime {
+        value: value.to_string(),
+    };
+
+    let (hour, minute) = value.split_once(':').ok_or_else(invalid)?;
+    if !is_number(hour, 1, 2) || !is_number(minute, 2, 2) {
+        return Err(invalid());
+    }
+    let hour: i64 = hour.parse().map_err(|_| invalid())?;
+    let minute: i64 = minute.parse().map_err(|_| invalid())?;
+
+    let is_end_of_day = is_end && hour == 24 && minute == 0;
+    if (hour >= 24 || minute >= 60) && !is_end_of_day {
+        return Err(invalid());
+    }
+    Ok(Duration::minutes(hour * 60 + minute))
+}
+
+fn is_number(value: &str, min_digits: usize, max_digits: usize) -> bool {
+    (min_digits..=max_digits).contains(&value.len()) && value.bytes().all(|b| b.is_ascii_digit())
+}
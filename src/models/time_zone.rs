@@ -0,0 +1,98 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// Zone of forms and rooms without a configured zone
+///
+/// This is the zone the form service was originally set up for, and the zone
+/// assumed for records stored before records had a zone.
+pub const DEFAULT_TIME_ZONE: Tz = chrono_tz::Asia::Shanghai;
+
+/// Parse an IANA time zone name, e.g. "Asia/Shanghai"
+pub fn parse_time_zone(name: &str) -> Result<Tz, String> {
+    name.trim()
+        .parse::<Tz>()
+        .map_err(|_| format!("Unknown time zone '{}'", name.trim()))
+}
+
+/// Resolve a local wall-clock time in a zone
+///
+/// A time that occurs twice (when clocks go back) resolves to the earlier
+/// instant. A time skipped when clocks go forward does not exist, so it is
+/// moved forward by the length of the gap, like a clock that was not adjusted.
+pub fn resolve_local_time(local: NaiveDateTime, zone: Tz) -> Option<DateTime<Utc>> {
+    if let Some(time) = zone.from_local_datetime(&local).earliest() {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    // Offsets on both sides of the gap give the instant past it
+    let before = zone
+        .from_local_datetime(&(local - chrono::Duration::hours(3)))
+        .earliest()?;
+    let after = zone
+        .from_local_datetime(&(local + chrono::Duration::hours(3)))
+        .latest()?;
+    let gap = chrono::Duration::hours(6) - (after - before);
+    zone.from_local_datetime(&(local + gap))
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Time zones of forms and rooms
+///
+/// Form slots, meeting times and stored wall-clock times use the zone of the
+/// slot's room if one is configured, else the zone of the form, else the
+/// default zone. Zones are set with `DEFAULT_TIME_ZONE` and
+/// `TIME_ZONE_MAPPING`.
+#[derive(Debug, Clone)]
+pub struct TimeZoneConfig {
+    default: Tz,
+    zones: HashMap<String, Tz>, // By room name or form name
+}
+
+impl Default for TimeZoneConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIME_ZONE)
+    }
+}
+
+impl TimeZoneConfig {
+    pub fn new(default: Tz) -> Self {
+        Self {
+            default,
+            zones: HashMap::new(),
+        }
+    }
+
+    /// Parse the `TIME_ZONE_MAPPING` setting on top of a default zone
+    ///
+    /// The setting is a comma-separated list of `room or form name=zone`
+    /// pairs, e.g. `成都会议室预约=Asia/Shanghai,London Room=Europe/London`.
+    pub fn parse(default: Tz, spec: &str) -> Result<Self, String> {
+        let mut config = Self::new(default);
+
+        for pair in spec.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (name, zone) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'name=time zone', got '{}'", pair.trim()))?;
+            config
+                .zones
+                .insert(name.trim().to_string(), parse_time_zone(zone)?);
+        }
+
+        Ok(config)
+    }
+
+    pub fn default_zone(&self) -> Tz {
+        self.default
+    }
+
+    /// Zone for a room booked through a form
+    pub fn zone_for(&self, form_name: &str, room_name: &str) -> Tz {
+        self.zones
+            .get(room_name)
+            .or_else(|| self.zones.get(form_name))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
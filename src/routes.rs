@@ -1,43 +1,96 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 use std::sync::Arc;
 use tracing::info;
 
+use crate::client::MeetingApi;
 use crate::handlers::api::{
-    book_rooms, cancel_meeting, create_meeting, handle_form_submission, list_meeting_rooms,
-    release_rooms, AppState,
+    book_rooms, cancel_meeting, create_meeting, get_meeting, handle_form_submission,
+    list_meeting_rooms, release_rooms, update_meeting, AppState,
+};
+use crate::handlers::blackouts::{
+    add_blackout_window, delete_blackout_window, get_blackout_calendar, list_blackout_calendars,
+    update_blackout_window,
+};
+use crate::handlers::calendar::{get_operator_calendar, get_room_calendar};
+use crate::handlers::jobs::get_job;
+use crate::handlers::reservations::{
+    export_reservations, get_reservations_by_token, list_reservations,
 };
 use crate::handlers::test::health_check;
 
-pub fn create_router(app_state: Arc<AppState>, is_production: bool) -> Router {
+/// Build the service's router
+///
+/// Production mode only exposes the health check and webhook, plus the
+/// calendar feeds if `calendar_feeds` is set; development mode exposes
+/// everything.
+pub fn create_router<C: MeetingApi + 'static>(
+    app_state: Arc<AppState<C>>,
+    is_production: bool,
+    calendar_feeds: bool,
+) -> Router {
     let mut router = Router::new();
 
     // Health check is always available
     let health_route = Router::new().route("/health", get(health_check));
     router = router.merge(health_route);
 
-    // Webhook endpoint is always available
-    let webhook_route =
-        Router::new().route("/webhook/form-submission", post(handle_form_submission));
+    // Webhook endpoint is always available, with status lookup for queued submissions
+    let webhook_route = Router::new()
+        .route(
+            "/webhook/form-submission",
+            post(handle_form_submission::<C>),
+        )
+        .route("/jobs/:job_id", get(get_job::<C>));
     router = router.merge(webhook_route);
 
+    // Calendar feeds are read by calendar apps, so production can opt in to them
+    if !is_production || calendar_feeds {
+        let calendar_routes = Router::new()
+            .route("/calendar/:file", get(get_room_calendar::<C>))
+            .route("/calendar/operators/:file", get(get_operator_calendar::<C>));
+        router = router.merge(calendar_routes);
+    }
+
     // Only add management API routes if not in production mode
     if !is_production {
         // Management API routes
         let api_routes = Router::new()
-            .route("/meeting-rooms", get(list_meeting_rooms))
-            .route("/meetings", post(create_meeting))
-            .route("/meetings/:meeting_id/cancel", post(cancel_meeting))
-            .route("/meetings/:meeting_id/book-rooms", post(book_rooms))
-            .route("/meetings/:meeting_id/release-rooms", post(release_rooms));
+            .route("/meeting-rooms", get(list_meeting_rooms::<C>))
+            .route("/meetings", post(create_meeting::<C>))
+            .route(
+                "/meetings/:meeting_id",
+                get(get_meeting::<C>).put(update_meeting::<C>),
+            )
+            .route("/meetings/:meeting_id/cancel", post(cancel_meeting::<C>))
+            .route("/meetings/:meeting_id/book-rooms", post(book_rooms::<C>))
+            .route(
+                "/meetings/:meeting_id/release-rooms",
+                post(release_rooms::<C>),
+            )
+            .route("/reservations", get(list_reservations::<C>))
+            .route("/reservations/export", get(export_reservations::<C>))
+            .route("/reservations/:token", get(get_reservations_by_token::<C>))
+            .route("/blackouts", get(list_blackout_calendars::<C>))
+            .route(
+                "/blackouts/:calendar",
+                get(get_blackout_calendar::<C>).post(add_blackout_window::<C>),
+            )
+            .route(
+                "/blackouts/:calendar/:window_id",
+                put(update_blackout_window::<C>).delete(delete_blackout_window::<C>),
+            );
 
         router = router.merge(api_routes);
 
         info!("Management API routes enabled - server running in development mode");
     } else {
         info!("Running in production mode - only webhook and health endpoints exposed");
+        if calendar_feeds {
+            info!("Calendar feeds enabled in production mode");
+        }
     }
 
     router.with_state(app_state)
@@ -0,0 +1,379 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tracing::error;
+
+use crate::models::form::FormSubmission;
+use crate::models::meeting::TimeSlot;
+use crate::models::reservation::ReservationStatus;
+use crate::services::database::{MeetingFilter, MeetingRecord, MeetingStore};
+use crate::services::export::{export_reservations, ExportFormat};
+use crate::services::reservations::ReservationQuery;
+
+// Writes waiting for the writer task before submitters have to wait
+const WRITE_QUEUE_CAPACITY: usize = 256;
+
+type WriteCommand = Box<dyn FnOnce(&dyn MeetingStore) + Send>;
+
+/// Async front end for a `MeetingStore`
+///
+/// Store calls do blocking file or database I/O, so they never run on the
+/// runtime's worker threads: reads run on the blocking thread pool, and
+/// writes are sent over a channel to a single writer task, which applies
+/// them one at a time in the order they were submitted. Async callers only
+/// await the channel and the blocking task; they never hold a thread-blocking
+/// lock.
+///
+/// Must be created inside a Tokio runtime.
+#[derive(Clone)]
+pub struct AsyncMeetingStore {
+    store: Arc<dyn MeetingStore>,
+    writer: mpsc::Sender<WriteCommand>,
+}
+
+impl AsyncMeetingStore {
+    pub fn new(store: Arc<dyn MeetingStore>) -> Self {
+        let (writer, mut commands) = mpsc::channel::<WriteCommand>(WRITE_QUEUE_CAPACITY);
+
+        // The writer stops once every handle has been dropped
+        let writer_store = Arc::clone(&store);
+        tokio::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                let store = Arc::clone(&writer_store);
+                if let Err(e) = tokio::task::spawn_blocking(move || command(&*store)).await {
+                    error!("Meeting store write failed: {}", e);
+                }
+            }
+        });
+
+        Self { store, writer }
+    }
+
+    /// Run a read on the blocking thread pool
+    pub async fn read<T, F>(&self, read: F) -> Result<T, String>
+    where
+        F: FnOnce(&dyn MeetingStore) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let store = Arc::clone(&self.store);
+        tokio::task::spawn_blocking(move || read(&*store))
+            .await
+            .map_err(|e| format!("Meeting store read failed: {}", e))?
+    }
+
+    /// Hand a write to the writer task and wait for its result
+    pub async fn write<T, F>(&self, write: F) -> Result<T, String>
+    where
+        F: FnOnce(&dyn MeetingStore) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        let command: WriteCommand = Box::new(move |store| {
+            // The caller may have stopped waiting; the write is applied anyway
+            let _ = reply.send(write(store));
+        });
+
+        self.writer
+            .send(command)
+            .await
+            .map_err(|_| "Meeting store writer has stopped".to_string())?;
+
+        response
+            .await
+            .map_err(|_| "Meeting store write was abandoned".to_string())?
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_meeting_with_time_slot(
+        &self,
+        form: &FormSubmission,
+        meeting_id: &str,
+        room_name: &str,
+        room_id: &str,
+        time_slot: &TimeSlot,
+        operator_name: &str,
+        operator_id: &str,
+    ) -> Result<(), String> {
+        let (form, time_slot) = (form.clone(), time_slot.clone());
+        let [meeting_id, room_name, room_id, operator_name, operator_id] =
+            owned([meeting_id, room_name, room_id, operator_name, operator_id]);
+        self.write(move |store| {
+            store.store_meeting_with_time_slot(
+                &form,
+                &meeting_id,
+                &room_name,
+                &room_id,
+                &time_slot,
+                &operator_name,
+                &operator_id,
+            )
+        })
+        .await
+    }
+
+    pub async fn store_meeting(
+        &self,
+        form: &FormSubmission,
+        meeting_id: &str,
+        room_name: &str,
+        room_id: &str,
+        operator_name: &str,
+        operator_id: &str,
+    ) -> Result<(), String> {
+        let form = form.clone();
+        let [meeting_id, room_name, room_id, operator_name, operator_id] =
+            owned([meeting_id, room_name, room_id, operator_name, operator_id]);
+        self.write(move |store| {
+            store.store_meeting(
+                &form,
+                &meeting_id,
+                &room_name,
+                &room_id,
+                &operator_name,
+                &operator_id,
+            )
+        })
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_merged_meeting(
+        &self,
+        form: &FormSubmission,
+        meeting_id: &str,
+        room_name: &str,
+        room_id: &str,
+        time_slots: &[TimeSlot],
+        operator_name: &str,
+        operator_id: &str,
+    ) -> Result<(), String> {
+        let (form, time_slots) = (form.clone(), time_slots.to_vec());
+        let [meeting_id, room_name, room_id, operator_name, operator_id] =
+            owned([meeting_id, room_name, room_id, operator_name, operator_id]);
+        self.write(move |store| {
+            store.store_merged_meeting(
+                &form,
+                &meeting_id,
+                &room_name,
+                &room_id,
+                &time_slots,
+                &operator_name,
+                &operator_id,
+            )
+        })
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_created_meeting(
+        &self,
+        form: &FormSubmission,
+        meeting_id: &str,
+        room_name: &str,
+        room_id: &str,
+        time_slots: &[TimeSlot],
+        join_url: &str,
+        operator_name: &str,
+        operator_id: &str,
+    ) -> Result<(), String> {
+        let (form, time_slots) = (form.clone(), time_slots.to_vec());
+        let [meeting_id, room_name, room_id, join_url, operator_name, operator_id] = owned([
+            meeting_id,
+            room_name,
+            room_id,
+            join_url,
+            operator_name,
+            operator_id,
+        ]);
+        self.write(move |store| {
+            store.store_created_meeting(
+                &form,
+                &meeting_id,
+                &room_name,
+                &room_id,
+                &time_slots,
+                &join_url,
+                &operator_name,
+                &operator_id,
+            )
+        })
+        .await
+    }
+
+    pub async fn request_cancellation(
+        &self,
+        entry_token: &str,
+    ) -> Result<Vec<(String, String)>, String> {
+        let entry_token = entry_token.to_string();
+        self.write(move |store| store.request_cancellation(&entry_token))
+            .await
+    }
+
+    pub async fn request_cancellation_by_id(
+        &self,
+        meeting_id: &str,
+    ) -> Result<Vec<(String, String)>, String> {
+        let meeting_id = meeting_id.to_string();
+        self.write(move |store| store.request_cancellation_by_id(&meeting_id))
+            .await
+    }
+
+    pub async fn mark_room_released(&self, meeting_id: &str) -> Result<usize, String> {
+        let meeting_id = meeting_id.to_string();
+        self.write(move |store| store.mark_room_released(&meeting_id))
+            .await
+    }
+
+    pub async fn mark_cancellation_failed(&self, meeting_id: &str) -> Result<usize, String> {
+        let meeting_id = meeting_id.to_string();
+        self.write(move |store| store.mark_cancellation_failed(&meeting_id))
+            .await
+    }
+
+    pub async fn mark_cancellation_abandoned(&self, meeting_id: &str) -> Result<usize, String> {
+        let meeting_id = meeting_id.to_string();
+        self.write(move |store| store.mark_cancellation_abandoned(&meeting_id))
+            .await
+    }
+
+    pub async fn finalize_cancellation(&self, meeting_id: &str) -> Result<usize, String> {
+        let meeting_id = meeting_id.to_string();
+        self.write(move |store| store.finalize_cancellation(&meeting_id))
+            .await
+    }
+
+    pub async fn reschedule_meeting(
+        &self,
+        meeting_id: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        scheduled_label: &str,
+        slot_labels: &[String],
+        subject: Option<&str>,
+    ) -> Result<usize, String> {
+        let [meeting_id, scheduled_label] = owned([meeting_id, scheduled_label]);
+        let slot_labels = slot_labels.to_vec();
+        let subject = subject.map(str::to_string);
+        self.write(move |store| {
+            store.reschedule_meeting(
+                &meeting_id,
+                start_time,
+                end_time,
+                &scheduled_label,
+                &slot_labels,
+                subject.as_deref(),
+            )
+        })
+        .await
+    }
+
+    pub async fn set_meeting_status(
+        &self,
+        meeting_id: &str,
+        status: ReservationStatus,
+    ) -> Result<usize, String> {
+        let meeting_id = meeting_id.to_string();
+        self.write(move |store| store.set_meeting_status(&meeting_id, status))
+            .await
+    }
+
+    pub async fn update_meeting_subject(
+        &self,
+        meeting_id: &str,
+        subject: &str,
+    ) -> Result<usize, String> {
+        let [meeting_id, subject] = owned([meeting_id, subject]);
+        self.write(move |store| store.update_meeting_subject(&meeting_id, &subject))
+            .await
+    }
+
+    pub async fn find_pending_cancellations(
+        &self,
+        requested_before: DateTime<Utc>,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        self.read(move |store| store.find_pending_cancellations(requested_before))
+            .await
+    }
+
+    pub async fn find_meeting_by_token(
+        &self,
+        entry_token: &str,
+    ) -> Result<Option<MeetingRecord>, String> {
+        let entry_token = entry_token.to_string();
+        self.read(move |store| store.find_meeting_by_token(&entry_token))
+            .await
+    }
+
+    pub async fn find_all_meetings_by_token(
+        &self,
+        entry_token: &str,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let entry_token = entry_token.to_string();
+        self.read(move |store| store.find_all_meetings_by_token(&entry_token))
+            .await
+    }
+
+    pub async fn find_meetings_by_id(
+        &self,
+        meeting_id: &str,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let meeting_id = meeting_id.to_string();
+        self.read(move |store| store.find_meetings(MeetingFilter::MeetingId(&meeting_id)))
+            .await
+    }
+
+    pub async fn find_reservations(
+        &self,
+        query: &ReservationQuery,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let query = query.clone();
+        self.read(move |store| store.find_reservations(&query))
+            .await
+    }
+
+    /// Export the reservations matching a query (rendered on the blocking
+    /// thread pool as well)
+    pub async fn export_reservations(
+        &self,
+        query: &ReservationQuery,
+        format: ExportFormat,
+    ) -> Result<Vec<u8>, String> {
+        let query = query.clone();
+        self.read(move |store| export_reservations(store, &query, format))
+            .await
+    }
+
+    pub async fn find_meetings_by_room_and_date(
+        &self,
+        room_name: &str,
+        date: &str,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let [room_name, date] = owned([room_name, date]);
+        self.read(move |store| store.find_meetings_by_room_and_date(&room_name, &date))
+            .await
+    }
+
+    pub async fn find_overlapping_meetings(
+        &self,
+        room_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        time_zone: Tz,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let room_name = room_name.to_string();
+        self.read(move |store| store.find_overlapping_meetings(&room_name, start, end, time_zone))
+            .await
+    }
+}
+
+impl<S: MeetingStore + 'static> From<Arc<S>> for AsyncMeetingStore {
+    fn from(store: Arc<S>) -> Self {
+        Self::new(store)
+    }
+}
+
+// Copy borrowed arguments so they can move to another thread
+fn owned<const N: usize>(values: [&str; N]) -> [String; N] {
+    values.map(str::to_string)
+}
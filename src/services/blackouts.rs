@@ -0,0 +1,284 @@
+use chrono::Utc;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+use crate::models::blackout::{
+    BlackoutCalendar, BlackoutWindow, BlackoutWindowRequest, SlotBlackout,
+};
+use crate::models::meeting::TimeSlot;
+use crate::services::time_slots::get_location_for_form;
+
+/// Holiday and blackout calendars of rooms and locations
+///
+/// Every calendar is a JSON file named after its room or location (see
+/// `BlackoutCalendar`) in the calendar directory. A slot is checked against
+/// the calendar of its room and that of its location. Edits made through the
+/// management API are written back to the file (temp file + rename).
+#[derive(Debug, Default)]
+pub struct BlackoutCalendars {
+    dir: Option<PathBuf>, // Not set for calendars only kept in memory
+    calendars: RwLock<BTreeMap<String, BlackoutCalendar>>, // By room or location name
+}
+
+impl BlackoutCalendars {
+    /// Load every calendar in `dir`; a missing directory has no calendars
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let mut calendars = BTreeMap::new();
+
+        if dir.exists() {
+            let entries = fs::read_dir(dir)
+                .map_err(|e| format!("Failed to read calendar directory: {}", e))?;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    warn!(
+                        "Skipping calendar file with invalid name {}",
+                        path.display()
+                    );
+                    continue;
+                };
+
+                let calendar = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| BlackoutCalendar::parse(&json))
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                calendars.insert(name.to_string(), calendar);
+            }
+        }
+
+        info!(
+            "Loaded {} blackout calendars from {}",
+            calendars.len(),
+            dir.display()
+        );
+
+        Ok(Self {
+            dir: Some(dir.to_path_buf()),
+            calendars: RwLock::new(calendars),
+        })
+    }
+
+    /// Calendars kept in memory only, e.g. for tests
+    pub fn from_calendars(calendars: BTreeMap<String, BlackoutCalendar>) -> Self {
+        Self {
+            dir: None,
+            calendars: RwLock::new(calendars),
+        }
+    }
+
+    pub fn list(&self) -> BTreeMap<String, BlackoutCalendar> {
+        self.read().clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<BlackoutCalendar> {
+        self.read().get(name).cloned()
+    }
+
+    /// Whether a local date is a working day for a room booked through a form
+    ///
+    /// The room's calendar is asked before its location's. `None` if neither
+    /// lists the date as a holiday or adjusted workday.
+    pub fn working_day(
+        &self,
+        form_name: &str,
+        room_name: &str,
+        date: chrono::NaiveDate,
+    ) -> Option<bool> {
+        let calendars = self.read();
+        calendar_names(form_name, room_name)
+            .iter()
+            .filter_map(|name| calendars.get(name))
+            .find_map(|calendar| calendar.working_day(date))
+    }
+
+    /// Slots that fall on a holiday or into a blackout window, in form order
+    pub fn find_blackouts(&self, form_name: &str, time_slots: &[TimeSlot]) -> Vec<SlotBlackout> {
+        let calendars = self.read();
+
+        time_slots
+            .iter()
+            .filter_map(|slot| {
+                let start = slot.start_time.with_timezone(&slot.time_zone).naive_local();
+                let end = slot.end_time.with_timezone(&slot.time_zone).naive_local();
+
+                let unavailable: Vec<_> = calendar_names(form_name, &slot.item_name)
+                    .iter()
+                    .filter_map(|name| Some((name, calendars.get(name)?)))
+                    .flat_map(|(name, calendar)| calendar.unavailability(name, start, end))
+                    .collect();
+                (!unavailable.is_empty()).then(|| SlotBlackout {
+                    number: slot.number,
+                    item_name: slot.item_name.clone(),
+                    scheduled_label: slot.scheduled_label.clone(),
+                    unavailable,
+                })
+            })
+            .collect()
+    }
+
+    /// Add a blackout window to a calendar, creating the calendar if needed
+    pub fn add_window(
+        &self,
+        calendar_name: &str,
+        request: BlackoutWindowRequest,
+    ) -> Result<BlackoutWindow, String> {
+        request.validate()?;
+        let window = BlackoutWindow {
+            id: format!(
+                "blackout-{}-{:08x}",
+                Utc::now().timestamp_millis(),
+                rand::thread_rng().gen::<u32>()
+            ),
+            start: request.start,
+            end: request.end,
+            reason: request.reason,
+        };
+
+        let mut calendars = self.write();
+        let mut calendar = calendars.get(calendar_name).cloned().unwrap_or_default();
+        calendar.blackouts.push(window.clone());
+        self.persist(calendar_name, &calendar)?;
+        calendars.insert(calendar_name.to_string(), calendar);
+
+        info!("Added blackout window {} to {}", window.id, calendar_name);
+
+        Ok(window)
+    }
+
+    /// Replace a blackout window; `None` if the calendar has no such window
+    pub fn update_window(
+        &self,
+        calendar_name: &str,
+        window_id: &str,
+        request: BlackoutWindowRequest,
+    ) -> Result<Option<BlackoutWindow>, String> {
+        request.validate()?;
+
+        let mut calendars = self.write();
+        let Some(mut calendar) = calendars.get(calendar_name).cloned() else {
+            return Ok(None);
+        };
+        let Some(window) = calendar
+            .blackouts
+            .iter_mut()
+            .find(|window| window.id == window_id)
+        else {
+            return Ok(None);
+        };
+        window.start = request.start;
+        window.end = request.end;
+        window.reason = request.reason;
+        let window = window.clone();
+
+        self.persist(calendar_name, &calendar)?;
+        calendars.insert(calendar_name.to_string(), calendar);
+
+        info!("Updated blackout window {} of {}", window_id, calendar_name);
+
+        Ok(Some(window))
+    }
+
+    /// Remove a blackout window; `false` if the calendar has no such window
+    pub fn remove_window(&self, calendar_name: &str, window_id: &str) -> Result<bool, String> {
+        let mut calendars = self.write();
+        let Some(mut calendar) = calendars.get(calendar_name).cloned() else {
+            return Ok(false);
+        };
+        let count = calendar.blackouts.len();
+        calendar.blackouts.retain(|window| window.id != window_id);
+        if calendar.blackouts.len() == count {
+            return Ok(false);
+        }
+
+        self.persist(calendar_name, &calendar)?;
+        calendars.insert(calendar_name.to_string(), calendar);
+
+        info!(
+            "Removed blackout window {} from {}",
+            window_id, calendar_name
+        );
+
+        Ok(true)
+    }
+
+    // Write a calendar back to its file, unless calendars are in memory only
+    fn persist(&self, calendar_name: &str, calendar: &BlackoutCalendar) -> Result<(), String> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create calendar directory: {}", e))?;
+
+        let path = dir.join(format!("{}.json", calendar_name));
+        let tmp_path = dir.join(format!("{}.json.tmp", calendar_name));
+
+        let json = serde_json::to_vec_pretty(calendar)
+            .map_err(|e| format!("Failed to serialize calendar: {}", e))?;
+
+        let mut file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create calendar file: {}", e))?;
+        file.write_all(&json)
+            .map_err(|e| format!("Failed to write calendar file: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync calendar file: {}", e))?;
+
+        fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace calendar file: {}", e))
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, BTreeMap<String, BlackoutCalendar>> {
+        self.calendars.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, BTreeMap<String, BlackoutCalendar>> {
+        self.calendars.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Whether a calendar name can be used as a file name
+///
+/// Names are room or location names; path separators and names starting
+/// with a dot are refused so a calendar can't be written outside the
+/// calendar directory.
+pub fn is_valid_calendar_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.starts_with('.') && !name.contains(['/', '\\', '\0'])
+}
+
+// Calendars that apply to a room booked through a form: the room's, then its location's
+fn calendar_names(form_name: &str, room_name: &str) -> Vec<String> {
+    let location = get_location_for_form(form_name, room_name);
+    if location == room_name {
+        vec![room_name.to_string()]
+    } else {
+        vec![room_name.to_string(), location]
+    }
+}
+
+// Load the blackout calendars
+//
+// Calendars are read from `BLACKOUT_CALENDAR_DIR`, or a `calendars`
+// directory next to the meetings database by default
+pub fn load_blackout_calendars() -> Arc<BlackoutCalendars> {
+    let dir = match std::env::var("BLACKOUT_CALENDAR_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => {
+            let db_path = std::env::var("MEETING_DATABASE_PATH")
+                .unwrap_or_else(|_| "/app/data/meetings.csv".to_string());
+            Path::new(&db_path)
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join("calendars")
+        }
+    };
+
+    let calendars = BlackoutCalendars::load(&dir)
+        .unwrap_or_else(|e| panic!("Invalid blackout calendar: {}", e));
+    Arc::new(calendars)
+}
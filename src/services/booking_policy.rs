@@ -0,0 +1,110 @@
+use chrono::{DateTime, Datelike, Utc};
+use std::collections::HashMap;
+
+use crate::models::booking_policy::{
+    BookingPolicy, BookingPolicyConfig, PolicyViolation, SlotPolicyViolations,
+};
+use crate::models::meeting::TimeSlot;
+use crate::services::blackouts::BlackoutCalendars;
+use crate::services::time_slots::find_mergeable_groups;
+
+/// Check a submission's slots against the policies of their rooms
+///
+/// Slots are grouped the way meetings are created (`find_mergeable_groups`),
+/// so the duration limit applies to the merged meeting: every slot of a group
+/// that is too long is reported. Holidays and adjusted workdays in the
+/// blackout calendars override the working days of the week. `now` is the
+/// time the advance window counts from. Returns the slots that break a rule,
+/// in form order.
+pub fn check_booking_policies(
+    config: &BookingPolicyConfig,
+    calendars: &BlackoutCalendars,
+    form_name: &str,
+    time_slots: &[TimeSlot],
+    now: DateTime<Utc>,
+) -> Vec<SlotPolicyViolations> {
+    // Length of the meeting each slot ends up in, by room and label
+    let mut meeting_minutes = HashMap::new();
+    for group in find_mergeable_groups(time_slots) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let minutes = (last.end_time - first.start_time).num_minutes();
+        for slot in &group {
+            meeting_minutes.insert(
+                (slot.item_name.clone(), slot.scheduled_label.clone()),
+                minutes,
+            );
+        }
+    }
+
+    time_slots
+        .iter()
+        .filter_map(|slot| {
+            let (policy_name, policy) = config.policy_for(form_name, &slot.item_name)?;
+            let minutes = meeting_minutes
+                .get(&(slot.item_name.clone(), slot.scheduled_label.clone()))
+                .copied()
+                .unwrap_or_else(|| (slot.end_time - slot.start_time).num_minutes());
+
+            let local_date = slot.start_time.with_timezone(&slot.time_zone).date_naive();
+            let working_day = calendars.working_day(form_name, &slot.item_name, local_date);
+
+            let violations = check_slot(policy, slot, minutes, working_day, now);
+            (!violations.is_empty()).then(|| SlotPolicyViolations {
+                number: slot.number,
+                item_name: slot.item_name.clone(),
+                scheduled_label: slot.scheduled_label.clone(),
+                policy: policy_name.to_string(),
+                violations,
+            })
+        })
+        .collect()
+}
+
+// Rules one slot breaks, given the length of the meeting it is merged into
+// and whether its calendar makes its date a working day or not
+fn check_slot(
+    policy: &BookingPolicy,
+    slot: &TimeSlot,
+    meeting_minutes: i64,
+    working_day: Option<bool>,
+    now: DateTime<Utc>,
+) -> Vec<PolicyViolation> {
+    let local_start = slot.start_time.with_timezone(&slot.time_zone);
+    let local_end = slot.end_time.with_timezone(&slot.time_zone);
+    let mut violations = Vec::new();
+
+    if let Some(working_hours) = &policy.working_hours {
+        let is_working_day =
+            working_day.unwrap_or_else(|| working_hours.days.contains(&local_start.weekday()));
+        let within = is_working_day
+            && local_start.date_naive() == local_end.date_naive()
+            && local_start.time() >= working_hours.start
+            && local_end.time() <= working_hours.end;
+        if !within {
+            violations.push(PolicyViolation::OutsideWorkingHours {
+                working_hours: working_hours.clone(),
+            });
+        }
+    }
+
+    if let Some(max_minutes) = policy.max_duration_minutes {
+        if meeting_minutes > max_minutes {
+            violations.push(PolicyViolation::TooLong {
+                minutes: meeting_minutes,
+                max_minutes,
+            });
+        }
+    }
+
+    if let Some(max_days) = policy.max_advance_days {
+        let today = now.with_timezone(&slot.time_zone).date_naive();
+        let days = (local_start.date_naive() - today).num_days();
+        if days > max_days {
+            violations.push(PolicyViolation::TooFarAhead { days, max_days });
+        }
+    }
+
+    violations
+}
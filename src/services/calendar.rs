@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::reservation::ReservationStatus;
+use crate::services::database::MeetingRecord;
+use crate::services::time_slots::get_location_for_form;
+
+pub const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+const PRODUCT_ID: &str = "-//Tencent Meeting Service//Reservations//ZH";
+
+// Domain part of event UIDs
+const UID_DOMAIN: &str = "tencent-meeting-service";
+
+// RFC 5545 limit on the length of a content line, in octets, without the line break
+const MAX_LINE_OCTETS: usize = 75;
+
+// UTC date-time form used for DTSTART, DTEND, DTSTAMP and LAST-MODIFIED
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Render records as an iCalendar (RFC 5545) feed
+///
+/// Every record with a valid start time becomes one event; failed records are
+/// left out. Cancelled meetings, and meetings whose cancellation is under way,
+/// stay in the feed with `STATUS:CANCELLED` so subscribed calendars remove them.
+///
+/// An event's UID only depends on the entry and meeting, so a rescheduled
+/// meeting updates its event instead of adding another; `SEQUENCE` and
+/// `LAST-MODIFIED` follow the record's revision.
+pub fn render_calendar(
+    name: &str,
+    records: &[MeetingRecord],
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+
+    for record in records {
+        if record.status != ReservationStatus::Failed {
+            lines.extend(event_lines(record, generated_at));
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+// Content lines of the VEVENT for a record, or nothing if it has no valid start time
+fn event_lines(record: &MeetingRecord, generated_at: DateTime<Utc>) -> Vec<String> {
+    let Ok(start) = DateTime::parse_from_rfc3339(&record.start_time) else {
+        return Vec::new();
+    };
+    let start = start.with_timezone(&Utc);
+    let end = DateTime::parse_from_rfc3339(&record.end_time)
+        .ok()
+        .map(|end| end.with_timezone(&Utc))
+        .filter(|end| *end > start);
+
+    let mut description = Vec::new();
+    if !record.meeting_id.is_empty() {
+        description.push(format!("会议号: {}", record.meeting_id));
+    }
+    if !record.join_url.is_empty() {
+        description.push(format!("入会链接: {}", record.join_url));
+    }
+    if !record.operator_name.is_empty() {
+        description.push(format!("预约人: {}", record.operator_name));
+    }
+
+    let status = match record.status {
+        status if status.is_cancellation() => "CANCELLED",
+        ReservationStatus::Pending => "TENTATIVE",
+        _ => "CONFIRMED",
+    };
+
+    // Last change of the record, or its creation if it never changed
+    let last_modified = [&record.updated_at, &record.created_at]
+        .into_iter()
+        .find_map(|time| DateTime::parse_from_rfc3339(time).ok());
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!(
+            "UID:{}-{}@{}",
+            record.entry_token, record.meeting_id, UID_DOMAIN
+        ),
+        format!("DTSTAMP:{}", generated_at.format(UTC_FORMAT)),
+        format!("SEQUENCE:{}", record.sequence),
+    ];
+    if let Some(last_modified) = last_modified {
+        lines.push(format!(
+            "LAST-MODIFIED:{}",
+            last_modified.with_timezone(&Utc).format(UTC_FORMAT)
+        ));
+    }
+    lines.push(format!("DTSTART:{}", start.format(UTC_FORMAT)));
+    if let Some(end) = end {
+        lines.push(format!("DTEND:{}", end.format(UTC_FORMAT)));
+    }
+    lines.push(format!("SUMMARY:{}", escape_text(&record.subject)));
+    lines.push(format!(
+        "LOCATION:{}",
+        escape_text(&get_location_for_form(&record.form_name, &record.room_name))
+    ));
+    if !description.is_empty() {
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_text(&description.join("\n"))
+        ));
+    }
+    if !record.join_url.is_empty() {
+        lines.push(format!("URL:{}", record.join_url));
+    }
+    lines.push(format!("STATUS:{}", status));
+    lines.push("END:VEVENT".to_string());
+
+    lines
+}
+
+// Escape a TEXT property value
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Fold a content line into lines of at most 75 octets
+//
+// Continuation lines start with a space, which counts towards their length.
+// Lines are only split between characters, never inside a UTF-8 sequence.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_octets = 0;
+
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+
+    folded
+}
@@ -1,17 +1,25 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use csv::{ReaderBuilder, StringRecord, WriterBuilder};
 use serde::{Deserialize, Serialize};
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tracing::{error, info, warn};
 
 use crate::models::form::FormSubmission;
 use crate::models::meeting::TimeSlot;
-use crate::services::time_slots::parse_time_slot;
+use crate::models::reservation::ReservationStatus;
+use crate::models::scheduled_label::ScheduledLabel;
+use crate::models::time_zone::{parse_time_zone, DEFAULT_TIME_ZONE};
+use crate::services::meeting_index::MeetingIndex;
+use crate::services::reservations::ReservationQuery;
+use crate::services::sqlite_store::SqliteMeetingStore;
+use crate::services::time_slots::{label_duration, merged_scheduled_label, parse_time_slot};
 
 // Record to be stored in CSV
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct MeetingRecord {
     // Form data
     pub entry_token: String,
@@ -19,9 +27,14 @@ pub struct MeetingRecord {
     pub form_name: String,
     pub subject: String,
     pub room_name: String,
-    pub scheduled_at: String, // ISO format
+    pub scheduled_at: String, // ISO format, same as start_time
     pub scheduled_label: String,
-    pub status: String, // "Reserved" or "Cancelled"
+    pub status: ReservationStatus, // Stored as its Chinese label
+
+    // Booked time range
+    pub start_time: String,       // ISO format
+    pub end_time: String,         // ISO format (empty if unknown)
+    pub slot_labels: Vec<String>, // Labels of the form slots the meeting covers, in order
 
     // Tencent Meeting data
     pub meeting_id: String,
@@ -32,58 +45,123 @@ pub struct MeetingRecord {
     // Operator information
     pub operator_name: String, // Name of the operator from form submission
     pub operator_id: String,   // ID of the operator used for API calls
-}
 
-// Database service for storing form submissions and meeting data
-pub struct DatabaseService {
-    csv_path: String,
-    file_mutex: Mutex<()>,
-}
+    pub join_url: String, // Tencent Meeting join URL (empty if unknown)
 
-impl DatabaseService {
-    pub fn new(csv_path: &str) -> Self {
-        // Create the CSV file if it doesn't exist with proper headers
-        if !Path::new(csv_path).exists() {
-            info!("Creating new meetings database file at {}", csv_path);
+    // Local time of the booked range
+    pub time_zone: String, // Zone of the room or form, e.g. "Asia/Shanghai"
+    pub local_start_time: String, // start_time as wall-clock time, with the zone's offset
+    pub local_end_time: String, // end_time as wall-clock time (empty if unknown)
 
-            let file = File::create(csv_path).unwrap_or_else(|e| {
-                error!("Failed to create database file: {}", e);
-                panic!("Failed to create database file: {}", e)
-            });
+    // Revision, e.g. for calendar feeds
+    pub sequence: u32, // Number of times the record was changed after it was stored
+    pub updated_at: String, // ISO format, time of the last change (empty if never changed)
+}
 
-            let mut writer = WriterBuilder::new().has_headers(true).from_writer(file);
-
-            if let Err(e) = writer.write_record([
-                "entry_token",
-                "form_id",
-                "form_name",
-                "subject",
-                "room_name",
-                "scheduled_at",
-                "scheduled_label",
-                "status",
-                "meeting_id",
-                "room_id",
-                "created_at",
-                "cancelled_at",
-                "operator_name",
-                "operator_id",
-            ]) {
-                error!("Failed to write headers: {}", e);
-                panic!("Failed to write headers: {}", e);
-            }
+impl MeetingRecord {
+    // Build a new record for a form submission
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        form: &FormSubmission,
+        meeting_id: &str,
+        room_name: &str,
+        room_id: &str,
+        start_time: String,
+        end_time: String,
+        scheduled_label: String,
+        slot_labels: Vec<String>,
+        time_zone: Tz,
+        operator_name: &str,
+        operator_id: &str,
+    ) -> Self {
+        let local_start_time = local_time(&start_time, time_zone);
+        let local_end_time = local_time(&end_time, time_zone);
 
-            if let Err(e) = writer.flush() {
-                error!("Failed to flush headers: {}", e);
-                panic!("Failed to flush headers: {}", e);
-            }
+        Self {
+            entry_token: form.entry.token.clone(),
+            form_id: form.form.clone(),
+            form_name: form.form_name.clone(),
+            subject: form.entry.field_8.clone(),
+            room_name: room_name.to_string(),
+            scheduled_at: start_time.clone(),
+            scheduled_label,
+            status: ReservationStatus::Reserved,
+            start_time,
+            end_time,
+            slot_labels,
+            meeting_id: meeting_id.to_string(),
+            room_id: room_id.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            cancelled_at: "".to_string(),
+            operator_name: operator_name.to_string(),
+            operator_id: operator_id.to_string(),
+            join_url: String::new(),
+            time_zone: time_zone.name().to_string(),
+            local_start_time,
+            local_end_time,
+            sequence: 0,
+            updated_at: String::new(),
         }
+    }
 
-        Self {
-            csv_path: csv_path.to_string(),
-            file_mutex: Mutex::new(()),
+    // Record that the stored record changed
+    fn touch(&mut self) {
+        self.sequence += 1;
+        self.updated_at = Utc::now().to_rfc3339();
+    }
+
+    /// Zone of the record's local times (the default zone if it is not known)
+    pub fn zone(&self) -> Tz {
+        parse_time_zone(&self.time_zone).unwrap_or(DEFAULT_TIME_ZONE)
+    }
+
+    /// Whether the record's time range overlaps `start..end`
+    ///
+    /// Ranges that only touch (one ends when the other starts) don't overlap.
+    /// A record without a valid time range overlaps nothing.
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        match (
+            DateTime::parse_from_rfc3339(&self.start_time),
+            DateTime::parse_from_rfc3339(&self.end_time),
+        ) {
+            (Ok(record_start), Ok(record_end)) => record_start < end && start < record_end,
+            _ => false,
         }
     }
+}
+
+/// Which records a `MeetingStore` query or update applies to
+#[derive(Debug, Clone, Copy)]
+pub enum MeetingFilter<'a> {
+    All,
+    Token(&'a str),
+    MeetingId(&'a str),
+    // Records for a room on a date (`YYYY-MM-DD`, the start of the scheduled label)
+    RoomOnDate { room_name: &'a str, date: &'a str },
+}
+
+/// Storage backend for meeting records
+///
+/// Backends implement three primitives: inserting a record, finding records
+/// and atomically updating records. Everything the handlers use is built on
+/// top of those, so the CSV and SQLite stores behave the same.
+pub trait MeetingStore: Send + Sync {
+    /// Insert a record unless an identical entry (same token, status and
+    /// scheduled label) already exists; returns whether it was inserted
+    fn insert_meeting(&self, record: &MeetingRecord) -> Result<bool, String>;
+
+    /// Find all records matching the filter, in insertion order
+    fn find_meetings(&self, filter: MeetingFilter<'_>) -> Result<Vec<MeetingRecord>, String>;
+
+    /// Apply `update` to every record matching the filter, as one atomic step
+    ///
+    /// `update` returns whether it changed the record. Returns the changed
+    /// records.
+    fn update_meetings(
+        &self,
+        filter: MeetingFilter<'_>,
+        update: &mut dyn FnMut(&mut MeetingRecord) -> bool,
+    ) -> Result<Vec<MeetingRecord>, String>;
 
     /// Store a meeting record using a specific time slot
     ///
@@ -93,7 +171,8 @@ impl DatabaseService {
     ///
     /// Used for individual (non-merged) meetings and distinguishes between
     /// multiple meetings with the same form token but different time slots.
-    pub fn store_meeting_with_time_slot(
+    #[allow(clippy::too_many_arguments)]
+    fn store_meeting_with_time_slot(
         &self,
         form: &FormSubmission,
         meeting_id: &str,
@@ -103,51 +182,21 @@ impl DatabaseService {
         operator_name: &str,
         operator_id: &str,
     ) -> Result<(), String> {
-        // Use the specific time slot's label
-        let scheduled_label = time_slot.scheduled_label.clone();
-
-        // Check if an identical meeting entry already exists (same token, status, and time)
-        let is_duplicate = self
-            .find_all_meetings_by_token(&form.entry.token)?
-            .into_iter()
-            .any(|record| {
-                record.status == form.entry.reservation_status_fsf_field
-                    && record.scheduled_label == scheduled_label
-            });
-
-        if is_duplicate {
-            // Entry with same token, status, and time already exists
-            info!("Meeting with token {} and status {} for time {} already exists, skipping insertion", 
-                form.entry.token, form.entry.reservation_status_fsf_field, scheduled_label);
-            return Ok(());
-        }
-
-        // Get current time in UTC
-        let now = Utc::now();
-
-        // Create a new record
-        let record = MeetingRecord {
-            entry_token: form.entry.token.clone(),
-            form_id: form.form.clone(),
-            form_name: form.form_name.clone(),
-            subject: form.entry.field_8.clone(),
-            room_name: room_name.to_string(),
-            scheduled_at: time_slot.start_time.to_rfc3339(),
-            scheduled_label,
-            status: form.entry.reservation_status_fsf_field.clone(),
-            meeting_id: meeting_id.to_string(),
-            room_id: room_id.to_string(),
-            created_at: now.to_rfc3339(),
-            cancelled_at: "".to_string(),
-            operator_name: operator_name.to_string(),
-            operator_id: operator_id.to_string(),
-        };
+        let record = time_slot_record(
+            form,
+            meeting_id,
+            room_name,
+            room_id,
+            time_slot,
+            operator_name,
+            operator_id,
+        );
 
-        self.write_record(&record)
+        insert_logged(self, &record)
     }
 
     // Store a meeting record (keep for backward compatibility)
-    pub fn store_meeting(
+    fn store_meeting(
         &self,
         form: &FormSubmission,
         meeting_id: &str,
@@ -158,8 +207,9 @@ impl DatabaseService {
     ) -> Result<(), String> {
         // Get the first available time slot from the form
         if let Some(first_slot) = form.entry.field_1.first() {
-            // Create a TimeSlot from the form field
-            let parsed_slot = parse_time_slot(first_slot)
+            // Create a TimeSlot from the form field (no zone is configured
+            // here, so the slot is read in the default zone)
+            let parsed_slot = parse_time_slot(first_slot, DEFAULT_TIME_ZONE)
                 .map_err(|e| format!("Failed to parse time slot: {}", e))?;
 
             // Call the new method with the parsed slot
@@ -174,28 +224,21 @@ impl DatabaseService {
             )
         } else {
             // Fallback to default behavior if no time slots available
-            // Get current time in UTC
-            let now = Utc::now();
-
-            // Create a new record
-            let record = MeetingRecord {
-                entry_token: form.entry.token.clone(),
-                form_id: form.form.clone(),
-                form_name: form.form_name.clone(),
-                subject: form.entry.field_8.clone(),
-                room_name: room_name.to_string(),
-                scheduled_at: now.to_rfc3339(),
-                scheduled_label: "No time specified".to_string(),
-                status: form.entry.reservation_status_fsf_field.clone(),
-                meeting_id: meeting_id.to_string(),
-                room_id: room_id.to_string(),
-                created_at: now.to_rfc3339(),
-                cancelled_at: "".to_string(),
-                operator_name: operator_name.to_string(),
-                operator_id: operator_id.to_string(),
-            };
+            let record = MeetingRecord::new(
+                form,
+                meeting_id,
+                room_name,
+                room_id,
+                Utc::now().to_rfc3339(),
+                String::new(),
+                "No time specified".to_string(),
+                Vec::new(),
+                DEFAULT_TIME_ZONE,
+                operator_name,
+                operator_id,
+            );
 
-            self.write_record(&record)
+            insert_logged(self, &record)
         }
     }
 
@@ -203,12 +246,14 @@ impl DatabaseService {
     ///
     /// This function creates a database record for a merged meeting composed of multiple
     /// contiguous time slots. It combines multiple time slots into a single meeting with:
-    /// - The start time of the first slot
+    /// - The start time of the first slot and the end time of the last
     /// - A combined scheduled label showing the full time range (e.g., "09:00-11:00")
+    /// - The labels of the original slots
     ///
     /// This is used when multiple adjacent time slots for the same room can be
     /// merged into a single, longer meeting.
-    pub fn store_merged_meeting(
+    #[allow(clippy::too_many_arguments)]
+    fn store_merged_meeting(
         &self,
         form: &FormSubmission,
         meeting_id: &str,
@@ -218,303 +263,997 @@ impl DatabaseService {
         operator_name: &str,
         operator_id: &str,
     ) -> Result<(), String> {
-        // Sort time slots to ensure correct ordering
-        let mut sorted_slots = time_slots.to_vec();
-        sorted_slots.sort_by_key(|slot| slot.start_time);
-
-        // Get the earliest start time and latest end time
-        let first_slot = sorted_slots.first().unwrap();
-        let last_slot = sorted_slots.last().unwrap();
-
-        // Create combined scheduled_label (e.g., "2025-04-01 09:00-11:00")
-        let first_time = first_slot
-            .scheduled_label
-            .split(' ')
-            .nth(1)
-            .unwrap_or("")
-            .split('-')
-            .next()
-            .unwrap_or("");
-        let last_time = last_slot
-            .scheduled_label
-            .split(' ')
-            .nth(1)
-            .unwrap_or("")
-            .split('-')
-            .nth(1)
-            .unwrap_or("");
-        let date = first_slot.scheduled_label.split(' ').next().unwrap_or("");
-        let combined_label = format!("{} {}-{}", date, first_time, last_time);
+        let record = merged_record(
+            form,
+            meeting_id,
+            room_name,
+            room_id,
+            time_slots,
+            operator_name,
+            operator_id,
+        )?;
+
+        insert_logged(self, &record)
+    }
 
-        // Check if an identical meeting entry already exists (same token, status, and time)
-        let is_duplicate = self
-            .find_all_meetings_by_token(&form.entry.token)?
+    /// Store a meeting just created in Tencent Meeting, with its join URL
+    ///
+    /// A single slot is stored like `store_meeting_with_time_slot`, several
+    /// like `store_merged_meeting`.
+    #[allow(clippy::too_many_arguments)]
+    fn store_created_meeting(
+        &self,
+        form: &FormSubmission,
+        meeting_id: &str,
+        room_name: &str,
+        room_id: &str,
+        time_slots: &[TimeSlot],
+        join_url: &str,
+        operator_name: &str,
+        operator_id: &str,
+    ) -> Result<(), String> {
+        let mut record = match time_slots {
+            [time_slot] => time_slot_record(
+                form,
+                meeting_id,
+                room_name,
+                room_id,
+                time_slot,
+                operator_name,
+                operator_id,
+            ),
+            _ => merged_record(
+                form,
+                meeting_id,
+                room_name,
+                room_id,
+                time_slots,
+                operator_name,
+                operator_id,
+            )?,
+        };
+        record.join_url = join_url.to_string();
+
+        insert_logged(self, &record)
+    }
+
+    /// Start cancelling all meetings with the given token
+    ///
+    /// This finds every reserved meeting that matches the provided token (like
+    /// when a user books multiple time slots with the same form submission) and
+    /// marks it as cancel-requested. Nothing is marked as cancelled yet: the
+    /// caller releases the room and cancels the meeting in Tencent Meeting, and
+    /// records each step with `mark_room_released` and `finalize_cancellation`.
+    /// Meetings whose earlier cancellation failed are picked up again.
+    ///
+    /// Returns a Vec of (meeting_id, room_id) pairs for all meetings to cancel.
+    /// Meetings with no booked room to release (never booked, or already
+    /// released) are returned with an empty room ID.
+    fn request_cancellation(&self, entry_token: &str) -> Result<Vec<(String, String)>, String> {
+        let requested = request_cancellation_where(self, MeetingFilter::Token(entry_token))?;
+
+        if requested.is_empty() {
+            warn!("No active meetings found for token: {}", entry_token);
+        } else {
+            info!(
+                "Requested cancellation of {} meetings with token {}",
+                requested.len(),
+                entry_token
+            );
+        }
+
+        Ok(requested)
+    }
+
+    /// Start cancelling a single meeting by its meeting ID
+    ///
+    /// Used when a form edit removes some of its slots; other meetings with the
+    /// same token stay active. Returns the same pairs as `request_cancellation`.
+    fn request_cancellation_by_id(
+        &self,
+        meeting_id: &str,
+    ) -> Result<Vec<(String, String)>, String> {
+        request_cancellation_where(self, MeetingFilter::MeetingId(meeting_id))
+    }
+
+    /// Record that a meeting's room was released in Tencent Meeting
+    fn mark_room_released(&self, meeting_id: &str) -> Result<usize, String> {
+        self.set_meeting_status(meeting_id, ReservationStatus::RoomReleased)
+    }
+
+    /// Record that a cancellation failed before the room was released
+    fn mark_cancellation_failed(&self, meeting_id: &str) -> Result<usize, String> {
+        self.set_meeting_status(meeting_id, ReservationStatus::CancelFailed)
+    }
+
+    /// Record that a cancellation is no longer retried
+    fn mark_cancellation_abandoned(&self, meeting_id: &str) -> Result<usize, String> {
+        self.set_meeting_status(meeting_id, ReservationStatus::CancelAbandoned)
+    }
+
+    /// Mark a meeting as cancelled once Tencent Meeting has confirmed it
+    fn finalize_cancellation(&self, meeting_id: &str) -> Result<usize, String> {
+        let now = Utc::now().to_rfc3339();
+        let updated = update_records(self, meeting_id, true, &|record| {
+            record.status = ReservationStatus::Cancelled;
+            record.cancelled_at = now.clone();
+        })?;
+
+        info!(
+            "Marked meeting {} as cancelled ({} records)",
+            meeting_id, updated
+        );
+
+        Ok(updated)
+    }
+
+    /// Find cancellations that were requested before `requested_before` and
+    /// have not been confirmed yet
+    ///
+    /// Used by the cancellation sweeper; requests newer than that are assumed
+    /// to still be in progress.
+    fn find_pending_cancellations(
+        &self,
+        requested_before: DateTime<Utc>,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        Ok(self
+            .find_meetings(MeetingFilter::All)?
             .into_iter()
-            .any(|record| {
-                record.status == form.entry.reservation_status_fsf_field
-                    && record.scheduled_label == combined_label
-            });
+            .filter(|record| record.status.is_cancellation_pending())
+            .filter(|record| {
+                // cancelled_at holds the request time until the cancellation is final;
+                // retry records with an unreadable time rather than leaving them stuck
+                DateTime::parse_from_rfc3339(&record.cancelled_at)
+                    .map(|at| at <= requested_before)
+                    .unwrap_or(true)
+            })
+            .collect())
+    }
 
-        if is_duplicate {
-            // Entry with same token, status, and time already exists
-            info!("Meeting with token {} and status {} for time {} already exists, skipping insertion", 
-                form.entry.token, form.entry.reservation_status_fsf_field, combined_label);
-            return Ok(());
+    /// Record a new time for a meeting that was moved in Tencent Meeting
+    ///
+    /// Updates the time range, `scheduled_label` and slot labels on every
+    /// active record with the given meeting ID (and the subject, if it
+    /// changed), and marks reserved records as rescheduled. Cancelled records
+    /// are left untouched. Returns the number of records updated.
+    fn reschedule_meeting(
+        &self,
+        meeting_id: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        scheduled_label: &str,
+        slot_labels: &[String],
+        subject: Option<&str>,
+    ) -> Result<usize, String> {
+        let updated = update_records(self, meeting_id, false, &|record| {
+            record.scheduled_at = start_time.to_rfc3339();
+            record.start_time = start_time.to_rfc3339();
+            record.end_time = end_time.to_rfc3339();
+            record.local_start_time = local_time(&record.start_time, record.zone());
+            record.local_end_time = local_time(&record.end_time, record.zone());
+            record.scheduled_label = scheduled_label.to_string();
+            record.slot_labels = slot_labels.to_vec();
+            if record.status == ReservationStatus::Reserved {
+                record.status = ReservationStatus::Rescheduled;
+            }
+            if let Some(subject) = subject {
+                record.subject = subject.to_string();
+            }
+        })?;
+
+        if updated == 0 {
+            warn!("No active meeting records found for meeting {}", meeting_id);
+        } else {
+            info!(
+                "Rescheduled meeting {} to {} ({} records)",
+                meeting_id, scheduled_label, updated
+            );
         }
 
-        // Get current time in UTC
-        let now = Utc::now();
+        Ok(updated)
+    }
+
+    /// Set the status of every record with the given meeting ID that is not
+    /// cancelled yet (including cancellations in progress)
+    fn set_meeting_status(
+        &self,
+        meeting_id: &str,
+        status: ReservationStatus,
+    ) -> Result<usize, String> {
+        let updated = update_records(self, meeting_id, true, &|record| {
+            record.status = status;
+        })?;
 
         info!(
-            "Creating merged meeting record with label: {}",
-            combined_label
+            "Set status of meeting {} to {} ({} records)",
+            meeting_id, status, updated
         );
 
-        // Create a new record
-        let record = MeetingRecord {
-            entry_token: form.entry.token.clone(),
-            form_id: form.form.clone(),
-            form_name: form.form_name.clone(),
-            subject: form.entry.field_8.clone(),
-            room_name: room_name.to_string(),
-            scheduled_at: first_slot.start_time.to_rfc3339(),
-            scheduled_label: combined_label,
-            status: form.entry.reservation_status_fsf_field.clone(),
-            meeting_id: meeting_id.to_string(),
-            room_id: room_id.to_string(),
-            created_at: now.to_rfc3339(),
-            cancelled_at: "".to_string(),
-            operator_name: operator_name.to_string(),
-            operator_id: operator_id.to_string(),
-        };
+        Ok(updated)
+    }
+
+    /// Record a new subject for a meeting without changing its time
+    fn update_meeting_subject(&self, meeting_id: &str, subject: &str) -> Result<usize, String> {
+        update_records(self, meeting_id, false, &|record| {
+            record.subject = subject.to_string();
+        })
+    }
 
-        self.write_record(&record)
+    // Find a meeting by entry token (active/not cancelled)
+    fn find_meeting_by_token(&self, entry_token: &str) -> Result<Option<MeetingRecord>, String> {
+        Ok(self
+            .find_meetings(MeetingFilter::Token(entry_token))?
+            .into_iter()
+            .find(|record| record.status != ReservationStatus::Cancelled))
+    }
+
+    // Find a meeting by entry token and specific status
+    fn find_meeting_by_token_and_status(
+        &self,
+        entry_token: &str,
+        status: ReservationStatus,
+    ) -> Result<Option<MeetingRecord>, String> {
+        Ok(self
+            .find_meetings(MeetingFilter::Token(entry_token))?
+            .into_iter()
+            .find(|record| record.status == status))
     }
 
-    /// Update meeting status to cancelled for all meetings with the given token
+    /// Find all meetings with a specific token
     ///
-    /// This function finds and cancels all meetings that match the provided token.
-    /// It supports canceling multiple meetings at once (like when a user books
-    /// multiple time slots with the same form submission).
+    /// Unlike find_meeting_by_token which returns only one meeting,
+    /// this function returns all meetings that match the given token.
+    /// This is useful for operations that need to process multiple
+    /// meetings associated with the same form submission, such as
+    /// deduplication checks and batch cancellations.
+    fn find_all_meetings_by_token(&self, entry_token: &str) -> Result<Vec<MeetingRecord>, String> {
+        self.find_meetings(MeetingFilter::Token(entry_token))
+    }
+
+    /// Find all records matching a reservation query, in insertion order
+    fn find_reservations(&self, query: &ReservationQuery) -> Result<Vec<MeetingRecord>, String> {
+        // Use the room + date index when the query allows it
+        let records = match (&query.room, query.single_date()) {
+            (Some(room_name), Some(date)) => self.find_meetings(MeetingFilter::RoomOnDate {
+                room_name,
+                date: &date.format("%Y-%m-%d").to_string(),
+            })?,
+            _ => self.find_meetings(MeetingFilter::All)?,
+        };
+
+        Ok(records
+            .into_iter()
+            .filter(|record| query.matches(record))
+            .collect())
+    }
+
+    /// Find all records for a room on a date (`YYYY-MM-DD`)
+    fn find_meetings_by_room_and_date(
+        &self,
+        room_name: &str,
+        date: &str,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        self.find_meetings(MeetingFilter::RoomOnDate { room_name, date })
+    }
+
+    /// Find the active records for a room whose time range overlaps `start..end`
     ///
-    /// Returns a Vec of (meeting_id, room_id) pairs for all cancelled meetings,
-    /// which allows the caller to handle multiple cancellations appropriately.
-    pub fn cancel_meeting(&self, entry_token: &str) -> Result<Vec<(String, String)>, String> {
-        let _lock = self
-            .file_mutex
-            .lock()
-            .map_err(|e| format!("Failed to acquire mutex: {}", e))?;
+    /// Uses the room + date index for every date the range touches in
+    /// `time_zone`, and the date before, so records that run past midnight
+    /// are found as well.
+    fn find_overlapping_meetings(
+        &self,
+        room_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        time_zone: Tz,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let start_date = start.with_timezone(&time_zone).date_naive();
+        let end_date = end.with_timezone(&time_zone).date_naive();
+
+        let mut overlapping = Vec::new();
+        for date in start_date
+            .pred_opt()
+            .unwrap_or(start_date)
+            .iter_days()
+            .take_while(|date| *date <= end_date)
+        {
+            let records = self.find_meetings(MeetingFilter::RoomOnDate {
+                room_name,
+                date: &date.format("%Y-%m-%d").to_string(),
+            })?;
+            overlapping.extend(
+                records
+                    .into_iter()
+                    .filter(|record| record.status.is_active() && record.overlaps(start, end)),
+            );
+        }
 
-        // Load all records
-        let file = File::open(&self.csv_path)
-            .map_err(|e| format!("Failed to open database file: {}", e))?;
+        Ok(overlapping)
+    }
+}
 
-        let mut reader = ReaderBuilder::new().has_headers(true).from_reader(file);
+// Record for a meeting covering a single time slot, with that slot's label
+fn time_slot_record(
+    form: &FormSubmission,
+    meeting_id: &str,
+    room_name: &str,
+    room_id: &str,
+    time_slot: &TimeSlot,
+    operator_name: &str,
+    operator_id: &str,
+) -> MeetingRecord {
+    MeetingRecord::new(
+        form,
+        meeting_id,
+        room_name,
+        room_id,
+        time_slot.start_time.to_rfc3339(),
+        time_slot.end_time.to_rfc3339(),
+        time_slot.scheduled_label.clone(),
+        vec![time_slot.scheduled_label.clone()],
+        time_slot.time_zone,
+        operator_name,
+        operator_id,
+    )
+}
 
-        let headers = reader
-            .headers()
-            .map_err(|e| format!("Failed to read headers: {}", e))?
-            .clone();
+// Record for a meeting covering several contiguous time slots, with the
+// combined label and the range from the first slot's start to the last's end
+fn merged_record(
+    form: &FormSubmission,
+    meeting_id: &str,
+    room_name: &str,
+    room_id: &str,
+    time_slots: &[TimeSlot],
+    operator_name: &str,
+    operator_id: &str,
+) -> Result<MeetingRecord, String> {
+    // Sort time slots to ensure correct ordering
+    let mut sorted_slots = time_slots.to_vec();
+    sorted_slots.sort_by_key(|slot| slot.start_time);
+
+    // Get the earliest start time and the latest end time
+    let (first_slot, last_slot) = match (sorted_slots.first(), sorted_slots.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err("No time slots to store".to_string()),
+    };
+
+    // Create combined scheduled_label (e.g., "2025-04-01 09:00-11:00")
+    let combined_label = merged_scheduled_label(&sorted_slots);
+
+    info!(
+        "Creating merged meeting record with label: {}",
+        combined_label
+    );
+
+    Ok(MeetingRecord::new(
+        form,
+        meeting_id,
+        room_name,
+        room_id,
+        first_slot.start_time.to_rfc3339(),
+        last_slot.end_time.to_rfc3339(),
+        combined_label,
+        sorted_slots
+            .iter()
+            .map(|slot| slot.scheduled_label.clone())
+            .collect(),
+        first_slot.time_zone,
+        operator_name,
+        operator_id,
+    ))
+}
 
-        let mut records: Vec<StringRecord> = Vec::new();
-        let mut cancelled_meetings = Vec::new();
+// Insert a record, logging whether it was stored or skipped as a duplicate
+fn insert_logged<S: MeetingStore + ?Sized>(
+    store: &S,
+    record: &MeetingRecord,
+) -> Result<(), String> {
+    if store.insert_meeting(record)? {
+        info!(
+            "Stored meeting record for token {} with ID {}",
+            record.entry_token, record.meeting_id
+        );
+    } else {
+        // Entry with same token, status, and time already exists
+        info!(
+            "Meeting with token {} and status {} for time {} already exists, skipping insertion",
+            record.entry_token, record.status, record.scheduled_label
+        );
+    }
 
-        // Find the record with matching token and copy all records
-        for result in reader.records() {
-            let record = result.map_err(|e| format!("Failed to read record: {}", e))?;
+    Ok(())
+}
+
+// Mark matching meetings as cancel-requested and return what is left to do
+fn request_cancellation_where<S: MeetingStore + ?Sized>(
+    store: &S,
+    filter: MeetingFilter<'_>,
+) -> Result<Vec<(String, String)>, String> {
+    let now = Utc::now().to_rfc3339();
+    let requested = store.update_meetings(filter, &mut |record| {
+        let Some(next_status) = record.status.cancellation_request() else {
+            return false;
+        };
+
+        info!(
+            "Requested cancellation of meeting {} (was {})",
+            record.meeting_id, record.status
+        );
+        record.status = next_status;
+        record.cancelled_at = now.clone(); // Request time, replaced once the cancellation is final
+        record.touch();
+        true
+    })?;
+
+    Ok(requested
+        .into_iter()
+        .map(|record| {
+            let room_id = if record.status == ReservationStatus::RoomReleased {
+                String::new()
+            } else {
+                record.room_id
+            };
+            (record.meeting_id, room_id)
+        })
+        .collect())
+}
+
+// Apply `update` to every active record with the given meeting ID, optionally
+// including cancellations in progress
+fn update_records<S: MeetingStore + ?Sized>(
+    store: &S,
+    meeting_id: &str,
+    include_pending_cancellations: bool,
+    update: &dyn Fn(&mut MeetingRecord),
+) -> Result<usize, String> {
+    let updated = store.update_meetings(MeetingFilter::MeetingId(meeting_id), &mut |record| {
+        let pending = include_pending_cancellations && record.status.is_cancellation_pending();
+        if !record.status.is_active() && !pending {
+            return false;
+        }
+        update(record);
+        record.touch();
+        true
+    })?;
+
+    Ok(updated.len())
+}
+
+// Slot labels are stored in one column, separated by '|'
+pub(crate) fn join_slot_labels(slot_labels: &[String]) -> String {
+    slot_labels.join("|")
+}
+
+pub(crate) fn split_slot_labels(value: &str) -> Vec<String> {
+    value
+        .split('|')
+        .filter(|label| !label.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Time range (start, end) of a record written before records had one
+//
+// The start is `scheduled_at`; the end adds the duration of the label's time
+// range, and is empty if the label has none.
+pub(crate) fn legacy_time_range(scheduled_at: &str, scheduled_label: &str) -> (String, String) {
+    let end_time = DateTime::parse_from_rfc3339(scheduled_at)
+        .ok()
+        .zip(label_duration(scheduled_label))
+        .map(|(start, duration)| (start.with_timezone(&Utc) + duration).to_rfc3339())
+        .unwrap_or_default();
+
+    (scheduled_at.to_string(), end_time)
+}
+
+// Canonical form of a label stored by an older version (e.g.
+// "2025-04-01 09:00-10:00" for "2025-4-1 9:00-10:00"), or `None` if it
+// already is canonical or does not parse
+pub(crate) fn canonical_label(label: &str) -> Option<String> {
+    let canonical = ScheduledLabel::parse(label).ok()?.to_string();
+    (canonical != label).then_some(canonical)
+}
+
+// A record's scheduled label and slot labels in canonical form, or `None` if
+// they already are
+pub(crate) fn canonical_labels(
+    scheduled_label: &str,
+    slot_labels: &str,
+) -> Option<(String, String)> {
+    let slots = split_slot_labels(slot_labels);
+    let canonical_slots: Vec<String> = slots
+        .iter()
+        .map(|label| canonical_label(label).unwrap_or_else(|| label.clone()))
+        .collect();
+    let label = canonical_label(scheduled_label);
+
+    if label.is_none() && canonical_slots == slots {
+        return None;
+    }
+    Some((
+        label.unwrap_or_else(|| scheduled_label.to_string()),
+        join_slot_labels(&canonical_slots),
+    ))
+}
+
+// Stored RFC 3339 time as wall-clock time in a zone, with the zone's offset
+// (e.g. "2025-04-01T09:00:00+08:00"); empty if it is not a valid time
+pub(crate) fn local_time(value: &str, time_zone: Tz) -> String {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&time_zone).to_rfc3339())
+        .unwrap_or_default()
+}
 
-            // Check if this is the record to update - not yet cancelled
-            let is_reserved = record.get(7) == Some("Reserved") || record.get(7) == Some("已预约");
-            let is_cancelled =
-                record.get(7) == Some("Cancelled") || record.get(7) == Some("已取消");
-            if record.get(0) == Some(entry_token) && is_reserved && !is_cancelled {
-                // Get meeting_id and room_id for cancellation
-                if let (Some(meeting_id), Some(room_id)) = (record.get(8), record.get(9)) {
-                    // Add to the list of cancelled meetings
-                    cancelled_meetings.push((meeting_id.to_string(), room_id.to_string()));
+/// CSV-backed meeting store
+///
+/// Keeps every record in a single CSV file guarded by a process-local mutex,
+/// so the file must not be shared between processes (use the SQLite store
+/// for that). The file is loaded into an indexed in-memory table at startup;
+/// lookups are served from memory and the file is only written to persist
+/// changes.
+pub struct DatabaseService {
+    csv_path: String,
+    table: Mutex<CsvTable>,
+}
 
-                    let now = Utc::now().to_rfc3339();
+// In-memory copy of the CSV file
+#[derive(Default)]
+struct CsvTable {
+    headers: StringRecord,
+    rows: Vec<StringRecord>, // As stored, including rows that can't be parsed
+    index: MeetingIndex,     // Parsed records, at the same positions as `rows`
+}
+
+// Columns of the current meetings.csv layout, in order
+const CSV_COLUMNS: [&str; 23] = [
+    "entry_token",
+    "form_id",
+    "form_name",
+    "subject",
+    "room_name",
+    "scheduled_at",
+    "scheduled_label",
+    "status",
+    "meeting_id",
+    "room_id",
+    "created_at",
+    "cancelled_at",
+    "operator_name",
+    "operator_id",
+    "start_time",
+    "end_time",
+    "slot_labels",
+    "join_url",
+    "time_zone",
+    "local_start_time",
+    "local_end_time",
+    "sequence",
+    "updated_at",
+];
+
+// Number of columns in schema version 1, the original layout
+const SCHEMA_V1_COLUMNS: usize = 12;
+
+// A step from one meetings.csv layout to the next
+struct SchemaMigration {
+    version: u32, // Schema version after this step
+    added_columns: &'static [&'static str],
+    fill: fn(&StringRecord) -> Vec<String>, // Values of the added columns for an older row
+}
 
-                    // Build a new record with updated status and cancelled_at time
-                    let mut updated_vec: Vec<String> = record.iter().map(String::from).collect();
-                    updated_vec[7] = "已取消".to_string(); // Update status to Chinese "Cancelled"
-                    updated_vec[11] = now.clone(); // Update cancelled_at
+// Every schema change since version 1, oldest first
+//
+// To add a column:
+//   1. Append it to `CSV_COLUMNS` (columns are only ever added at the end)
+//   2. Add a step here with the next version, naming the new column and
+//      how older rows get a value for it
+//   3. Update `MeetingRecord`, the row conversions and the SQLite table
+//
+// On startup `DatabaseService::new` detects the version from the header,
+// backs the file up and applies the missing steps in order.
+const SCHEMA_MIGRATIONS: &[SchemaMigration] = &[
+    // Version 2: operator who created the meeting (unknown for older rows)
+    SchemaMigration {
+        version: 2,
+        added_columns: &["operator_name", "operator_id"],
+        fill: |_| vec![String::new(), String::new()],
+    },
+    // Version 3: booked time range and slot labels, derived from the
+    // scheduled time and label (slot labels are unknown for older rows)
+    SchemaMigration {
+        version: 3,
+        added_columns: &["start_time", "end_time", "slot_labels"],
+        fill: |row| {
+            let (start_time, end_time) =
+                legacy_time_range(row.get(5).unwrap_or(""), row.get(6).unwrap_or(""));
+            vec![start_time, end_time, String::new()]
+        },
+    },
+    // Version 4: join URL of the meeting (unknown for older rows)
+    SchemaMigration {
+        version: 4,
+        added_columns: &["join_url"],
+        fill: |_| vec![String::new()],
+    },
+    // Version 5: time zone and local wall-clock times; older rows were
+    // booked in the default zone
+    SchemaMigration {
+        version: 5,
+        added_columns: &["time_zone", "local_start_time", "local_end_time"],
+        fill: |row| {
+            vec![
+                DEFAULT_TIME_ZONE.name().to_string(),
+                local_time(row.get(14).unwrap_or(""), DEFAULT_TIME_ZONE),
+                local_time(row.get(15).unwrap_or(""), DEFAULT_TIME_ZONE),
+            ]
+        },
+    },
+    // Version 6: revision of the record; older rows count as unchanged
+    SchemaMigration {
+        version: 6,
+        added_columns: &["sequence", "updated_at"],
+        fill: |_| vec!["0".to_string(), String::new()],
+    },
+];
+
+// Schema version of a meetings.csv header, or None if it is not a known layout
+fn detect_schema_version(headers: &StringRecord) -> Option<u32> {
+    let headers: Vec<&str> = headers.iter().collect();
+
+    let mut version = 1;
+    let mut columns = SCHEMA_V1_COLUMNS;
+    if headers == CSV_COLUMNS[..columns] {
+        return Some(version);
+    }
 
-                    let updated = StringRecord::from(updated_vec);
+    for migration in SCHEMA_MIGRATIONS {
+        version = migration.version;
+        columns += migration.added_columns.len();
+        if headers == CSV_COLUMNS[..columns] {
+            return Some(version);
+        }
+    }
 
-                    info!(
-                        "Marked meeting {} as cancelled for token {}",
-                        meeting_id, entry_token
-                    );
+    None
+}
 
-                    records.push(updated);
+// Length of `data` up to the end of its last complete CSV row
+//
+// A row is complete when it parses and has as many fields as the header; a
+// complete last row may lack its trailing newline. Only a broken row at the
+// very end is treated as torn; if complete rows follow it, the damage is not
+// from an interrupted append and the whole file is kept.
+fn complete_rows_len(data: &[u8]) -> usize {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data);
+
+    let mut record = StringRecord::new();
+    let mut field_count = None;
+    let mut valid_len = 0;
+    let mut broken = false;
+
+    loop {
+        match reader.read_record(&mut record) {
+            Ok(true) => {
+                let end = reader.position().byte() as usize;
+                let expected = *field_count.get_or_insert(record.len());
+                if record.len() == expected {
+                    valid_len = end;
+                    broken = false;
                 } else {
-                    // Record is missing meeting_id or room_id, push it unchanged
-                    records.push(record);
+                    broken = true;
                 }
-            } else {
-                records.push(record);
+            }
+            Ok(false) => break,
+            Err(_) => {
+                broken = true;
+                break;
             }
         }
+    }
 
-        // If no matching record found
-        if cancelled_meetings.is_empty() {
-            warn!("No active meetings found for token: {}", entry_token);
-            return Ok(Vec::new());
-        }
+    if broken {
+        valid_len
+    } else {
+        data.len()
+    }
+}
 
-        // Write all records back (overwrite the file)
-        let file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(&self.csv_path)
-            .map_err(|e| format!("Failed to open database file for writing: {}", e))?;
+fn current_schema_version() -> u32 {
+    SCHEMA_MIGRATIONS
+        .last()
+        .map_or(1, |migration| migration.version)
+}
 
-        let mut writer = WriterBuilder::new().has_headers(false).from_writer(file);
+impl DatabaseService {
+    pub fn new(csv_path: &str) -> Self {
+        let service = Self {
+            csv_path: csv_path.to_string(),
+            table: Mutex::new(CsvTable::default()),
+        };
 
-        // Write headers
-        writer
-            .write_record(&headers)
-            .map_err(|e| format!("Failed to write headers: {}", e))?;
+        // Create the CSV file with proper headers if it doesn't exist (or is empty)
+        let is_empty = std::fs::metadata(csv_path)
+            .map(|metadata| metadata.len() == 0)
+            .unwrap_or(true);
+        if is_empty {
+            info!("Creating new meetings database file at {}", csv_path);
 
-        // Write all records
-        for record in records {
-            writer
-                .write_record(&record)
-                .map_err(|e| format!("Failed to write record: {}", e))?;
+            if let Err(e) = service.rewrite_records(&StringRecord::from(CSV_COLUMNS.to_vec()), &[])
+            {
+                error!("Failed to create database file: {}", e);
+                panic!("Failed to create database file: {}", e);
+            }
+        } else {
+            if let Err(e) = service.recover() {
+                error!("Failed to recover database file: {}", e);
+                panic!("Failed to recover database file: {}", e);
+            }
+            if let Err(e) = service.migrate_schema() {
+                error!("Failed to migrate database file: {}", e);
+                panic!("Failed to migrate database file: {}", e);
+            }
+            if let Err(e) = service.canonicalize_labels() {
+                error!("Failed to migrate scheduled labels: {}", e);
+                panic!("Failed to migrate scheduled labels: {}", e);
+            }
         }
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush writer: {}", e))?;
+        if let Err(e) = service.load() {
+            error!("Failed to load database file: {}", e);
+            panic!("Failed to load database file: {}", e);
+        }
+
+        service
+    }
+
+    // Read the CSV file into the in-memory table and build its index
+    fn load(&self) -> Result<(), String> {
+        let mut table = self.lock_table()?;
+
+        let (headers, rows) = self.read_all()?;
+        let mut index = MeetingIndex::new();
+        for row in &rows {
+            match self.string_record_to_meeting_record(row) {
+                Ok(record) => index.push(Some(record)),
+                Err(e) => {
+                    warn!("Skipping unreadable record {:?}: {}", row, e);
+                    index.push(None);
+                }
+            }
+        }
 
         info!(
-            "Cancelled {} meetings with token {}",
-            cancelled_meetings.len(),
-            entry_token
+            "Loaded {} meeting records from {}",
+            rows.len(),
+            self.csv_path
         );
 
-        // Return all cancelled meeting IDs and room IDs
-        Ok(cancelled_meetings)
+        *table = CsvTable {
+            headers,
+            rows,
+            index,
+        };
+        Ok(())
+    }
+
+    // Path of the temp file used while rewriting the CSV file
+    fn temp_path(&self) -> String {
+        format!("{}.tmp", self.csv_path)
     }
 
-    // Find a meeting by entry token (active/not cancelled)
-    pub fn find_meeting_by_token(
-        &self,
-        entry_token: &str,
-    ) -> Result<Option<MeetingRecord>, String> {
-        let _lock = self
-            .file_mutex
-            .lock()
-            .map_err(|e| format!("Failed to acquire mutex: {}", e))?;
+    // Repair the damage a crash can leave behind
+    //
+    // A leftover temp file is an interrupted rewrite; the rename never
+    // happened, so the CSV file itself is intact and the temp file is dropped.
+    // A row cut off by a crash during an append is removed from the end of
+    // the file, after the file is copied to `<path>.torn.bak` so the row can
+    // be recovered by hand. A complete last row that only lacks its newline
+    // is kept and terminated.
+    fn recover(&self) -> Result<(), String> {
+        let _lock = self.lock_table()?;
+
+        let temp_path = self.temp_path();
+        if Path::new(&temp_path).exists() {
+            warn!("Removing unfinished rewrite {}", temp_path);
+            fs::remove_file(&temp_path)
+                .map_err(|e| format!("Failed to remove temp file: {}", e))?;
+        }
 
-        let file = File::open(&self.csv_path)
-            .map_err(|e| format!("Failed to open database file: {}", e))?;
+        let data =
+            fs::read(&self.csv_path).map_err(|e| format!("Failed to read database file: {}", e))?;
+        let valid_len = complete_rows_len(&data);
+        if valid_len == data.len() {
+            if data.is_empty() || data.ends_with(b"\n") {
+                return Ok(());
+            }
 
-        let mut reader = ReaderBuilder::new().has_headers(true).from_reader(file);
+            // Terminate the last row, so the next append starts a new one
+            warn!("Adding the missing newline at the end of {}", self.csv_path);
+            let mut file = OpenOptions::new()
+                .append(true)
+                .open(&self.csv_path)
+                .map_err(|e| format!("Failed to open database file for writing: {}", e))?;
+            file.write_all(b"\n")
+                .map_err(|e| format!("Failed to write database file: {}", e))?;
+            return file
+                .sync_all()
+                .map_err(|e| format!("Failed to sync database file: {}", e));
+        }
+
+        let backup_path = format!("{}.torn.bak", self.csv_path);
+        fs::copy(&self.csv_path, &backup_path)
+            .map_err(|e| format!("Failed to back up database file: {}", e))?;
+
+        warn!(
+            "Discarding torn row at the end of {} (original kept at {}): {:?}",
+            self.csv_path,
+            backup_path,
+            String::from_utf8_lossy(&data[valid_len..])
+        );
+
+        let file = OpenOptions::new()
+            .write(true)
+            .open(&self.csv_path)
+            .map_err(|e| format!("Failed to open database file for writing: {}", e))?;
+        file.set_len(valid_len as u64)
+            .map_err(|e| format!("Failed to truncate database file: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync database file: {}", e))
+    }
+
+    // Bring an existing CSV file up to the current schema
+    //
+    // The original file is copied to `<path>.v<version>.bak` before it is
+    // rewritten, and each step fills in its added columns for every row.
+    fn migrate_schema(&self) -> Result<(), String> {
+        let _lock = self.lock_table()?;
+
+        let (headers, records) = self.read_all()?;
+        let version = detect_schema_version(&headers).ok_or_else(|| {
+            format!(
+                "Unrecognized meetings.csv header: {}",
+                headers.iter().collect::<Vec<_>>().join(",")
+            )
+        })?;
+
+        let target = current_schema_version();
+        if version == target {
+            return Ok(());
+        }
 
-        // Find the record with matching token
-        for result in reader.records() {
-            let record = result.map_err(|e| format!("Failed to read record: {}", e))?;
+        let backup_path = format!("{}.v{}.bak", self.csv_path, version);
+        std::fs::copy(&self.csv_path, &backup_path)
+            .map_err(|e| format!("Failed to back up database file: {}", e))?;
 
-            // For finding a meeting, we check if it's either reserved or not cancelled
-            let is_cancelled =
-                record.get(7) == Some("Cancelled") || record.get(7) == Some("已取消");
-            if record.get(0) == Some(entry_token) && !is_cancelled {
-                // Convert to MeetingRecord
-                return Ok(Some(self.string_record_to_meeting_record(&record)?));
+        info!(
+            "Migrating {} from schema version {} to {} ({} records, backup at {})",
+            self.csv_path,
+            version,
+            target,
+            records.len(),
+            backup_path
+        );
+
+        let mut migrated = records;
+        for migration in SCHEMA_MIGRATIONS.iter().filter(|m| m.version > version) {
+            for row in migrated.iter_mut() {
+                for value in (migration.fill)(row) {
+                    row.push_field(&value);
+                }
             }
         }
 
-        // No matching record found
-        Ok(None)
+        self.rewrite_records(&StringRecord::from(CSV_COLUMNS.to_vec()), &migrated)
     }
 
-    // Find a meeting by entry token and specific status
-    pub fn find_meeting_by_token_and_status(
-        &self,
-        entry_token: &str,
-        status: &str,
-    ) -> Result<Option<MeetingRecord>, String> {
-        let _lock = self
-            .file_mutex
-            .lock()
-            .map_err(|e| format!("Failed to acquire mutex: {}", e))?;
+    // Rewrite labels stored by older versions in their canonical form
+    //
+    // Older versions stored labels as the form sent them; lookups by date and
+    // form edits compare labels literally and would miss those rows.
+    fn canonicalize_labels(&self) -> Result<(), String> {
+        let _lock = self.lock_table()?;
+
+        let (headers, mut records) = self.read_all()?;
+        let mut changed = 0;
+        for row in records.iter_mut() {
+            // Columns 6 and 16 are scheduled_label and slot_labels
+            let (Some(label), Some(slots)) = (row.get(6), row.get(16)) else {
+                continue;
+            };
+            let Some((label, slots)) = canonical_labels(label, slots) else {
+                continue;
+            };
 
-        // If file doesn't exist, return None early
-        if !Path::new(&self.csv_path).exists() {
-            return Ok(None);
+            let mut fields: Vec<String> = row.iter().map(str::to_string).collect();
+            fields[6] = label;
+            fields[16] = slots;
+            *row = StringRecord::from(fields);
+            changed += 1;
+        }
+
+        if changed == 0 {
+            return Ok(());
         }
 
-        // Open file with better error handling
+        info!(
+            "Rewriting {} scheduled labels in {} in canonical form",
+            changed, self.csv_path
+        );
+        self.rewrite_records(&headers, &records)
+    }
+
+    // Read the headers and all records from the CSV file
+    //
+    // Callers must hold the table lock
+    fn read_all(&self) -> Result<(StringRecord, Vec<StringRecord>), String> {
         let file = File::open(&self.csv_path)
             .map_err(|e| format!("Failed to open database file: {}", e))?;
 
         let mut reader = ReaderBuilder::new().has_headers(true).from_reader(file);
 
-        // Find the record with matching token and status
-        for result in reader.records() {
-            let record = result.map_err(|e| format!("Failed to read record: {}", e))?;
-
-            // Use Option combinators to check if we have a match
-            let token_matches = record.get(0).map_or(false, |val| val == entry_token);
-            let status_matches = record.get(7).map_or(false, |val| val == status);
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Failed to read headers: {}", e))?
+            .clone();
 
-            if token_matches && status_matches {
-                // Convert to MeetingRecord and return early
-                return self.string_record_to_meeting_record(&record).map(Some);
-            }
-        }
+        let records = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read record: {}", e))?;
 
-        // No matching record found
-        Ok(None)
+        Ok((headers, records))
     }
 
-    /// Find all meetings with a specific token
-    ///
-    /// Unlike find_meeting_by_token which returns only one meeting,
-    /// this function returns all meetings that match the given token.
-    /// This is useful for operations that need to process multiple
-    /// meetings associated with the same form submission, such as
-    /// deduplication checks and batch cancellations.
-    pub fn find_all_meetings_by_token(
+    // Replace the CSV file with the given headers and records
+    //
+    // The new contents are written to a temp file, synced and renamed over
+    // the CSV file, so a crash leaves either the old or the new file, never a
+    // partial one. Callers must hold the table lock
+    fn rewrite_records(
         &self,
-        entry_token: &str,
-    ) -> Result<Vec<MeetingRecord>, String> {
-        let _lock = self
-            .file_mutex
-            .lock()
-            .map_err(|e| format!("Failed to acquire mutex: {}", e))?;
-
-        // If file doesn't exist yet, return empty vector
-        if !Path::new(&self.csv_path).exists() {
-            return Ok(Vec::new());
-        }
-
-        let file = match File::open(&self.csv_path) {
-            Ok(file) => file,
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    return Ok(Vec::new());
-                }
-                return Err(format!("Failed to open database file: {}", e));
-            }
-        };
+        headers: &StringRecord,
+        records: &[StringRecord],
+    ) -> Result<(), String> {
+        let temp_path = self.temp_path();
+        let file = File::create(&temp_path)
+            .map_err(|e| format!("Failed to create temp database file: {}", e))?;
 
-        let mut reader = ReaderBuilder::new().has_headers(true).from_reader(file);
-        let mut meetings = Vec::new();
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(file);
 
-        // Find all records with matching token
-        for result in reader.records() {
-            let record = result.map_err(|e| format!("Failed to read record: {}", e))?;
+        // Write headers
+        writer
+            .write_record(headers)
+            .map_err(|e| format!("Failed to write headers: {}", e))?;
 
-            if record.get(0) == Some(entry_token) {
-                // Convert to MeetingRecord and add to results
-                meetings.push(self.string_record_to_meeting_record(&record)?);
-            }
+        // Write all records
+        for record in records {
+            writer
+                .write_record(record)
+                .map_err(|e| format!("Failed to write record: {}", e))?;
         }
 
-        Ok(meetings)
+        let file = writer
+            .into_inner()
+            .map_err(|e| format!("Failed to flush writer: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync temp database file: {}", e))?;
+
+        fs::rename(&temp_path, &self.csv_path)
+            .map_err(|e| format!("Failed to replace database file: {}", e))?;
+
+        // Persist the rename itself
+        let dir = Path::new(&self.csv_path)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| format!("Failed to sync database directory: {}", e))
     }
 
     // Convert StringRecord to MeetingRecord
@@ -534,10 +1273,11 @@ impl DatabaseService {
         };
 
         // Ensure record has at least the required fields
-        if record.len() < 14 {
+        if record.len() < CSV_COLUMNS.len() {
             return Err(format!(
-                "Invalid record length: {}. Expected at least 14 fields.",
-                record.len()
+                "Invalid record length: {}. Expected at least {} fields.",
+                record.len(),
+                CSV_COLUMNS.len()
             ));
         }
 
@@ -550,56 +1290,199 @@ impl DatabaseService {
             room_name: get_field(4, "room_name")?,
             scheduled_at: get_field(5, "scheduled_at")?,
             scheduled_label: get_field(6, "scheduled_label")?,
-            status: get_field(7, "status")?,
+            status: ReservationStatus::parse_stored(&get_field(7, "status")?),
             meeting_id: get_field(8, "meeting_id")?,
             room_id: get_field(9, "room_id")?,
             created_at: get_field(10, "created_at")?,
             cancelled_at: get_field(11, "cancelled_at")?,
             operator_name: get_field(12, "operator_name")?,
             operator_id: get_field(13, "operator_id")?,
+            start_time: get_field(14, "start_time")?,
+            end_time: get_field(15, "end_time")?,
+            slot_labels: split_slot_labels(&get_field(16, "slot_labels")?),
+            join_url: get_field(17, "join_url")?,
+            time_zone: get_field(18, "time_zone")?,
+            local_start_time: get_field(19, "local_start_time")?,
+            local_end_time: get_field(20, "local_end_time")?,
+            sequence: {
+                let sequence = get_field(21, "sequence")?;
+                sequence
+                    .parse()
+                    .map_err(|_| format!("Invalid sequence: {}", sequence))?
+            },
+            updated_at: get_field(22, "updated_at")?,
         })
     }
 
-    // Helper to write a record to the CSV
+    // Convert MeetingRecord back to a CSV row, in column order
+    fn meeting_record_to_string_record(record: &MeetingRecord) -> StringRecord {
+        let slot_labels = join_slot_labels(&record.slot_labels);
+        let sequence = record.sequence.to_string();
+        StringRecord::from(vec![
+            record.entry_token.as_str(),
+            record.form_id.as_str(),
+            record.form_name.as_str(),
+            record.subject.as_str(),
+            record.room_name.as_str(),
+            record.scheduled_at.as_str(),
+            record.scheduled_label.as_str(),
+            record.status.as_str(),
+            record.meeting_id.as_str(),
+            record.room_id.as_str(),
+            record.created_at.as_str(),
+            record.cancelled_at.as_str(),
+            record.operator_name.as_str(),
+            record.operator_id.as_str(),
+            record.start_time.as_str(),
+            record.end_time.as_str(),
+            slot_labels.as_str(),
+            record.join_url.as_str(),
+            record.time_zone.as_str(),
+            record.local_start_time.as_str(),
+            record.local_end_time.as_str(),
+            sequence.as_str(),
+            record.updated_at.as_str(),
+        ])
+    }
+
+    // Helper to append a record to the CSV
+    //
+    // The row is written with a single call and synced; if the write fails
+    // the file is cut back to its previous length, so no torn row is left
+    // behind. Callers must hold the table lock
     fn write_record(&self, record: &MeetingRecord) -> Result<(), String> {
-        let _lock = self
-            .file_mutex
-            .lock()
-            .map_err(|e| format!("Failed to acquire mutex: {}", e))?;
+        let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        writer
+            .write_record(&Self::meeting_record_to_string_record(record))
+            .map_err(|e| format!("Failed to serialize record: {}", e))?;
+        let row = writer
+            .into_inner()
+            .map_err(|e| format!("Failed to serialize record: {}", e))?;
 
-        let file = OpenOptions::new()
+        let mut file = OpenOptions::new()
             .append(true)
             .open(&self.csv_path)
             .map_err(|e| format!("Failed to open database file: {}", e))?;
+        let original_len = file
+            .metadata()
+            .map_err(|e| format!("Failed to read database file metadata: {}", e))?
+            .len();
+
+        if let Err(e) = file.write_all(&row).and_then(|_| file.sync_data()) {
+            if let Err(truncate_err) = file.set_len(original_len) {
+                error!("Failed to remove partial record: {}", truncate_err);
+            }
+            return Err(format!("Failed to write record: {}", e));
+        }
 
-        let mut writer = WriterBuilder::new().has_headers(false).from_writer(file);
+        Ok(())
+    }
 
-        writer
-            .serialize(record)
-            .map_err(|e| format!("Failed to serialize record: {}", e))?;
+    fn lock_table(&self) -> Result<std::sync::MutexGuard<'_, CsvTable>, String> {
+        self.table
+            .lock()
+            .map_err(|e| format!("Failed to acquire mutex: {}", e))
+    }
+}
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush writer: {}", e))?;
+impl MeetingStore for DatabaseService {
+    fn insert_meeting(&self, record: &MeetingRecord) -> Result<bool, String> {
+        let mut table = self.lock_table()?;
 
-        info!(
-            "Stored meeting record for token {} with ID {}",
-            record.entry_token, record.meeting_id
-        );
+        // Check if an identical meeting entry already exists (same token, status, and time)
+        let is_duplicate = table
+            .index
+            .find(MeetingFilter::Token(&record.entry_token))
+            .iter()
+            .any(|existing| {
+                existing.status == record.status
+                    && existing.scheduled_label == record.scheduled_label
+            });
+        if is_duplicate {
+            return Ok(false);
+        }
 
-        Ok(())
+        self.write_record(record)?;
+
+        table
+            .rows
+            .push(Self::meeting_record_to_string_record(record));
+        table.index.push(Some(record.clone()));
+        Ok(true)
+    }
+
+    fn find_meetings(&self, filter: MeetingFilter<'_>) -> Result<Vec<MeetingRecord>, String> {
+        Ok(self.lock_table()?.index.find(filter))
+    }
+
+    fn update_meetings(
+        &self,
+        filter: MeetingFilter<'_>,
+        update: &mut dyn FnMut(&mut MeetingRecord) -> bool,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let mut table = self.lock_table()?;
+
+        let mut changed = Vec::new();
+        for position in table.index.positions(filter) {
+            if let Some(record) = table.index.get(position) {
+                let mut record = record.clone();
+                if update(&mut record) {
+                    changed.push((position, record));
+                }
+            }
+        }
+
+        if changed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Write all records back (replace the file) before changing the
+        // in-memory table, so it never gets ahead of the file
+        let mut rows = table.rows.clone();
+        for (position, record) in &changed {
+            rows[*position] = Self::meeting_record_to_string_record(record);
+        }
+        self.rewrite_records(&table.headers, &rows)?;
+
+        table.rows = rows;
+        Ok(changed
+            .into_iter()
+            .map(|(position, record)| {
+                table.index.replace(position, record.clone());
+                record
+            })
+            .collect())
     }
 }
 
-// Create a singleton database service
-pub fn create_database_service() -> Arc<DatabaseService> {
+// Create the meeting store selected by configuration
+//
+// `MEETING_STORE_BACKEND` is `csv` (default) or `sqlite`; `MEETING_DATABASE_PATH`
+// overrides the file location. Use SQLite when other processes share the
+// database; only one service process may handle webhooks for it, since
+// submissions and rooms are locked within the process.
+pub fn create_database_service() -> Arc<dyn MeetingStore> {
+    let backend = std::env::var("MEETING_STORE_BACKEND")
+        .map(|val| val.to_lowercase())
+        .unwrap_or_else(|_| "csv".to_string());
+
     // Default path with environment variable override
-    let default_path = "/app/data/meetings.csv";
-    let csv_path =
+    let default_path = match backend.as_str() {
+        "csv" => "/app/data/meetings.csv",
+        "sqlite" => "/app/data/meetings.db",
+        other => {
+            error!("Unknown MEETING_STORE_BACKEND: {}", other);
+            panic!(
+                "Unknown MEETING_STORE_BACKEND '{}': expected 'csv' or 'sqlite'",
+                other
+            );
+        }
+    };
+    let db_path =
         std::env::var("MEETING_DATABASE_PATH").unwrap_or_else(|_| default_path.to_string());
 
     // Create the data directory if it doesn't exist and we're using the default path
-    if csv_path == default_path {
+    if db_path == default_path {
         let dir = std::path::Path::new(default_path).parent().unwrap();
         if let Err(e) = std::fs::create_dir_all(dir) {
             tracing::error!("Failed to create data directory: {}", e);
@@ -607,5 +1490,17 @@ pub fn create_database_service() -> Arc<DatabaseService> {
         }
     }
 
-    Arc::new(DatabaseService::new(&csv_path))
+    info!("Using {} meeting store at {}", backend, db_path);
+
+    if backend == "sqlite" {
+        match SqliteMeetingStore::open(&db_path) {
+            Ok(store) => Arc::new(store),
+            Err(e) => {
+                error!("Failed to open SQLite meeting store: {}", e);
+                panic!("Failed to open SQLite meeting store: {}", e);
+            }
+        }
+    } else {
+        Arc::new(DatabaseService::new(&db_path))
+    }
 }
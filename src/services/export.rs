@@ -0,0 +1,245 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use csv::WriterBuilder;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::services::database::{MeetingRecord, MeetingStore};
+use crate::services::reservations::ReservationQuery;
+
+// Column headers of CSV and Excel exports
+const EXPORT_HEADERS: [&str; 14] = [
+    "会议室",
+    "预约时段",
+    "开始时间",
+    "结束时间",
+    "时长(分钟)",
+    "会议主题",
+    "预约人",
+    "状态",
+    "会议号",
+    "表单名称",
+    "提交编号",
+    "合并时段",
+    "创建时间",
+    "取消时间",
+];
+
+// Column holding the duration, written as a number in Excel
+const DURATION_COLUMN: usize = 4;
+
+// Excel's limit on worksheet name length
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+/// File format of a reservation export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv, // Chinese headers, UTF-8 with a byte order mark so Excel detects the encoding
+    Jsonl, // One `MeetingRecord` per line
+    Xlsx,  // One worksheet per room
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Jsonl => "application/x-ndjson",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Export the reservations matching a query
+pub fn export_reservations<S: MeetingStore + ?Sized>(
+    store: &S,
+    query: &ReservationQuery,
+    format: ExportFormat,
+) -> Result<Vec<u8>, String> {
+    let records = store.find_reservations(query)?;
+    render_export(&records, format)
+}
+
+/// Write records in an export format
+pub fn render_export(records: &[MeetingRecord], format: ExportFormat) -> Result<Vec<u8>, String> {
+    match format {
+        ExportFormat::Csv => render_csv(records),
+        ExportFormat::Jsonl => render_json_lines(records),
+        ExportFormat::Xlsx => render_xlsx(records),
+    }
+}
+
+fn render_csv(records: &[MeetingRecord]) -> Result<Vec<u8>, String> {
+    let mut writer = WriterBuilder::new().from_writer(b"\xEF\xBB\xBF".to_vec());
+
+    writer
+        .write_record(EXPORT_HEADERS)
+        .map_err(|e| format!("Failed to write export headers: {}", e))?;
+    for record in records {
+        writer
+            .write_record(export_row(record))
+            .map_err(|e| format!("Failed to write export row: {}", e))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to finish export: {}", e))
+}
+
+fn render_json_lines(records: &[MeetingRecord]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut output, record)
+            .map_err(|e| format!("Failed to write export row: {}", e))?;
+        output.push(b'\n');
+    }
+
+    Ok(output)
+}
+
+fn render_xlsx(records: &[MeetingRecord]) -> Result<Vec<u8>, String> {
+    // Rooms in the order they first appear
+    let mut rooms: Vec<&str> = Vec::new();
+    for record in records {
+        if !rooms.contains(&record.room_name.as_str()) {
+            rooms.push(&record.room_name);
+        }
+    }
+
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let mut sheet_names = HashSet::new();
+
+    // An empty export still gets a sheet with the headers
+    let sheets: Vec<(String, Vec<&MeetingRecord>)> = if rooms.is_empty() {
+        vec![("预约".to_string(), Vec::new())]
+    } else {
+        rooms
+            .iter()
+            .map(|room| {
+                let room_records = records
+                    .iter()
+                    .filter(|record| record.room_name == *room)
+                    .collect();
+                (sheet_name(room, &mut sheet_names), room_records)
+            })
+            .collect()
+    };
+
+    for (name, room_records) in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet
+            .set_name(&name)
+            .map_err(|e| format!("Failed to name worksheet '{}': {}", name, e))?;
+
+        for (col, header) in EXPORT_HEADERS.iter().enumerate() {
+            worksheet
+                .write_string_with_format(0, col as u16, *header, &header_format)
+                .map_err(|e| format!("Failed to write export headers: {}", e))?;
+        }
+
+        for (row, record) in room_records.iter().enumerate() {
+            let row = row as u32 + 1;
+            for (col, value) in export_row(record).iter().enumerate() {
+                let written = match value.parse::<f64>() {
+                    Ok(minutes) if col == DURATION_COLUMN => {
+                        worksheet.write_number(row, col as u16, minutes)
+                    }
+                    _ => worksheet.write_string(row, col as u16, value),
+                };
+                written.map_err(|e| format!("Failed to write export row: {}", e))?;
+            }
+        }
+
+        worksheet.autofit();
+    }
+
+    workbook
+        .save_to_buffer()
+        .map_err(|e| format!("Failed to write workbook: {}", e))
+}
+
+// Values of a record in `EXPORT_HEADERS` order
+fn export_row(record: &MeetingRecord) -> Vec<String> {
+    let duration = DateTime::parse_from_rfc3339(&record.start_time)
+        .ok()
+        .zip(DateTime::parse_from_rfc3339(&record.end_time).ok())
+        .map(|(start, end)| (end - start).num_minutes().to_string())
+        .unwrap_or_default();
+
+    let zone = record.zone();
+    vec![
+        record.room_name.clone(),
+        record.scheduled_label.clone(),
+        local_time(&record.start_time, zone),
+        local_time(&record.end_time, zone),
+        duration,
+        record.subject.clone(),
+        record.operator_name.clone(),
+        record.status.to_string(),
+        record.meeting_id.clone(),
+        record.form_name.clone(),
+        record.entry_token.clone(),
+        record.slot_labels.join("、"),
+        local_time(&record.created_at, zone),
+        local_time(&record.cancelled_at, zone),
+    ]
+}
+
+// Stored RFC 3339 time as wall-clock time in the record's zone, e.g. "2025-04-01 09:00"
+//
+// Values that are not valid times are kept as they are.
+fn local_time(value: &str, zone: Tz) -> String {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| {
+            time.with_timezone(&zone)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| value.to_string())
+}
+
+// Worksheet name for a room: without the characters Excel does not allow,
+// shortened to Excel's limit and unique within the workbook
+fn sheet_name(room_name: &str, used: &mut HashSet<String>) -> String {
+    let cleaned: String = room_name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim_matches('\'');
+    let base = if cleaned.is_empty() {
+        "会议室"
+    } else {
+        cleaned
+    };
+
+    let mut suffix = 1;
+    loop {
+        let tag = if suffix == 1 {
+            String::new()
+        } else {
+            format!(" ({})", suffix)
+        };
+        let keep = MAX_SHEET_NAME_CHARS - tag.chars().count();
+        let name: String = base.chars().take(keep).collect::<String>() + &tag;
+
+        // Excel compares sheet names case-insensitively
+        if used.insert(name.to_lowercase()) {
+            return name;
+        }
+        suffix += 1;
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use tracing::debug;
+
+/// Serializes webhook processing per entry token
+///
+/// The form service retries webhooks it considers timed out, so the same
+/// submission can arrive again while the first one is still talking to
+/// Tencent. Holding the token's lock for the whole submission means the retry
+/// only runs once the first has stored its meetings, and then finds them
+/// instead of creating duplicates.
+///
+/// The same locks keyed by room name keep two entries from booking a room
+/// at once: each holds the room from its conflict check until its meetings
+/// are stored.
+///
+/// The locks only cover one process; a second process handling webhooks for
+/// the same database would not wait for them.
+#[derive(Default)]
+pub struct SubmissionLocks {
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+/// Held while a submission is processed; releases the token's lock on drop
+pub struct SubmissionGuard<'a> {
+    token: String,
+    locks: &'a SubmissionLocks,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl SubmissionLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no other submission with this token is being processed
+    pub async fn acquire(&self, token: &str) -> SubmissionGuard<'_> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(locks.entry(token.to_string()).or_default())
+        };
+
+        if lock.try_lock().is_err() {
+            debug!("Waiting for in-flight submission with token {}", token);
+        }
+
+        SubmissionGuard {
+            token: token.to_string(),
+            locks: self,
+            _guard: lock.lock_owned().await,
+        }
+    }
+
+    /// Number of tokens with a submission in flight or waiting
+    pub fn in_flight(&self) -> usize {
+        self.locks.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl Drop for SubmissionGuard<'_> {
+    fn drop(&mut self) {
+        let mut locks = self.locks.locks.lock().unwrap_or_else(|e| e.into_inner());
+
+        // One reference is the map's and one is our guard's; anything more
+        // belongs to a waiting submission, which still needs the entry
+        if let Some(lock) = locks.get(&self.token) {
+            if Arc::strong_count(lock) <= 2 {
+                locks.remove(&self.token);
+            }
+        }
+    }
+}
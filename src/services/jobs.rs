@@ -0,0 +1,374 @@
+use chrono::Utc;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::{error, info, warn};
+
+use crate::models::form::FormSubmission;
+use crate::models::meeting::WebhookResponse;
+
+// Lifecycle of a queued webhook submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,    // Waiting for the worker (also while waiting to be retried)
+    Running,   // Being processed by the worker
+    Succeeded, // Processed; `result` holds the webhook response
+    Failed,    // Gave up; `last_error` says why
+}
+
+// A webhook submission processed in the background
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub entry_token: String,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub payload: FormSubmission,
+    pub result: Option<WebhookResponse>,
+    pub last_error: Option<String>,
+    pub created_at: String, // ISO format
+    pub updated_at: String, // ISO format
+}
+
+// Job status as reported by `GET /jobs/:id` (without the form payload or join URLs)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobStatusResponse {
+    pub id: String,
+    pub entry_token: String,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub result: Option<WebhookResponse>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<Job> for JobStatusResponse {
+    fn from(job: Job) -> Self {
+        Self {
+            id: job.id,
+            entry_token: job.entry_token,
+            status: job.status,
+            attempts: job.attempts,
+            // Join URLs let anyone join the meeting, so they are not exposed here
+            result: job.result.map(|mut response| {
+                for meeting in &mut response.meetings {
+                    meeting.join_url = None;
+                }
+                response
+            }),
+            last_error: job.last_error,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+        }
+    }
+}
+
+// Response for a webhook accepted into the queue
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobAccepted {
+    pub job_id: String,
+    pub status: JobStatus,
+}
+
+/// Persistent queue of webhook submissions
+///
+/// Every job is kept as a JSON file in the queue directory and rewritten on
+/// each state change (temp file + rename), so jobs that were queued or
+/// running when the service stopped are picked up again on the next start.
+pub struct JobQueue {
+    dir: PathBuf,
+    jobs: Mutex<HashMap<String, Job>>,
+    sender: UnboundedSender<String>,
+    receiver: Mutex<Option<UnboundedReceiver<String>>>,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl JobQueue {
+    /// Open the queue in `dir`, re-queueing any unfinished jobs
+    pub fn open(dir: &Path, max_attempts: u32, retry_delay: Duration) -> Result<Self, String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create job queue directory: {}", e))?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut jobs = HashMap::new();
+
+        let entries =
+            fs::read_dir(dir).map_err(|e| format!("Failed to read job queue directory: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+
+            let job = match fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str::<Job>(&text).map_err(|e| e.to_string()))
+            {
+                Ok(job) => job,
+                Err(e) => {
+                    warn!("Skipping unreadable job file {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            jobs.insert(job.id.clone(), job);
+        }
+
+        // Resume unfinished jobs in the order they were submitted
+        let mut unfinished: Vec<&mut Job> = jobs
+            .values_mut()
+            .filter(|job| matches!(job.status, JobStatus::Queued | JobStatus::Running))
+            .collect();
+        unfinished.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        for job in unfinished {
+            // A job that was running when we stopped is retried from the start;
+            // webhook processing is idempotent per token
+            job.status = JobStatus::Queued;
+            let _ = sender.send(job.id.clone());
+        }
+
+        let queue = Self {
+            dir: dir.to_path_buf(),
+            jobs: Mutex::new(jobs),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            max_attempts: max_attempts.max(1),
+            retry_delay,
+        };
+
+        let resumed = queue
+            .lock_jobs()
+            .values()
+            .filter(|job| job.status == JobStatus::Queued)
+            .count();
+        info!(
+            "Job queue opened at {} ({} jobs resumed)",
+            dir.display(),
+            resumed
+        );
+
+        Ok(queue)
+    }
+
+    /// Persist a new job for the submission and hand it to the worker
+    pub fn enqueue(&self, form: FormSubmission) -> Result<Job, String> {
+        let now = Utc::now();
+        let job = Job {
+            id: format!(
+                "job-{}-{:08x}",
+                now.timestamp_millis(),
+                rand::thread_rng().gen::<u32>()
+            ),
+            entry_token: form.entry.token.clone(),
+            status: JobStatus::Queued,
+            attempts: 0,
+            payload: form,
+            result: None,
+            last_error: None,
+            created_at: now.to_rfc3339(),
+            updated_at: now.to_rfc3339(),
+        };
+
+        // Persist before acknowledging, so an accepted job is never lost
+        self.persist(&job)?;
+        self.lock_jobs().insert(job.id.clone(), job.clone());
+        self.requeue(&job.id);
+
+        info!("Queued job {} for token {}", job.id, job.entry_token);
+
+        Ok(job)
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<Job> {
+        self.lock_jobs().get(job_id).cloned()
+    }
+
+    /// Take the receiving end of the queue (only one worker can own it)
+    pub fn take_receiver(&self) -> Option<UnboundedReceiver<String>> {
+        self.receiver
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// Hand a queued job to the worker again
+    pub fn requeue(&self, job_id: &str) {
+        if self.sender.send(job_id.to_string()).is_err() {
+            error!("Job worker is not running; job {} stays queued", job_id);
+        }
+    }
+
+    /// Mark a job as running and count the attempt
+    ///
+    /// Returns `None` if the job is unknown or not queued.
+    pub fn start(&self, job_id: &str) -> Option<Job> {
+        self.update(job_id, |job| {
+            if job.status != JobStatus::Queued {
+                return false;
+            }
+            job.status = JobStatus::Running;
+            job.attempts += 1;
+            true
+        })
+    }
+
+    /// Record the webhook response of a finished job
+    pub fn succeed(&self, job_id: &str, response: WebhookResponse) -> Option<Job> {
+        self.update(job_id, |job| {
+            job.status = JobStatus::Succeeded;
+            job.result = Some(response);
+            job.last_error = None;
+            true
+        })
+    }
+
+    /// Record a failed attempt
+    ///
+    /// Retryable failures put the job back in the queue until it runs out of
+    /// attempts; returns the delay before it should be retried, or `None`
+    /// when the job has failed for good.
+    pub fn fail(&self, job_id: &str, error: &str, retryable: bool) -> Option<Duration> {
+        self.record_failure(job_id, error, retryable, None)
+    }
+
+    /// Record an attempt whose submission was processed but not fully booked
+    ///
+    /// Retried like a retryable `fail`, keeping the webhook response so
+    /// `GET /jobs/:id` shows what went wrong; after the last attempt the job
+    /// fails with that response.
+    pub fn fail_with_response(&self, job_id: &str, response: WebhookResponse) -> Option<Duration> {
+        let error = response.message.clone();
+        self.record_failure(job_id, &error, true, Some(response))
+    }
+
+    fn record_failure(
+        &self,
+        job_id: &str,
+        error: &str,
+        retryable: bool,
+        result: Option<WebhookResponse>,
+    ) -> Option<Duration> {
+        let mut retry_after = None;
+        self.update(job_id, |job| {
+            job.last_error = Some(error.to_string());
+            if result.is_some() {
+                job.result = result;
+            }
+            if retryable && job.attempts < self.max_attempts {
+                job.status = JobStatus::Queued;
+                retry_after = Some(self.backoff_delay(job.attempts));
+            } else {
+                job.status = JobStatus::Failed;
+            }
+            true
+        });
+
+        match retry_after {
+            Some(delay) => warn!("Job {} failed ({}), retrying in {:?}", job_id, error, delay),
+            None => error!("Job {} failed permanently: {}", job_id, error),
+        }
+
+        retry_after
+    }
+
+    // Exponential backoff between job attempts, capped at 32x the base delay
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        self.retry_delay * 2u32.saturating_pow(attempt.saturating_sub(1).min(5))
+    }
+
+    // Apply `change` to a job and persist it; `change` returns false to skip the update
+    fn update<F>(&self, job_id: &str, change: F) -> Option<Job>
+    where
+        F: FnOnce(&mut Job) -> bool,
+    {
+        let mut jobs = self.lock_jobs();
+        let job = jobs.get_mut(job_id)?;
+
+        if !change(job) {
+            return None;
+        }
+        job.updated_at = Utc::now().to_rfc3339();
+
+        if let Err(e) = self.persist(job) {
+            // The in-memory state is still correct; the job is redone after a restart
+            error!("Failed to persist job {}: {}", job_id, e);
+        }
+
+        Some(job.clone())
+    }
+
+    // Write the job file atomically
+    fn persist(&self, job: &Job) -> Result<(), String> {
+        let path = self.dir.join(format!("{}.json", job.id));
+        let tmp_path = self.dir.join(format!("{}.json.tmp", job.id));
+
+        let json = serde_json::to_vec_pretty(job)
+            .map_err(|e| format!("Failed to serialize job: {}", e))?;
+
+        let mut file =
+            File::create(&tmp_path).map_err(|e| format!("Failed to create job file: {}", e))?;
+        file.write_all(&json)
+            .map_err(|e| format!("Failed to write job file: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync job file: {}", e))?;
+
+        fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to replace job file: {}", e))
+    }
+
+    fn lock_jobs(&self) -> std::sync::MutexGuard<'_, HashMap<String, Job>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// Create the job queue when asynchronous webhook processing is enabled
+//
+// Jobs are stored in `JOB_QUEUE_DIR`, or a `jobs` directory next to the
+// meetings database by default
+pub fn create_job_queue() -> Option<Arc<JobQueue>> {
+    let enabled = std::env::var("WEBHOOK_ASYNC_MODE")
+        .map(|val| val.to_lowercase() == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+
+    let dir = match std::env::var("JOB_QUEUE_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => {
+            let db_path = std::env::var("MEETING_DATABASE_PATH")
+                .unwrap_or_else(|_| "/app/data/meetings.csv".to_string());
+            Path::new(&db_path)
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join("jobs")
+        }
+    };
+
+    let max_attempts = std::env::var("JOB_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .unwrap_or(5);
+    let retry_delay = std::env::var("JOB_RETRY_DELAY_SECS")
+        .ok()
+        .and_then(|val| val.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(5));
+
+    match JobQueue::open(&dir, max_attempts, retry_delay) {
+        Ok(queue) => Some(Arc::new(queue)),
+        Err(e) => {
+            error!("Failed to open job queue: {}", e);
+            panic!("Failed to open job queue: {}", e);
+        }
+    }
+}
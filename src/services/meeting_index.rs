@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::services::database::{MeetingFilter, MeetingRecord};
+
+/// Date part (`YYYY-MM-DD`) of a scheduled label such as "2025-04-01 09:00-10:00"
+pub fn scheduled_date(scheduled_label: &str) -> &str {
+    scheduled_label.split(' ').next().unwrap_or("")
+}
+
+/// In-memory index over the rows of a meeting store
+///
+/// Rows keep their position in the store, so results come back in insertion
+/// order. Rows that could not be parsed take up a position but are never
+/// returned. Lookups by entry token, meeting id and room + date only touch the
+/// matching rows.
+#[derive(Debug, Default)]
+pub struct MeetingIndex {
+    records: Vec<Option<MeetingRecord>>,
+    by_token: HashMap<String, Vec<usize>>,
+    by_meeting_id: HashMap<String, Vec<usize>>,
+    by_room_date: HashMap<(String, String), Vec<usize>>,
+}
+
+impl MeetingIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next row (`None` for a row that could not be parsed)
+    pub fn push(&mut self, record: Option<MeetingRecord>) {
+        let position = self.records.len();
+        if let Some(record) = &record {
+            self.add_keys(position, record);
+        }
+        self.records.push(record);
+    }
+
+    /// Replace the record at `position`, moving it to its new keys
+    pub fn replace(&mut self, position: usize, record: MeetingRecord) {
+        if let Some(old) = self.records[position].take() {
+            self.remove_keys(position, &old);
+        }
+        self.add_keys(position, &record);
+        self.records[position] = Some(record);
+    }
+
+    /// Positions of the records matching the filter, in insertion order
+    pub fn positions(&self, filter: MeetingFilter<'_>) -> Vec<usize> {
+        let positions = match filter {
+            MeetingFilter::All => {
+                return (0..self.records.len())
+                    .filter(|&position| self.records[position].is_some())
+                    .collect()
+            }
+            MeetingFilter::Token(token) => self.by_token.get(token),
+            MeetingFilter::MeetingId(meeting_id) => self.by_meeting_id.get(meeting_id),
+            MeetingFilter::RoomOnDate { room_name, date } => self
+                .by_room_date
+                .get(&(room_name.to_string(), date.to_string())),
+        };
+
+        positions.cloned().unwrap_or_default()
+    }
+
+    /// Records matching the filter, in insertion order
+    pub fn find(&self, filter: MeetingFilter<'_>) -> Vec<MeetingRecord> {
+        self.positions(filter)
+            .into_iter()
+            .filter_map(|position| self.get(position).cloned())
+            .collect()
+    }
+
+    pub fn get(&self, position: usize) -> Option<&MeetingRecord> {
+        self.records.get(position).and_then(Option::as_ref)
+    }
+
+    fn add_keys(&mut self, position: usize, record: &MeetingRecord) {
+        insert_sorted(
+            self.by_token.entry(record.entry_token.clone()).or_default(),
+            position,
+        );
+        insert_sorted(
+            self.by_meeting_id
+                .entry(record.meeting_id.clone())
+                .or_default(),
+            position,
+        );
+        insert_sorted(
+            self.by_room_date.entry(room_date_key(record)).or_default(),
+            position,
+        );
+    }
+
+    fn remove_keys(&mut self, position: usize, record: &MeetingRecord) {
+        remove_position(&mut self.by_token, &record.entry_token, position);
+        remove_position(&mut self.by_meeting_id, &record.meeting_id, position);
+        remove_position(&mut self.by_room_date, &room_date_key(record), position);
+    }
+}
+
+fn room_date_key(record: &MeetingRecord) -> (String, String) {
+    (
+        record.room_name.clone(),
+        scheduled_date(&record.scheduled_label).to_string(),
+    )
+}
+
+// Keep positions sorted, so lookups return rows in insertion order
+fn insert_sorted(positions: &mut Vec<usize>, position: usize) {
+    if let Err(at) = positions.binary_search(&position) {
+        positions.insert(at, position);
+    }
+}
+
+fn remove_position<K, Q>(map: &mut HashMap<K, Vec<usize>>, key: &Q, position: usize)
+where
+    K: std::borrow::Borrow<Q> + std::hash::Hash + Eq,
+    Q: std::hash::Hash + Eq + ?Sized,
+{
+    if let Some(positions) = map.get_mut(key) {
+        positions.retain(|&p| p != position);
+        if positions.is_empty() {
+            map.remove(key);
+        }
+    }
+}
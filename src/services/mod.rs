@@ -1,2 +1,13 @@
+pub mod async_store;
+pub mod blackouts;
+pub mod booking_policy;
+pub mod calendar;
 pub mod database;
+pub mod export;
+pub mod idempotency;
+pub mod jobs;
+pub mod meeting_index;
+pub mod reconcile;
+pub mod reservations;
+pub mod sqlite_store;
 pub mod time_slots;
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use tracing::{debug, info};
+
+use crate::models::meeting::TimeSlot;
+use crate::services::database::MeetingRecord;
+use crate::services::time_slots::merged_scheduled_label;
+
+// A meeting the form currently asks for (one mergeable group of slots)
+#[derive(Debug, Clone)]
+pub struct DesiredMeeting {
+    pub room_name: String,
+    pub scheduled_label: String, // Same format as the stored record's label
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub time_slots: Vec<TimeSlot>,
+}
+
+impl DesiredMeeting {
+    // Build from a group returned by find_mergeable_groups
+    pub fn from_group(group: &[TimeSlot]) -> Option<Self> {
+        let mut sorted_slots = group.to_vec();
+        sorted_slots.sort_by_key(|slot| slot.start_time);
+
+        let first_slot = sorted_slots.first()?;
+        let last_slot = sorted_slots.last()?;
+
+        // Single slots are stored with their own label, merged ones with the combined label
+        let scheduled_label = if sorted_slots.len() == 1 {
+            first_slot.scheduled_label.clone()
+        } else {
+            merged_scheduled_label(&sorted_slots)
+        };
+
+        Some(Self {
+            room_name: first_slot.item_name.clone(),
+            scheduled_label,
+            start_time: first_slot.start_time,
+            end_time: last_slot.end_time,
+            time_slots: sorted_slots,
+        })
+    }
+
+    pub fn is_merged(&self) -> bool {
+        self.time_slots.len() > 1
+    }
+
+    // Zone of the meeting's slots, else the zone the record was stored in
+    pub fn time_zone(&self, record: &MeetingRecord) -> Tz {
+        self.time_slots
+            .first()
+            .map(|slot| slot.time_zone)
+            .unwrap_or_else(|| record.zone())
+    }
+
+    pub fn slot_labels(&self) -> Vec<String> {
+        self.time_slots
+            .iter()
+            .map(|slot| slot.scheduled_label.clone())
+            .collect()
+    }
+}
+
+// One action needed to bring the stored meetings in line with an edited form
+#[derive(Debug, Clone)]
+pub enum ReconcileStep {
+    // Slots were removed: cancel the meeting
+    Cancel {
+        record: MeetingRecord,
+    },
+    // Same room and time, nothing to do
+    Keep {
+        record: MeetingRecord,
+        desired: DesiredMeeting,
+    },
+    // Same room and time, but the subject changed
+    UpdateSubject {
+        record: MeetingRecord,
+        desired: DesiredMeeting,
+    },
+    // Same room, different time: move the existing meeting
+    Reschedule {
+        record: MeetingRecord,
+        desired: DesiredMeeting,
+    },
+    // Slots were added: create a new meeting
+    Create {
+        desired: DesiredMeeting,
+    },
+}
+
+/// Compare the meetings a form asks for with its active stored meetings
+///
+/// Meetings are matched on room and scheduled label first. Any remaining
+/// desired meeting takes over an unmatched stored meeting in the same room
+/// (a reschedule, which keeps the meeting code); the rest are created.
+/// Stored meetings left over after that are cancelled.
+///
+/// Steps are returned in execution order: cancellations first so they free
+/// rooms before anything is moved or created.
+pub fn plan_reconciliation(
+    existing: &[MeetingRecord],
+    desired: Vec<DesiredMeeting>,
+    subject: &str,
+) -> Vec<ReconcileStep> {
+    let mut existing: Vec<&MeetingRecord> = existing.iter().collect();
+    existing.sort_by(|a, b| a.scheduled_at.cmp(&b.scheduled_at));
+    let mut matched = vec![false; existing.len()];
+
+    let mut desired = desired;
+    desired.sort_by_key(|meeting| meeting.start_time);
+
+    let mut kept = Vec::new();
+    let mut unmatched_desired = Vec::new();
+
+    // Pass 1: exact matches on room and time
+    for meeting in desired {
+        let exact = existing.iter().enumerate().position(|(i, record)| {
+            !matched[i]
+                && record.room_name == meeting.room_name
+                && record.scheduled_label == meeting.scheduled_label
+        });
+
+        match exact {
+            Some(i) => {
+                matched[i] = true;
+                let record = existing[i].clone();
+                if record.subject == subject {
+                    kept.push(ReconcileStep::Keep {
+                        record,
+                        desired: meeting,
+                    });
+                } else {
+                    kept.push(ReconcileStep::UpdateSubject {
+                        record,
+                        desired: meeting,
+                    });
+                }
+            }
+            None => unmatched_desired.push(meeting),
+        }
+    }
+
+    // Pass 2: move leftover meetings in the same room, create the rest
+    let mut moved = Vec::new();
+    let mut created = Vec::new();
+    for meeting in unmatched_desired {
+        let same_room = existing
+            .iter()
+            .enumerate()
+            .position(|(i, record)| !matched[i] && record.room_name == meeting.room_name);
+
+        match same_room {
+            Some(i) => {
+                matched[i] = true;
+                debug!(
+                    "Meeting {} moves from {} to {}",
+                    existing[i].meeting_id, existing[i].scheduled_label, meeting.scheduled_label
+                );
+                moved.push(ReconcileStep::Reschedule {
+                    record: existing[i].clone(),
+                    desired: meeting,
+                });
+            }
+            None => created.push(ReconcileStep::Create { desired: meeting }),
+        }
+    }
+
+    // Whatever is left was removed from the form
+    let cancelled: Vec<ReconcileStep> = existing
+        .iter()
+        .zip(matched.iter())
+        .filter(|(_, matched)| !**matched)
+        .map(|(record, _)| ReconcileStep::Cancel {
+            record: (*record).clone(),
+        })
+        .collect();
+
+    info!(
+        "Reconciliation plan: {} cancelled, {} kept, {} rescheduled, {} created",
+        cancelled.len(),
+        kept.len(),
+        moved.len(),
+        created.len()
+    );
+
+    cancelled
+        .into_iter()
+        .chain(kept)
+        .chain(moved)
+        .chain(created)
+        .collect()
+}
@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::common::PaginationParams;
+use crate::models::reservation::ReservationStatus;
+use crate::services::database::MeetingRecord;
+use crate::services::meeting_index::scheduled_date;
+
+// Largest page the listing endpoint returns
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Filters for listing stored reservations
+///
+/// Every filter is optional; a record has to match all that are set. Dates
+/// are compared with the scheduled date (the date in the scheduled label, in
+/// the forms' local time).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReservationQuery {
+    pub from: Option<NaiveDate>,           // First scheduled date, inclusive
+    pub to: Option<NaiveDate>,             // Last scheduled date, inclusive
+    pub room: Option<String>,              // Room name
+    pub operator: Option<String>,          // Operator name or ID
+    pub status: Option<ReservationStatus>, // Status label or name (e.g. "已预约" or "reserved")
+    pub form: Option<String>,              // Form ID or name
+}
+
+impl ReservationQuery {
+    /// Whether a record passes every filter
+    pub fn matches(&self, record: &MeetingRecord) -> bool {
+        if self.from.is_some() || self.to.is_some() {
+            let Ok(date) =
+                NaiveDate::parse_from_str(scheduled_date(&record.scheduled_label), "%Y-%m-%d")
+            else {
+                return false;
+            };
+            if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+                return false;
+            }
+        }
+
+        self.room
+            .as_ref()
+            .is_none_or(|room| record.room_name == *room)
+            && self.operator.as_ref().is_none_or(|operator| {
+                record.operator_name == *operator || record.operator_id == *operator
+            })
+            && self.status.is_none_or(|status| record.status == status)
+            && self
+                .form
+                .as_ref()
+                .is_none_or(|form| record.form_id == *form || record.form_name == *form)
+    }
+
+    /// The single date the query covers, if `from` and `to` are the same day
+    pub fn single_date(&self) -> Option<NaiveDate> {
+        self.from.filter(|from| self.to == Some(*from))
+    }
+}
+
+/// One page of matching reservations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReservationPage {
+    pub total: usize, // Matching records across all pages
+    pub page: usize,
+    pub page_size: usize,
+    pub reservations: Vec<MeetingRecord>,
+}
+
+impl ReservationPage {
+    /// Cut one page out of the matching records
+    ///
+    /// Pages start at 1; the page size is limited to `MAX_PAGE_SIZE`.
+    pub fn new(records: Vec<MeetingRecord>, params: &PaginationParams) -> Self {
+        let page = params.page.max(1);
+        let page_size = params.page_size.clamp(1, MAX_PAGE_SIZE);
+        let total = records.len();
+
+        let reservations = records
+            .into_iter()
+            .skip((page - 1).saturating_mul(page_size))
+            .take(page_size)
+            .collect();
+
+        Self {
+            total,
+            page,
+            page_size,
+            reservations,
+        }
+    }
+}
@@ -0,0 +1,479 @@
+use rusqlite::types::{FromSql, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{
+    params, params_from_iter, Connection, OptionalExtension, Row, ToSql, TransactionBehavior,
+};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+use crate::models::reservation::ReservationStatus;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::database::{
+    canonical_labels, join_slot_labels, legacy_time_range, local_time, split_slot_labels,
+    MeetingFilter, MeetingRecord, MeetingStore,
+};
+
+// Columns in the same order as the CSV store
+const COLUMNS: &str = "entry_token, form_id, form_name, subject, room_name, scheduled_at, \
+     scheduled_label, status, meeting_id, room_id, created_at, cancelled_at, operator_name, \
+     operator_id, start_time, end_time, slot_labels, join_url, time_zone, local_start_time, \
+     local_end_time, sequence, updated_at";
+
+/// SQLite-backed meeting store
+///
+/// The database runs in WAL mode with a busy timeout, and every
+/// read-modify-write happens inside an immediate transaction, so other
+/// processes can safely share the database file. Webhook handling is only
+/// serialized within a process (see `SubmissionLocks`), so one service process
+/// should handle the webhooks for a database.
+pub struct SqliteMeetingStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteMeetingStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &str) -> Result<Self, String> {
+        let conn =
+            Connection::open(path).map_err(|e| format!("Failed to open SQLite database: {}", e))?;
+
+        conn.busy_timeout(Duration::from_secs(5))
+            .map_err(|e| format!("Failed to set busy timeout: {}", e))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("Failed to enable WAL mode: {}", e))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS meetings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entry_token TEXT NOT NULL,
+                form_id TEXT NOT NULL,
+                form_name TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                room_name TEXT NOT NULL,
+                scheduled_at TEXT NOT NULL,
+                scheduled_label TEXT NOT NULL,
+                status TEXT NOT NULL,
+                meeting_id TEXT NOT NULL,
+                room_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                cancelled_at TEXT NOT NULL,
+                operator_name TEXT NOT NULL,
+                operator_id TEXT NOT NULL,
+                start_time TEXT NOT NULL,
+                end_time TEXT NOT NULL,
+                slot_labels TEXT NOT NULL,
+                join_url TEXT NOT NULL,
+                time_zone TEXT NOT NULL,
+                local_start_time TEXT NOT NULL,
+                local_end_time TEXT NOT NULL,
+                sequence INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_meetings_token ON meetings (entry_token);
+            CREATE INDEX IF NOT EXISTS idx_meetings_meeting_id ON meetings (meeting_id);
+            CREATE INDEX IF NOT EXISTS idx_meetings_room ON meetings (room_name, scheduled_label);",
+        )
+        .map_err(|e| format!("Failed to create meetings table: {}", e))?;
+
+        Self::add_time_range_columns(&conn)?;
+        if !Self::has_column(&conn, "join_url")? {
+            conn.execute(
+                "ALTER TABLE meetings ADD COLUMN join_url TEXT NOT NULL DEFAULT ''",
+                [],
+            )
+            .map_err(|e| format!("Failed to add join_url column: {}", e))?;
+        }
+        Self::add_local_time_columns(&conn)?;
+        if !Self::has_column(&conn, "sequence")? {
+            // Older rows count as unchanged
+            conn.execute_batch(
+                "ALTER TABLE meetings ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+                 ALTER TABLE meetings ADD COLUMN updated_at TEXT NOT NULL DEFAULT '';",
+            )
+            .map_err(|e| format!("Failed to add revision columns: {}", e))?;
+        }
+        Self::canonicalize_labels(&conn)?;
+
+        info!("Opened SQLite meeting store at {}", path);
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    // Add the time range columns to a table created by an older version,
+    // deriving each row's range from its scheduled time and label
+    fn add_time_range_columns(conn: &Connection) -> Result<(), String> {
+        if Self::has_column(conn, "start_time")? {
+            return Ok(());
+        }
+
+        info!("Adding time range columns to the meetings table");
+
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute_batch(
+            "ALTER TABLE meetings ADD COLUMN start_time TEXT NOT NULL DEFAULT '';
+             ALTER TABLE meetings ADD COLUMN end_time TEXT NOT NULL DEFAULT '';
+             ALTER TABLE meetings ADD COLUMN slot_labels TEXT NOT NULL DEFAULT '';",
+        )
+        .map_err(|e| format!("Failed to add time range columns: {}", e))?;
+
+        let rows = {
+            let mut statement = tx
+                .prepare("SELECT id, scheduled_at, scheduled_label FROM meetings")
+                .map_err(|e| format!("Failed to prepare query: {}", e))?;
+            let rows = statement
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                })
+                .map_err(|e| format!("Failed to query meetings: {}", e))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read record: {}", e))?
+        };
+
+        for (id, scheduled_at, scheduled_label) in rows {
+            let (start_time, end_time) = legacy_time_range(&scheduled_at, &scheduled_label);
+            tx.execute(
+                "UPDATE meetings SET start_time = ?1, end_time = ?2 WHERE id = ?3",
+                params![start_time, end_time, id],
+            )
+            .map_err(|e| format!("Failed to update record: {}", e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit update: {}", e))
+    }
+
+    // Add the time zone and local time columns to a table created by an older
+    // version; older rows were booked in the default zone
+    fn add_local_time_columns(conn: &Connection) -> Result<(), String> {
+        if Self::has_column(conn, "time_zone")? {
+            return Ok(());
+        }
+
+        info!("Adding local time columns to the meetings table");
+
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute_batch(
+            "ALTER TABLE meetings ADD COLUMN time_zone TEXT NOT NULL DEFAULT '';
+             ALTER TABLE meetings ADD COLUMN local_start_time TEXT NOT NULL DEFAULT '';
+             ALTER TABLE meetings ADD COLUMN local_end_time TEXT NOT NULL DEFAULT '';",
+        )
+        .map_err(|e| format!("Failed to add local time columns: {}", e))?;
+
+        let rows = {
+            let mut statement = tx
+                .prepare("SELECT id, start_time, end_time FROM meetings")
+                .map_err(|e| format!("Failed to prepare query: {}", e))?;
+            let rows = statement
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                })
+                .map_err(|e| format!("Failed to query meetings: {}", e))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read record: {}", e))?
+        };
+
+        for (id, start_time, end_time) in rows {
+            tx.execute(
+                "UPDATE meetings SET time_zone = ?1, local_start_time = ?2, local_end_time = ?3
+                 WHERE id = ?4",
+                params![
+                    DEFAULT_TIME_ZONE.name(),
+                    local_time(&start_time, DEFAULT_TIME_ZONE),
+                    local_time(&end_time, DEFAULT_TIME_ZONE),
+                    id
+                ],
+            )
+            .map_err(|e| format!("Failed to update record: {}", e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit update: {}", e))
+    }
+
+    // Rewrite labels stored by older versions in their canonical form, since
+    // lookups by date and form edits compare labels literally
+    fn canonicalize_labels(conn: &Connection) -> Result<(), String> {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let rows = {
+            let mut statement = tx
+                .prepare("SELECT id, scheduled_label, slot_labels FROM meetings")
+                .map_err(|e| format!("Failed to prepare query: {}", e))?;
+            let rows = statement
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                })
+                .map_err(|e| format!("Failed to query meetings: {}", e))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read record: {}", e))?
+        };
+
+        let mut changed = 0;
+        for (id, scheduled_label, slot_labels) in rows {
+            let Some((label, slots)) = canonical_labels(&scheduled_label, &slot_labels) else {
+                continue;
+            };
+            tx.execute(
+                "UPDATE meetings SET scheduled_label = ?1, slot_labels = ?2 WHERE id = ?3",
+                params![label, slots, id],
+            )
+            .map_err(|e| format!("Failed to update record: {}", e))?;
+            changed += 1;
+        }
+
+        if changed > 0 {
+            info!("Rewrote {} scheduled labels in canonical form", changed);
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit update: {}", e))
+    }
+
+    // Whether the meetings table has the given column
+    fn has_column(conn: &Connection, column: &str) -> Result<bool, String> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('meetings') WHERE name = ?1",
+            [column],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|count| count > 0)
+        .map_err(|e| format!("Failed to read meetings table layout: {}", e))
+    }
+
+    fn lock_conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.conn
+            .lock()
+            .map_err(|e| format!("Failed to acquire mutex: {}", e))
+    }
+
+    // Query rows (id + record) matching the filter, in insertion order
+    fn select(
+        conn: &Connection,
+        filter: MeetingFilter<'_>,
+    ) -> Result<Vec<(i64, MeetingRecord)>, String> {
+        let (condition, values) = match filter {
+            MeetingFilter::All => ("", vec![]),
+            MeetingFilter::Token(token) => ("WHERE entry_token = ?1", vec![token.to_string()]),
+            MeetingFilter::MeetingId(meeting_id) => {
+                ("WHERE meeting_id = ?1", vec![meeting_id.to_string()])
+            }
+            MeetingFilter::RoomOnDate { room_name, date } => (
+                "WHERE room_name = ?1 AND (scheduled_label = ?2
+                     OR substr(scheduled_label, 1, length(?2) + 1) = ?2 || ' ')",
+                vec![room_name.to_string(), date.to_string()],
+            ),
+        };
+
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT id, {} FROM meetings {} ORDER BY id",
+                COLUMNS, condition
+            ))
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = statement
+            .query_map(params_from_iter(values), |row| {
+                Ok((row.get(0)?, Self::row_to_record(row)?))
+            })
+            .map_err(|e| format!("Failed to query meetings: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read record: {}", e))
+    }
+
+    // Convert a row selected as `id, COLUMNS` to a MeetingRecord
+    fn row_to_record(row: &Row<'_>) -> rusqlite::Result<MeetingRecord> {
+        Ok(MeetingRecord {
+            entry_token: row.get(1)?,
+            form_id: row.get(2)?,
+            form_name: row.get(3)?,
+            subject: row.get(4)?,
+            room_name: row.get(5)?,
+            scheduled_at: row.get(6)?,
+            scheduled_label: row.get(7)?,
+            status: row.get(8)?,
+            meeting_id: row.get(9)?,
+            room_id: row.get(10)?,
+            created_at: row.get(11)?,
+            cancelled_at: row.get(12)?,
+            operator_name: row.get(13)?,
+            operator_id: row.get(14)?,
+            start_time: row.get(15)?,
+            end_time: row.get(16)?,
+            slot_labels: split_slot_labels(&row.get::<_, String>(17)?),
+            join_url: row.get(18)?,
+            time_zone: row.get(19)?,
+            local_start_time: row.get(20)?,
+            local_end_time: row.get(21)?,
+            sequence: row.get(22)?,
+            updated_at: row.get(23)?,
+        })
+    }
+}
+
+impl MeetingStore for SqliteMeetingStore {
+    fn insert_meeting(&self, record: &MeetingRecord) -> Result<bool, String> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        // Check if an identical meeting entry already exists (same token, status, and time)
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM meetings
+                 WHERE entry_token = ?1 AND status = ?2 AND scheduled_label = ?3",
+                params![record.entry_token, record.status, record.scheduled_label],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to check for duplicates: {}", e))?;
+        if existing.is_some() {
+            return Ok(false);
+        }
+
+        tx.execute(
+            &format!(
+                "INSERT INTO meetings ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
+                COLUMNS
+            ),
+            params![
+                record.entry_token,
+                record.form_id,
+                record.form_name,
+                record.subject,
+                record.room_name,
+                record.scheduled_at,
+                record.scheduled_label,
+                record.status,
+                record.meeting_id,
+                record.room_id,
+                record.created_at,
+                record.cancelled_at,
+                record.operator_name,
+                record.operator_id,
+                record.start_time,
+                record.end_time,
+                join_slot_labels(&record.slot_labels),
+                record.join_url,
+                record.time_zone,
+                record.local_start_time,
+                record.local_end_time,
+                record.sequence,
+                record.updated_at,
+            ],
+        )
+        .map_err(|e| format!("Failed to insert record: {}", e))?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit record: {}", e))?;
+
+        Ok(true)
+    }
+
+    fn find_meetings(&self, filter: MeetingFilter<'_>) -> Result<Vec<MeetingRecord>, String> {
+        let conn = self.lock_conn()?;
+        Ok(Self::select(&conn, filter)?
+            .into_iter()
+            .map(|(_, record)| record)
+            .collect())
+    }
+
+    fn update_meetings(
+        &self,
+        filter: MeetingFilter<'_>,
+        update: &mut dyn FnMut(&mut MeetingRecord) -> bool,
+    ) -> Result<Vec<MeetingRecord>, String> {
+        let mut conn = self.lock_conn()?;
+
+        // Immediate: take the write lock before reading, so no other process
+        // can change the rows between our read and our write
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut updated = Vec::new();
+        for (id, mut record) in Self::select(&tx, filter)? {
+            if !update(&mut record) {
+                continue;
+            }
+
+            tx.execute(
+                "UPDATE meetings SET entry_token = ?1, form_id = ?2, form_name = ?3,
+                     subject = ?4, room_name = ?5, scheduled_at = ?6, scheduled_label = ?7,
+                     status = ?8, meeting_id = ?9, room_id = ?10, created_at = ?11,
+                     cancelled_at = ?12, operator_name = ?13, operator_id = ?14,
+                     start_time = ?15, end_time = ?16, slot_labels = ?17, join_url = ?18,
+                     time_zone = ?19, local_start_time = ?20, local_end_time = ?21,
+                     sequence = ?22, updated_at = ?23
+                 WHERE id = ?24",
+                params![
+                    record.entry_token,
+                    record.form_id,
+                    record.form_name,
+                    record.subject,
+                    record.room_name,
+                    record.scheduled_at,
+                    record.scheduled_label,
+                    record.status,
+                    record.meeting_id,
+                    record.room_id,
+                    record.created_at,
+                    record.cancelled_at,
+                    record.operator_name,
+                    record.operator_id,
+                    record.start_time,
+                    record.end_time,
+                    join_slot_labels(&record.slot_labels),
+                    record.join_url,
+                    record.time_zone,
+                    record.local_start_time,
+                    record.local_end_time,
+                    record.sequence,
+                    record.updated_at,
+                    id,
+                ],
+            )
+            .map_err(|e| format!("Failed to update record: {}", e))?;
+
+            updated.push(record);
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit update: {}", e))?;
+
+        Ok(updated)
+    }
+}
+
+// Statuses are stored as their label, like in the CSV store
+impl ToSql for ReservationStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for ReservationStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ok(ReservationStatus::parse_stored(value.as_str()?))
+    }
+}
@@ -1,15 +1,17 @@
-use axum::http::StatusCode;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono_tz::Tz;
 use std::collections::HashMap;
 use tracing::{debug, error, info, warn};
 
-use crate::client::{CreateMeetingRequest, TencentMeetingClient};
+use crate::client::{CreateMeetingRequest, MeetingApi, TencentApiError};
 use crate::models::form::FormField1Item;
 use crate::models::form::FormSubmission;
-use crate::models::meeting::{MeetingResult, TimeSlot};
+use crate::models::meeting::{MeetingAction, MeetingResult, RoomBooking, TimeSlot, TimeSlotError};
+use crate::models::scheduled_label::ScheduledLabel;
+use crate::models::time_zone::resolve_local_time;
 
 // Helper function to determine location based on form name
-fn get_location_for_form(form_name: &str, room_name: &str) -> String {
+pub fn get_location_for_form(form_name: &str, room_name: &str) -> String {
     debug!(
         "Getting location for form: {}, room: {}",
         form_name, room_name
@@ -40,8 +42,8 @@ pub fn get_room_id_for_form(form_name: &str, xa_room_id: &str, cd_room_id: &str)
 }
 
 // Helper function to get operator name and ID from form submission
-pub fn get_operator_info(
-    client: &TencentMeetingClient,
+pub fn get_operator_info<C: MeetingApi + ?Sized>(
+    client: &C,
     form: &FormSubmission,
     user_field_name: &str,
 ) -> (String, String) {
@@ -87,66 +89,47 @@ pub fn get_operator_info(
 }
 
 // Parse a scheduled time from a form field item
-pub fn parse_time_slot(reservation: &FormField1Item) -> Result<TimeSlot, String> {
+//
+// The label's time range is local time in `time_zone`, the zone of the
+// slot's room or form. A label that doesn't parse is an error. The slot
+// keeps the label in its canonical form (e.g. "2025-04-01 09:00-10:00" for
+// "2025-4-1 9:00-10:00"), which is what stored records and lookups use.
+pub fn parse_time_slot(
+    reservation: &FormField1Item,
+    time_zone: Tz,
+) -> Result<TimeSlot, TimeSlotError> {
     // Parse the scheduled time
-    let scheduled_at_str = &reservation.scheduled_at;
-    let parsed_start_time = match DateTime::parse_from_rfc3339(scheduled_at_str) {
-        Ok(dt) => dt.with_timezone(&Utc),
-        Err(e) => {
-            return Err(format!("Failed to parse scheduled_at time: {}", e));
-        }
-    };
+    let parsed_start_time =
+        parse_scheduled_at(&reservation.scheduled_at, time_zone).map_err(|reason| {
+            TimeSlotError::InvalidScheduledAt {
+                value: reservation.scheduled_at.clone(),
+                reason,
+            }
+        })?;
 
-    // Parse the scheduled label to determine meeting duration and original end time
+    // Calculate the original end time from the label's duration
     // Format expected: "2025-03-30 09:00-10:00" or similar
-    let scheduled_label = &reservation.scheduled_label;
-    let parts: Vec<&str> = scheduled_label.split(' ').collect();
-
-    // Calculate the original end time based on the label first
-    let mut original_end_time = parsed_start_time + chrono::Duration::hours(1); // Default 1 hour
-
-    if parts.len() > 1 {
-        let time_parts: Vec<&str> = parts[1].split('-').collect();
-        if time_parts.len() > 1 {
-            let start_time_str = time_parts[0];
-            let end_time_str = time_parts[1];
-
-            // Parse full time including both hours and minutes
-            let parse_time = |time_str: &str| {
-                let parts: Vec<&str> = time_str.split(':').collect();
-                let hour = parts
-                    .first()
-                    .and_then(|h| h.parse::<i64>().ok())
-                    .unwrap_or(0);
-                let minute = parts
-                    .get(1)
-                    .and_then(|m| m.parse::<i64>().ok())
-                    .unwrap_or(0);
-                (hour, minute)
-            };
-
-            let (start_hour, start_min) = parse_time(start_time_str);
-            let (end_hour, end_min) = parse_time(end_time_str);
-
-            // Calculate total minutes
-            let start_total_mins = start_hour * 60 + start_min;
-            let end_total_mins = end_hour * 60 + end_min;
-
-            let duration_mins = if end_total_mins >= start_total_mins {
-                end_total_mins - start_total_mins
-            } else {
-                // Handle overnight meetings
-                (24 * 60) + end_total_mins - start_total_mins
-            };
-
-            debug!(
-                "Time range {}-{} calculated as {} minutes difference",
-                start_time_str, end_time_str, duration_mins
-            );
-
-            original_end_time = parsed_start_time + chrono::Duration::minutes(duration_mins);
-        }
+    //
+    // The duration is added to the local wall-clock time, so a range across a
+    // daylight saving change still ends at the time on the label
+    let label = ScheduledLabel::parse(&reservation.scheduled_label)
+        .map_err(|reason| TimeSlotError::InvalidLabel { reason })?;
+    let local_start = parsed_start_time.with_timezone(&time_zone).naive_local();
+
+    // scheduled_at has to be the label's start; a start skipped by a daylight
+    // saving change matches the instant it resolves to
+    if local_start != label.start
+        && resolve_local_time(label.start, time_zone) != Some(parsed_start_time)
+    {
+        return Err(TimeSlotError::ScheduledAtMismatch {
+            scheduled_at: reservation.scheduled_at.clone(),
+            label_start: label.start.format("%Y-%m-%d %H:%M").to_string(),
+        });
     }
+    let duration = label.duration();
+    let local_end = local_start + duration;
+    let original_end_time =
+        resolve_local_time(local_end, time_zone).unwrap_or(parsed_start_time + duration);
 
     // Check if both start and end times are in the past
     let now = Utc::now();
@@ -159,10 +142,7 @@ pub fn parse_time_slot(reservation: &FormField1Item) -> Result<TimeSlot, String>
             "Both start time {} and end time {} are in the past",
             parsed_start_time, original_end_time
         );
-        return Err(
-            "Time slot is entirely in the past. Cannot create a meeting for past times."
-                .to_string(),
-        );
+        return Err(TimeSlotError::InPast);
     } else if parsed_start_time < now {
         // Only start time is in the past, end time is in the future
         debug!(
@@ -183,14 +163,103 @@ pub fn parse_time_slot(reservation: &FormField1Item) -> Result<TimeSlot, String>
 
     Ok(TimeSlot {
         item_name: reservation.item_name.clone(),
-        scheduled_label: reservation.scheduled_label.clone(),
+        scheduled_label: label.to_string(), // Canonical form, as lookups by date expect
         number: reservation.number,
         start_time: meeting_start_time,
         end_time: meeting_end_time,
         api_code: reservation.api_code.clone(),
+        time_zone,
     })
 }
 
+// Parse the `scheduled_at` time of a form slot
+//
+// RFC 3339 times are used with whatever offset they carry. Times without an
+// offset (e.g. "2025-03-30T09:00:00" or "2025-03-30 09:00") are local time
+// in `time_zone`.
+pub fn parse_scheduled_at(scheduled_at: &str, time_zone: Tz) -> Result<DateTime<Utc>, String> {
+    const LOCAL_FORMATS: [&str; 4] = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ];
+
+    let rfc3339_error = match DateTime::parse_from_rfc3339(scheduled_at) {
+        Ok(time) => return Ok(time.with_timezone(&Utc)),
+        Err(e) => e,
+    };
+
+    let local = LOCAL_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(scheduled_at, format).ok())
+        .ok_or_else(|| rfc3339_error.to_string())?;
+
+    resolve_local_time(local, time_zone)
+        .ok_or_else(|| format!("{} does not exist in {}", scheduled_at, time_zone))
+}
+
+// Duration of the time range in a scheduled label (e.g. "2025-03-30 09:00-10:00")
+//
+// A range whose end is before its start runs past midnight. Returns None if
+// the label doesn't parse.
+pub fn label_duration(scheduled_label: &str) -> Option<chrono::Duration> {
+    ScheduledLabel::parse(scheduled_label)
+        .map(|label| label.duration())
+        .ok()
+}
+
+// Build a scheduled label (e.g. "2025-03-30 09:00-10:00") for a time range
+//
+// Labels use local time in `time_zone` like the form service does.
+// A range that ends on a later day (other than at midnight right after the
+// start day) gets the end date as well, e.g. "2025-03-30 23:00-2025-03-31 01:00".
+pub fn format_scheduled_label(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    time_zone: Tz,
+) -> String {
+    ScheduledLabel {
+        start: start_time.with_timezone(&time_zone).naive_local(),
+        end: end_time.with_timezone(&time_zone).naive_local(),
+    }
+    .to_string()
+}
+
+// Build the combined scheduled label for a group of slots (e.g. "2025-04-01 09:00-11:00")
+//
+// Uses the date and start time of the earliest slot and the end time of the
+// latest. If the latest slot is on a later date, its date is included, e.g.
+// "2025-04-01 23:00-2025-04-02 01:00".
+pub fn merged_scheduled_label(time_slots: &[TimeSlot]) -> String {
+    let mut sorted_slots = time_slots.to_vec();
+    sorted_slots.sort_by_key(|slot| slot.start_time);
+
+    let (first_slot, last_slot) = match (sorted_slots.first(), sorted_slots.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return String::new(),
+    };
+
+    match (
+        ScheduledLabel::parse(&first_slot.scheduled_label),
+        ScheduledLabel::parse(&last_slot.scheduled_label),
+    ) {
+        (Ok(first), Ok(last)) => ScheduledLabel {
+            start: first.start,
+            end: last.end,
+        }
+        .to_string(),
+        // Slots are parsed before they are merged, so this is not expected
+        _ => {
+            warn!(
+                "Cannot merge unparseable labels '{}' and '{}'",
+                first_slot.scheduled_label, last_slot.scheduled_label
+            );
+            first_slot.scheduled_label.clone()
+        }
+    }
+}
+
 // Attempt to find mergeable groups in time slots
 pub fn find_mergeable_groups(slots: &[TimeSlot]) -> Vec<Vec<TimeSlot>> {
     if slots.is_empty() {
@@ -241,13 +310,13 @@ pub fn find_mergeable_groups(slots: &[TimeSlot]) -> Vec<Vec<TimeSlot>> {
 }
 
 // Create a meeting with the given time slot
-pub async fn create_meeting_with_time_slot(
-    client: &TencentMeetingClient,
+pub async fn create_meeting_with_time_slot<C: MeetingApi + ?Sized>(
+    client: &C,
     _dept_field_name: &str, // Preserved for API compatibility
     form_submission: &FormSubmission,
     time_slot: &TimeSlot,
     user_field_name: &str,
-) -> Result<MeetingResult, StatusCode> {
+) -> Result<MeetingResult, TencentApiError> {
     // Get operator information based on the form submission
     let (operator_name, operator_id) = get_operator_info(client, form_submission, user_field_name);
 
@@ -266,7 +335,7 @@ pub async fn create_meeting_with_time_slot(
             &form_submission.form_name,
             time_slot.item_name.as_str(),
         )),
-        time_zone: Some("Asia/Shanghai".to_string()),
+        time_zone: Some(time_slot.time_zone.name().to_string()),
         guests: None,
     };
 
@@ -296,6 +365,11 @@ pub async fn create_meeting_with_time_slot(
                     room_name: time_slot.item_name.clone(),
                     time_slots: vec![time_slot.scheduled_label.clone()],
                     success: true,
+                    action: MeetingAction::Created,
+                    room_booking: RoomBooking::NotAttempted,
+                    error: None,
+                    join_url: None,
+                    conflicts: Vec::new(),
                 })
             } else {
                 let meeting_info = &response.meeting_info_list[0];
@@ -310,26 +384,33 @@ pub async fn create_meeting_with_time_slot(
                     room_name: time_slot.item_name.clone(),
                     time_slots: vec![time_slot.scheduled_label.clone()],
                     success: true,
+                    action: MeetingAction::Created,
+                    room_booking: RoomBooking::NotAttempted,
+                    error: None,
+                    join_url: meeting_info.join_url.clone(),
+                    conflicts: Vec::new(),
                 })
             }
         }
         Err(err) => {
             error!("Failed to create meeting: {}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(err)
         }
     }
 }
 
 // Create a merged meeting from multiple time slots
-pub async fn create_merged_meeting(
-    client: &TencentMeetingClient,
+pub async fn create_merged_meeting<C: MeetingApi + ?Sized>(
+    client: &C,
     _dept_field_name: &str, // Preserved for API compatibility
     form_submission: &FormSubmission,
     time_slots: &[TimeSlot],
     user_field_name: &str,
-) -> Result<MeetingResult, StatusCode> {
+) -> Result<MeetingResult, TencentApiError> {
     if time_slots.is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(TencentApiError::InvalidRequest(
+            "No time slots to merge".to_string(),
+        ));
     }
 
     // Sort time slots to ensure correct merging
@@ -376,7 +457,7 @@ pub async fn create_merged_meeting(
             &form_submission.form_name,
             room_name.as_str(),
         )),
-        time_zone: Some("Asia/Shanghai".to_string()),
+        time_zone: Some(sorted_slots[0].time_zone.name().to_string()),
         guests: None,
     };
 
@@ -406,6 +487,11 @@ pub async fn create_merged_meeting(
                     room_name: room_name.clone(),
                     time_slots: time_slot_labels,
                     success: true,
+                    action: MeetingAction::Created,
+                    room_booking: RoomBooking::NotAttempted,
+                    error: None,
+                    join_url: None,
+                    conflicts: Vec::new(),
                 })
             } else {
                 let meeting_info = &response.meeting_info_list[0];
@@ -420,12 +506,17 @@ pub async fn create_merged_meeting(
                     room_name: room_name.clone(),
                     time_slots: time_slot_labels,
                     success: true,
+                    action: MeetingAction::Created,
+                    room_booking: RoomBooking::NotAttempted,
+                    error: None,
+                    join_url: meeting_info.join_url.clone(),
+                    conflicts: Vec::new(),
                 })
             }
         }
         Err(err) => {
             error!("Failed to create merged meeting: {}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(err)
         }
     }
 }
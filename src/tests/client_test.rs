@@ -1,7 +1,12 @@
+use axum::http::StatusCode;
+use std::time::Duration;
+
 use crate::client::{
-    BookRoomsRequest, CancelMeetingRequest, CreateMeetingRequest, ReleaseRoomsRequest, User,
+    BookRoomsRequest, CancelMeetingRequest, CreateMeetingRequest, MeetingApi, ReleaseRoomsRequest,
+    RetryPolicy, TencentApiError, TencentMeetingClient, UpdateMeetingRequest, User,
 };
-use crate::tests::common::mocks::setup_mock_client;
+use crate::tests::common::mocks::{setup_mock_client, MockTencentMeetingClient};
+use crate::tests::common::stub_server::StubTencentServer;
 
 /// Client tests module
 #[cfg(test)]
@@ -63,7 +68,7 @@ mod client_tests {
 
     #[tokio::test]
     async fn test_cancel_meeting() {
-        let (mock_client, _data_store) = setup_mock_client();
+        let (mock_client, data_store) = setup_mock_client();
 
         // First create a meeting
         let create_request = CreateMeetingRequest {
@@ -104,8 +109,8 @@ mod client_tests {
         assert!(cancel_result.is_ok());
 
         // Verify that the meeting was removed from the data store
-        // let meeting = data_store.get_meeting(meeting_id);
-        // assert!(meeting.is_none());
+        let meeting = data_store.get_meeting(meeting_id);
+        assert!(meeting.is_none());
     }
 
     #[tokio::test]
@@ -182,7 +187,7 @@ mod client_tests {
             subject_visible: Some(true),
         };
 
-        let _ = mock_client
+        mock_client
             .book_rooms(meeting_id, &book_request)
             .await
             .unwrap();
@@ -267,6 +272,362 @@ mod client_tests {
         assert!(cancel_result.is_ok());
     }
 
-    // TODO: Add error handling tests in the future
-    // For now, we're skipping error tests due to complexities in creating reqwest::Error objects
-}
\ No newline at end of file
+    #[test]
+    fn test_error_from_response_parses_error_info() {
+        let body = r#"{"error_info":{"error_code":190303,"new_error_code":9061,"message":"signature invalid"}}"#;
+        let err = TencentApiError::from_response(401, body, Some("trace-123".to_string()));
+
+        assert!(matches!(err, TencentApiError::Auth(_)));
+        let detail = err.detail().unwrap();
+        assert_eq!(detail.http_status, 401);
+        assert_eq!(detail.error_code, Some(190303));
+        assert_eq!(detail.new_error_code, Some(9061));
+        assert_eq!(detail.message, "signature invalid");
+        assert_eq!(detail.request_id.as_deref(), Some("trace-123"));
+        assert!(err.to_string().contains("trace-123"));
+    }
+
+    #[test]
+    fn test_error_from_response_classification() {
+        let body = r#"{"error_info":{"error_code":1,"message":"x"}}"#;
+
+        let err = TencentApiError::from_response(429, body, None);
+        assert!(matches!(err, TencentApiError::RateLimited(_)));
+        assert_eq!(err.status_code(), StatusCode::TOO_MANY_REQUESTS);
+
+        let err = TencentApiError::from_response(404, body, None);
+        assert!(matches!(err, TencentApiError::NotFound(_)));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+
+        let err = TencentApiError::from_response(503, body, None);
+        assert!(matches!(err, TencentApiError::Server(_)));
+        assert_eq!(err.status_code(), StatusCode::BAD_GATEWAY);
+
+        let err = TencentApiError::from_response(400, body, None);
+        assert!(matches!(err, TencentApiError::Business(_)));
+        assert_eq!(err.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn test_error_from_response_without_envelope() {
+        let err = TencentApiError::from_response(502, "Bad Gateway\n", None);
+
+        let detail = err.detail().unwrap();
+        assert_eq!(detail.error_code, None);
+        assert_eq!(detail.message, "Bad Gateway");
+    }
+
+    #[tokio::test]
+    async fn test_book_rooms_error_is_propagated() {
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client.expect_book_rooms().returning(|_, _| {
+            Err(TencentApiError::from_response(
+                400,
+                r#"{"error_info":{"error_code":500125,"message":"room already booked"}}"#,
+                None,
+            ))
+        });
+
+        let book_request = BookRoomsRequest {
+            operator_id: "test_operator".to_string(),
+            operator_id_type: 1,
+            meeting_room_id_list: vec!["room1".to_string()],
+            subject_visible: Some(true),
+        };
+
+        let err = mock_client
+            .book_rooms("meeting_1", &book_request)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, TencentApiError::Business(_)));
+        assert_eq!(err.detail().unwrap().message, "room already booked");
+    }
+
+    fn fast_retry_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            budget: Duration::from_secs(5),
+        }
+    }
+
+    fn stub_client(server: &StubTencentServer, max_attempts: u32) -> TencentMeetingClient {
+        TencentMeetingClient::with_credentials("app", "secret_id", "secret_key", &server.endpoint)
+            .with_retry_policy(fast_retry_policy(max_attempts))
+    }
+
+    fn test_book_request() -> BookRoomsRequest {
+        BookRoomsRequest {
+            operator_id: "test_operator".to_string(),
+            operator_id_type: 1,
+            meeting_room_id_list: vec!["room1".to_string()],
+            subject_visible: Some(true),
+        }
+    }
+
+    fn test_release_request() -> ReleaseRoomsRequest {
+        ReleaseRoomsRequest {
+            operator_id: "test_operator".to_string(),
+            operator_id_type: 1,
+            meeting_room_id_list: vec!["room1".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff_delay_is_bounded() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+            budget: Duration::from_secs(5),
+        };
+
+        let first = policy.backoff_delay(1);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+
+        let second = policy.backoff_delay(2);
+        assert!(second >= Duration::from_millis(100) && second <= Duration::from_millis(200));
+
+        // Capped at max_delay
+        let tenth = policy.backoff_delay(10);
+        assert!(tenth >= Duration::from_millis(150) && tenth <= Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_release_rooms_retries_server_errors_until_success() {
+        let server = StubTencentServer::start(2, StatusCode::SERVICE_UNAVAILABLE, "").await;
+        let client = stub_client(&server, 3);
+
+        let result = client.release_rooms("meeting_1", &test_release_request()).await;
+        assert!(result.is_ok(), "expected success, got {:?}", result);
+
+        // Each attempt must be signed again with a fresh nonce
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_ne!(requests[0].nonce, requests[1].nonce);
+        assert_ne!(requests[1].signature, requests[2].signature);
+        assert!(requests.iter().all(|r| !r.timestamp.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let server = StubTencentServer::start(5, StatusCode::SERVICE_UNAVAILABLE, "").await;
+        let client = stub_client(&server, 3);
+
+        let err = client
+            .release_rooms("meeting_1", &test_release_request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, TencentApiError::Server(_)));
+        assert_eq!(
+            err.detail().unwrap().request_id.as_deref(),
+            Some("stub-trace-id")
+        );
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_when_budget_is_spent() {
+        let server = StubTencentServer::start(10, StatusCode::SERVICE_UNAVAILABLE, "").await;
+        let client = TencentMeetingClient::with_credentials("app", "secret_id", "secret_key", &server.endpoint)
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 10,
+                base_delay: Duration::from_millis(100),
+                max_delay: Duration::from_millis(100),
+                budget: Duration::from_millis(250),
+            });
+
+        // Delays of 50-100ms leave room for a few attempts, never all ten; a
+        // slow attempt is cut short when the budget runs out
+        let started = std::time::Instant::now();
+        let err = client
+            .release_rooms("meeting_1", &test_release_request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, TencentApiError::Server(_) | TencentApiError::Transport { timeout: true, .. }));
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert!((1..=5).contains(&server.requests().len()));
+    }
+
+    #[tokio::test]
+    async fn test_create_meeting_is_not_retried_on_server_error() {
+        let server = StubTencentServer::start(1, StatusCode::INTERNAL_SERVER_ERROR, "").await;
+        let client = stub_client(&server, 3);
+
+        let request = CreateMeetingRequest {
+            userid: "test_user".to_string(),
+            instanceid: 1,
+            subject: "Retry Meeting".to_string(),
+            type_: 0,
+            _type: 0,
+            guests: None,
+            invitees: None,
+            start_time: "1680000000".to_string(),
+            end_time: "1680003600".to_string(),
+            password: None,
+            time_zone: None,
+            location: None,
+        };
+
+        let err = client.create_meeting(&request).await.unwrap_err();
+        assert!(matches!(err, TencentApiError::Server(_)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_book_rooms_is_not_retried_on_server_error() {
+        // The room may have been booked before the error, so booking again could book it twice
+        let server = StubTencentServer::start(1, StatusCode::SERVICE_UNAVAILABLE, "").await;
+        let client = stub_client(&server, 3);
+
+        let err = client
+            .book_rooms("meeting_1", &test_book_request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, TencentApiError::Server(_)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_meeting_is_retried_when_rate_limited() {
+        let body = r#"{"meeting_number":1,"meeting_info_list":[{"subject":"Retry Meeting","meeting_id":"m1","meeting_code":"123","start_time":"1680000000","end_time":"1680003600"}]}"#;
+        let server = StubTencentServer::start(2, StatusCode::TOO_MANY_REQUESTS, body).await;
+        let client = stub_client(&server, 3);
+
+        let request = CreateMeetingRequest {
+            userid: "test_user".to_string(),
+            instanceid: 1,
+            subject: "Retry Meeting".to_string(),
+            type_: 0,
+            _type: 0,
+            guests: None,
+            invitees: None,
+            start_time: "1680000000".to_string(),
+            end_time: "1680003600".to_string(),
+            password: None,
+            time_zone: None,
+            location: None,
+        };
+
+        let response = client.create_meeting(&request).await.unwrap();
+        assert_eq!(response.meeting_info_list[0].meeting_id, "m1");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_business_errors_are_not_retried() {
+        let server = StubTencentServer::start(1, StatusCode::BAD_REQUEST, "").await;
+        let client = stub_client(&server, 3);
+
+        let err = client
+            .book_rooms("meeting_1", &test_book_request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, TencentApiError::Business(_)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_meeting_parses_meeting_details() {
+        let body = r#"{"meeting_number":1,"meeting_info_list":[{"subject":"Weekly Sync","meeting_id":"m42","meeting_code":"806146667","status":"MEETING_STATE_CANCELLED","start_time":"1680000000","end_time":"1680003600","hosts":[{"userid":"test_operator"}],"location":"Conference Room A","settings":{"mute_enable_join":true}}]}"#;
+        let server = StubTencentServer::start(0, StatusCode::OK, body).await;
+        let client = stub_client(&server, 1);
+
+        let meeting = client.get_meeting("m42", "test_operator").await.unwrap();
+
+        assert_eq!(meeting.meeting_id, "m42");
+        assert_eq!(meeting.subject, "Weekly Sync");
+        assert_eq!(meeting.status.as_deref(), Some("MEETING_STATE_CANCELLED"));
+        assert_eq!(meeting.hosts.unwrap()[0].userid, "test_operator");
+        assert_eq!(meeting.location.as_deref(), Some("Conference Room A"));
+        assert_eq!(meeting.settings.unwrap().mute_enable_join, Some(true));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, reqwest::Method::GET);
+        assert_eq!(requests[0].uri, "/v1/meetings/m42?userid=test_operator&instanceid=32");
+    }
+
+    #[tokio::test]
+    async fn test_get_meeting_with_empty_result_is_invalid_response() {
+        let body = r#"{"meeting_number":0,"meeting_info_list":[]}"#;
+        let server = StubTencentServer::start(0, StatusCode::OK, body).await;
+        let client = stub_client(&server, 1);
+
+        let err = client.get_meeting("m42", "test_operator").await.unwrap_err();
+        assert!(matches!(err, TencentApiError::InvalidResponse(_)));
+    }
+
+    #[tokio::test]
+    async fn test_get_meeting_not_found() {
+        let server = StubTencentServer::start(1, StatusCode::NOT_FOUND, "").await;
+        let client = stub_client(&server, 3);
+
+        let err = client.get_meeting("missing", "test_operator").await.unwrap_err();
+        assert!(matches!(err, TencentApiError::NotFound(_)));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    fn test_update_request() -> UpdateMeetingRequest {
+        UpdateMeetingRequest {
+            userid: "test_operator".to_string(),
+            instanceid: 32,
+            subject: Some("Moved Meeting".to_string()),
+            start_time: Some("1680007200".to_string()),
+            end_time: Some("1680010800".to_string()),
+            password: None,
+            time_zone: None,
+            location: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_meeting() {
+        let body = r#"{"meeting_number":1,"meeting_info_list":[{"meeting_id":"m42","meeting_code":"806146667"}]}"#;
+        let server = StubTencentServer::start(0, StatusCode::OK, body).await;
+        let client = stub_client(&server, 1);
+
+        let response = client.update_meeting("m42", &test_update_request()).await.unwrap();
+
+        assert_eq!(response.meeting_info_list[0].meeting_id, "m42");
+        assert_eq!(response.meeting_info_list[0].meeting_code, "806146667");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, reqwest::Method::PUT);
+        assert_eq!(requests[0].uri, "/v1/meetings/m42");
+    }
+
+    #[tokio::test]
+    async fn test_update_meeting_rejects_invalid_requests() {
+        let server = StubTencentServer::start(0, StatusCode::OK, "").await;
+        let client = stub_client(&server, 1);
+
+        // Start time without end time
+        let mut request = test_update_request();
+        request.end_time = None;
+        let err = client.update_meeting("m42", &request).await.unwrap_err();
+        assert!(matches!(err, TencentApiError::InvalidRequest(_)));
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+
+        // Nothing to change
+        let request = UpdateMeetingRequest {
+            subject: None,
+            start_time: None,
+            end_time: None,
+            ..test_update_request()
+        };
+        let err = client.update_meeting("m42", &request).await.unwrap_err();
+        assert!(matches!(err, TencentApiError::InvalidRequest(_)));
+
+        // Neither request reached Tencent
+        assert!(server.requests().is_empty());
+    }
+}
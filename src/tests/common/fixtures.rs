@@ -1,4 +1,6 @@
 use crate::models::form::{FormEntry, FormField1Item, FormSubmission};
+use crate::models::reservation::ReservationStatus;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
 use crate::services::database::MeetingRecord;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -49,9 +51,9 @@ pub fn generate_test_form_submission(token: &str, status: &str) -> FormSubmissio
 pub fn generate_test_meeting(
     id: &str,
     token: &str,
-    status: &str,
+    status: ReservationStatus,
     start_time: DateTime<Utc>,
-    _end_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
 ) -> MeetingRecord {
     MeetingRecord {
         entry_token: token.to_string(),
@@ -61,12 +63,21 @@ pub fn generate_test_meeting(
         room_name: "Test Room".to_string(),
         scheduled_at: start_time.to_rfc3339(),
         scheduled_label: "2035-03-30 09:00-10:00".to_string(),
-        status: status.to_string(),
+        status,
+        start_time: start_time.to_rfc3339(),
+        end_time: end_time.to_rfc3339(),
+        slot_labels: vec!["2035-03-30 09:00-10:00".to_string()],
         meeting_id: id.to_string(),
         room_id: "123456".to_string(),
         created_at: Utc::now().to_rfc3339(),
         cancelled_at: "".to_string(),
         operator_name: "Test Operator".to_string(),
         operator_id: "test_operator".to_string(),
+        join_url: format!("https://meeting.tencent.com/dm/{}", id),
+        time_zone: DEFAULT_TIME_ZONE.name().to_string(),
+        local_start_time: start_time.with_timezone(&DEFAULT_TIME_ZONE).to_rfc3339(),
+        local_end_time: end_time.with_timezone(&DEFAULT_TIME_ZONE).to_rfc3339(),
+        sequence: 0,
+        updated_at: String::new(),
     }
 }
\ No newline at end of file
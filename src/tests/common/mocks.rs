@@ -1,10 +1,12 @@
+use async_trait::async_trait;
 use mockall::mock;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use crate::client::{
     BookRoomsRequest, CancelMeetingRequest, CreateMeetingRequest, CreateMeetingResponse,
-    MeetingInfo, MeetingRoomItem, MeetingRoomsResponse, ReleaseRoomsRequest, TencentMeetingClient,
+    MeetingApi, MeetingInfo, MeetingRoomItem, MeetingRoomsResponse, ReleaseRoomsRequest,
+    TencentApiError, UpdateMeetingRequest, UpdateMeetingResponse, UpdatedMeetingInfo, User,
 };
 
 // Using the mockall for new wrappers
@@ -16,50 +18,58 @@ mock! {
     }
 }
 
-// Implement a trait for MockTencentMeetingClient to convert it to TencentMeetingClient
-// This will allow us to use it in place of TencentMeetingClient
-impl From<MockTencentMeetingClient> for TencentMeetingClient {
-    fn from(_mock: MockTencentMeetingClient) -> Self {
-        // Create a default client - it won't actually be used because the tests use simulation mode
-        TencentMeetingClient::default()
-    }
-}
-
-// Define a mock client for the Tencent Meeting API
+// Define a mock Tencent Meeting backend implementing the MeetingApi trait
+// so it can be used in AppState in place of the real client
 mock! {
-    pub TencentMeetingClient {
-        // Mock the getter for operator_id
-        pub fn get_operator_id(&self) -> &str;
+    pub TencentMeetingClient {}
 
-        // Mock all the API methods
-        pub async fn list_rooms(
+    #[async_trait]
+    impl MeetingApi for TencentMeetingClient {
+        async fn list_rooms(
             &self,
             page: usize,
             page_size: usize,
-        ) -> Result<MeetingRoomsResponse, reqwest::Error>;
+        ) -> Result<MeetingRoomsResponse, TencentApiError>;
+
+        async fn create_meeting(
+            &self,
+            meeting_request: &CreateMeetingRequest,
+        ) -> Result<CreateMeetingResponse, TencentApiError>;
+
+        async fn get_meeting(
+            &self,
+            meeting_id: &str,
+            userid: &str,
+        ) -> Result<MeetingInfo, TencentApiError>;
 
-        pub async fn create_meeting(
+        async fn update_meeting(
             &self,
-            request: &CreateMeetingRequest,
-        ) -> Result<CreateMeetingResponse, reqwest::Error>;
+            meeting_id: &str,
+            update_request: &UpdateMeetingRequest,
+        ) -> Result<UpdateMeetingResponse, TencentApiError>;
 
-        pub async fn cancel_meeting(
+        async fn cancel_meeting(
             &self,
             meeting_id: &str,
-            request: &CancelMeetingRequest,
-        ) -> Result<(), reqwest::Error>;
+            cancel_request: &CancelMeetingRequest,
+        ) -> Result<(), TencentApiError>;
 
-        pub async fn book_rooms(
+        async fn book_rooms(
             &self,
             meeting_id: &str,
-            request: &BookRoomsRequest,
-        ) -> Result<(), reqwest::Error>;
+            book_request: &BookRoomsRequest,
+        ) -> Result<(), TencentApiError>;
 
-        pub async fn release_rooms(
+        async fn release_rooms(
             &self,
             meeting_id: &str,
-            request: &ReleaseRoomsRequest,
-        ) -> Result<(), reqwest::Error>;
+            release_request: &ReleaseRoomsRequest,
+        ) -> Result<(), TencentApiError>;
+
+        // Mock the getters for operator IDs
+        fn get_operator_id(&self) -> &str;
+
+        fn get_operator_id_by_name(&self, name: &str) -> String;
     }
 }
 
@@ -120,13 +130,33 @@ impl MockDataStore {
         meetings.insert(meeting_id, meeting_info);
     }
 
-    /*
-    // Private helper method - currently not used but kept for possible future use
-    fn get_meeting(&self, meeting_id: &str) -> Option<MeetingInfo> {
+    pub fn get_meeting(&self, meeting_id: &str) -> Option<MeetingInfo> {
         let meetings = self.meetings.lock().unwrap();
         meetings.get(meeting_id).cloned()
     }
-    */
+
+    pub fn booked_rooms(&self, meeting_id: &str) -> Option<Vec<String>> {
+        let booked_rooms = self.booked_rooms.lock().unwrap();
+        booked_rooms.get(meeting_id).cloned()
+    }
+
+    pub fn update_meeting(&self, meeting_id: &str, request: &UpdateMeetingRequest) -> Option<MeetingInfo> {
+        let mut meetings = self.meetings.lock().unwrap();
+        let meeting = meetings.get_mut(meeting_id)?;
+
+        if let Some(subject) = &request.subject {
+            meeting.subject = subject.clone();
+        }
+        if let (Some(start_time), Some(end_time)) = (&request.start_time, &request.end_time) {
+            meeting.start_time = start_time.clone();
+            meeting.end_time = end_time.clone();
+        }
+        if let Some(location) = &request.location {
+            meeting.location = Some(location.clone());
+        }
+
+        Some(meeting.clone())
+    }
 
     pub fn cancel_meeting(&self, meeting_id: &str) -> bool {
         // Just remove the meeting from our store to simulate cancellation
@@ -175,10 +205,13 @@ pub fn setup_mock_client() -> (MockTencentMeetingClient, Arc<MockDataStore>) {
 
     let mut mock_client = MockTencentMeetingClient::default();
 
-    // Mock operator_id getter
+    // Mock operator_id getters
     mock_client
         .expect_get_operator_id()
         .return_const("test_operator".to_string());
+    mock_client
+        .expect_get_operator_id_by_name()
+        .returning(|_| "test_operator".to_string());
 
     // Mock list_rooms
     let store_ref1 = Arc::clone(&data_store);
@@ -218,6 +251,14 @@ pub fn setup_mock_client() -> (MockTencentMeetingClient, Arc<MockDataStore>) {
                 start_time: request.start_time.clone(),
                 end_time: request.end_time.clone(),
                 join_url: Some(format!("https://example.com/join/{}", meeting_id)),
+                status: Some("MEETING_STATE_INIT".to_string()),
+                hosts: Some(vec![User {
+                    userid: request.userid.clone(),
+                    is_anonymous: None,
+                    nick_name: None,
+                }]),
+                location: request.location.clone(),
+                settings: None,
             };
 
             // Store the meeting
@@ -229,6 +270,43 @@ pub fn setup_mock_client() -> (MockTencentMeetingClient, Arc<MockDataStore>) {
             })
         });
 
+    // Mock get_meeting
+    let store_ref_get = Arc::clone(&data_store);
+    mock_client
+        .expect_get_meeting()
+        .returning(move |meeting_id, _userid| {
+            store_ref_get.get_meeting(meeting_id).ok_or_else(|| {
+                TencentApiError::from_response(
+                    404,
+                    r#"{"error_info":{"error_code":9061,"message":"meeting not found"}}"#,
+                    None,
+                )
+            })
+        });
+
+    // Mock update_meeting
+    let store_ref_update = Arc::clone(&data_store);
+    mock_client
+        .expect_update_meeting()
+        .returning(move |meeting_id, request| {
+            request.validate()?;
+            let meeting = store_ref_update.update_meeting(meeting_id, request).ok_or_else(|| {
+                TencentApiError::from_response(
+                    404,
+                    r#"{"error_info":{"error_code":9061,"message":"meeting not found"}}"#,
+                    None,
+                )
+            })?;
+
+            Ok(UpdateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![UpdatedMeetingInfo {
+                    meeting_id: meeting.meeting_id,
+                    meeting_code: meeting.meeting_code,
+                }],
+            })
+        });
+
     // Mock cancel_meeting
     let store_ref3 = Arc::clone(&data_store);
     mock_client
@@ -237,7 +315,6 @@ pub fn setup_mock_client() -> (MockTencentMeetingClient, Arc<MockDataStore>) {
             // Cancel the meeting in the store
             let _cancelled = store_ref3.cancel_meeting(meeting_id);
             // For testing purposes, we'll always succeed
-            // This is a simplification to avoid creating reqwest::Error objects
             Ok(())
         });
 
@@ -249,7 +326,6 @@ pub fn setup_mock_client() -> (MockTencentMeetingClient, Arc<MockDataStore>) {
             // Book the rooms in the store
             let _booked = store_ref4.book_room(meeting_id, &request.meeting_room_id_list);
             // For testing purposes, we'll always succeed
-            // This is a simplification to avoid creating reqwest::Error objects
             Ok(())
         });
 
@@ -261,7 +337,6 @@ pub fn setup_mock_client() -> (MockTencentMeetingClient, Arc<MockDataStore>) {
             // Release the rooms in the store
             let _released = store_ref5.release_room(meeting_id);
             // For testing purposes, we'll always succeed
-            // This is a simplification to avoid creating reqwest::Error objects
             Ok(())
         });
 
@@ -1,3 +1,4 @@
 pub mod fixtures;
 pub mod mocks;
+pub mod stub_server;
 pub mod test_utils;
\ No newline at end of file
@@ -0,0 +1,107 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Method, Response, StatusCode, Uri},
+    Router,
+};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// A request received by the stub Tencent server
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub method: Method,
+    pub uri: String,
+    pub nonce: String,
+    pub timestamp: String,
+    pub signature: String,
+}
+
+struct StubState {
+    failures_remaining: Mutex<usize>,
+    failure_status: StatusCode,
+    success_body: String,
+    requests: Mutex<Vec<ReceivedRequest>>,
+}
+
+/// Local HTTP stand-in for the Tencent Meeting API
+///
+/// Answers the first `failures` requests with `failure_status` and an
+/// `error_info` body, then answers every later request with `200` and
+/// `success_body`. Every request's auth headers are recorded.
+pub struct StubTencentServer {
+    pub endpoint: String,
+    state: Arc<StubState>,
+}
+
+impl StubTencentServer {
+    pub async fn start(failures: usize, failure_status: StatusCode, success_body: &str) -> Self {
+        let state = Arc::new(StubState {
+            failures_remaining: Mutex::new(failures),
+            failure_status,
+            success_body: success_body.to_string(),
+            requests: Mutex::new(Vec::new()),
+        });
+
+        let app = Router::new()
+            .fallback(handle_request)
+            .with_state(Arc::clone(&state));
+
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Self {
+            endpoint: format!("http://{}", addr),
+            state,
+        }
+    }
+
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+async fn handle_request(
+    State(state): State<Arc<StubState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response<Body> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    state.requests.lock().unwrap().push(ReceivedRequest {
+        method,
+        uri: uri.to_string(),
+        nonce: header("X-TC-Nonce"),
+        timestamp: header("X-TC-Timestamp"),
+        signature: header("X-TC-Signature"),
+    });
+
+    let mut failures_remaining = state.failures_remaining.lock().unwrap();
+    if *failures_remaining > 0 {
+        *failures_remaining -= 1;
+        return Response::builder()
+            .status(state.failure_status)
+            .header("X-TC-Trace", "stub-trace-id")
+            .body(Body::from(
+                r#"{"error_info":{"error_code":500000,"message":"stub failure"}}"#,
+            ))
+            .unwrap();
+    }
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .body(Body::from(state.success_body.clone()))
+        .unwrap()
+}
@@ -14,7 +14,7 @@ pub async fn create_test_app(
     // Create app state
     let app_state = Arc::new(AppState {
         client,
-        database: Arc::clone(&database),
+        database: database.into(),
         user_field_name: "user_field_name".to_string(),
         dept_field_name: "department_field_name".to_string(),
         xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -22,10 +22,20 @@ pub async fn create_test_app(
         skip_meeting_creation: false,
         skip_room_booking: false,
         webhook_auth_token: None,         // No auth required for tests
+        calendar_feed_token: None,
+        submission_locks: Default::default(),
+        room_locks: Default::default(),
+        job_queue: None,
+        booking_failure_policy: Default::default(),
+        status_mapping: Default::default(),
+        time_zones: Default::default(),
+        room_conflict_policy: Default::default(),
+        booking_policies: Default::default(),
+        blackout_calendars: Default::default(),
     });
     
     // Create the router
-    create_router(app_state, false)
+    create_router(app_state, false, false)
 }
 
 /// Create a test request with the specified method, uri, and body
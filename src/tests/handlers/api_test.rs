@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use axum::{
     extract::{Query, State},
@@ -5,13 +6,24 @@ use axum::{
 };
 use axum_test::{TestServer, TestServerConfig};
 use tempfile::tempdir;
+use chrono::{TimeZone, Utc};
 use serde_json::json;
 
-use crate::client::TencentMeetingClient;
+use crate::client::{CreateMeetingResponse, MeetingInfo, TencentApiError, UpdateMeetingResponse, UpdatedMeetingInfo};
 use crate::tests::common::mocks::{MockTencentMeetingClient, setup_mock_client};
-use crate::handlers::api::{AppState, handle_form_submission, WebhookQueryParams};
+use crate::handlers::api::{AppState, handle_form_submission, process_form_submission, WebhookQueryParams};
+use crate::handlers::cancellations::{sweep_pending_cancellations, CancellationRetries};
+use crate::handlers::jobs::spawn_job_worker;
+use crate::services::jobs::JobQueue;
 use crate::models::form::FormSubmission;
-use crate::services::database::DatabaseService;
+use crate::models::blackout::BlackoutCalendar;
+use crate::models::booking_policy::BookingPolicyConfig;
+use crate::models::meeting::{BookingFailurePolicy, MeetingAction, RoomConflictPolicy};
+use crate::models::reservation::{ReservationStatus, StatusMapping};
+use crate::models::time_zone::{TimeZoneConfig, DEFAULT_TIME_ZONE};
+use crate::services::blackouts::BlackoutCalendars;
+use crate::services::database::{DatabaseService, MeetingStore};
+use crate::services::time_slots::parse_time_slot;
 use crate::routes::create_router;
 
 /// API handler tests
@@ -33,8 +45,8 @@ mod api_tests {
         
         // Create app state
         let app_state = Arc::new(AppState {
-            client: TencentMeetingClient::default(), // Use default client for tests - simulation mode
-            database: Arc::clone(&db_service),
+            client: Arc::clone(&mock_client_arc), // Mock backend - simulation mode
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -42,10 +54,20 @@ mod api_tests {
             skip_meeting_creation: true,      // Use simulation mode for tests
             skip_room_booking: true,
             webhook_auth_token: None,         // No auth token for tests by default
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
         });
         
         // Create the router - always use development mode in tests
-        let router = create_router(app_state, false);
+        let router = create_router(app_state, false, false);
         
         // Set up the test server
         let config = TestServerConfig::builder()
@@ -77,8 +99,8 @@ mod api_tests {
         
         // Create app state
         let app_state = Arc::new(AppState {
-            client: TencentMeetingClient::default(), // Use default client for tests - simulation mode
-            database: Arc::clone(&db_service),
+            client: Arc::clone(&mock_client_arc), // Mock backend - simulation mode
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -86,10 +108,16 @@ mod api_tests {
             skip_meeting_creation: true,      // Use simulation mode for tests
             skip_room_booking: true,
             webhook_auth_token: Some(auth_token.clone()),
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
         });
         
         // Create the router - always use development mode in tests
-        let router = create_router(app_state, false);
+        let router = create_router(app_state, false, false);
         
         // Set up the test server
         let config = TestServerConfig::builder()
@@ -115,8 +143,8 @@ mod api_tests {
         
         // Create app state with simulation mode enabled
         let app_state = Arc::new(AppState {
-            client: TencentMeetingClient::default(), // Use default client for tests - simulation mode
-            database: Arc::clone(&db_service),
+            client: Arc::clone(&mock_client_arc), // Mock backend - simulation mode
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -124,10 +152,20 @@ mod api_tests {
             skip_meeting_creation: true,      // Simulation mode ON
             skip_room_booking: true,          // Simulation mode ON
             webhook_auth_token: None,         // No auth token for tests by default
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
         });
         
         // Create the router - always use development mode in tests
-        let router = create_router(app_state, false);
+        let router = create_router(app_state, false, false);
         
         // Set up the test server
         let config = TestServerConfig::builder()
@@ -148,12 +186,11 @@ mod api_tests {
         
         // Set up mock client
         let (mock_client, _) = setup_mock_client();
-        let _mock_client_arc = mock_client; // No need for Arc wrapping here, we'll use it directly
         
-        // Create app state with simulation mode using the default client
+        // Create app state with simulation mode using the mock client
         let app_state = Arc::new(AppState {
-            client: TencentMeetingClient::default(), // Use default client - we're in simulation mode
-            database: Arc::clone(&db_service),
+            client: mock_client, // Mock backend - we're in simulation mode
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -161,6 +198,16 @@ mod api_tests {
             skip_meeting_creation: true,
             skip_room_booking: true,
             webhook_auth_token: None,         // No auth required
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
         });
         
         // Create a form submission payload
@@ -310,10 +357,11 @@ mod api_tests {
         
         let db_service = Arc::new(DatabaseService::new(&csv_path_str));
         
-        // Create app state using the default client
+        // Create app state using the mock client
+        let (mock_client, _) = setup_mock_client();
         let app_state = Arc::new(AppState {
-            client: TencentMeetingClient::default(), // We're in simulation mode
-            database: Arc::clone(&db_service),
+            client: mock_client, // We're in simulation mode
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -321,10 +369,20 @@ mod api_tests {
             skip_meeting_creation: true,      // Use simulation mode for tests
             skip_room_booking: true,
             webhook_auth_token: None,         // No auth token for tests by default
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
         });
         
         // Create the router - always use development mode in tests
-        let router = create_router(app_state, false);
+        let router = create_router(app_state, false, false);
         
         // Set up the test server
         let config = TestServerConfig::builder()
@@ -387,10 +445,11 @@ mod api_tests {
         // Create a test auth token
         let auth_token = "test_auth_token_123".to_string();
         
-        // Create app state using the default client
+        // Create app state using the mock client
+        let (mock_client, _) = setup_mock_client();
         let app_state = Arc::new(AppState {
-            client: TencentMeetingClient::default(), // We're in simulation mode
-            database: Arc::clone(&db_service),
+            client: mock_client, // We're in simulation mode
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(),  // Xi'an room ID
@@ -398,10 +457,20 @@ mod api_tests {
             skip_meeting_creation: true,      // Use simulation mode for tests
             skip_room_booking: true,
             webhook_auth_token: Some(auth_token.clone()),
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
         });
         
         // Create the router - always use development mode in tests
-        let router = create_router(app_state, false);
+        let router = create_router(app_state, false, false);
         
         // Set up the test server
         let config = TestServerConfig::builder()
@@ -500,4 +569,1406 @@ mod api_tests {
         assert!(response.status_code() == StatusCode::BAD_REQUEST ||
                 response.status_code() == StatusCode::OK);
     }
-}
\ No newline at end of file
+
+    // Helper function for exercising the real (non-simulation) webhook paths
+    // against the in-memory mock backend. The TempDir must outlive the test.
+    fn setup_backend_test_server(
+        mock_client: MockTencentMeetingClient,
+    ) -> (TestServer, Arc<DatabaseService>, tempfile::TempDir) {
+        setup_backend_test_server_with_policy(mock_client, BookingFailurePolicy::CancelMeeting)
+    }
+
+    fn setup_backend_test_server_with_policy(
+        mock_client: MockTencentMeetingClient,
+        booking_failure_policy: BookingFailurePolicy,
+    ) -> (TestServer, Arc<DatabaseService>, tempfile::TempDir) {
+        setup_backend_test_server_with_options(mock_client, booking_failure_policy, StatusMapping::default(), TimeZoneConfig::default(), RoomConflictPolicy::default(), BookingPolicyConfig::default(), Arc::new(BlackoutCalendars::default()))
+    }
+
+    fn setup_backend_test_server_with_options(
+        mock_client: MockTencentMeetingClient,
+        booking_failure_policy: BookingFailurePolicy,
+        status_mapping: StatusMapping,
+        time_zones: TimeZoneConfig,
+        room_conflict_policy: RoomConflictPolicy,
+        booking_policies: BookingPolicyConfig,
+        blackout_calendars: Arc<BlackoutCalendars>,
+    ) -> (TestServer, Arc<DatabaseService>, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+
+        let app_state = Arc::new(AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false, // Create meetings through the mock backend
+            skip_room_booking: false,
+            webhook_auth_token: None,
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy,
+            status_mapping,
+            time_zones,
+            room_conflict_policy,
+            booking_policies,
+            blackout_calendars,
+        });
+
+        let router = create_router(app_state, false, false);
+        let config = TestServerConfig::builder().mock_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        (server, db_service, dir)
+    }
+
+    fn backend_form_payload(token: &str, status: &str) -> serde_json::Value {
+        json!({
+            "form": "test_form",
+            "form_name": "西安会议室预约",
+            "entry": {
+                "token": token,
+                "field_1": [
+                    {
+                        "item_name": "Conference Room A",
+                        "scheduled_label": "2035-03-30 09:00-10:00",
+                        "number": 1,
+                        "scheduled_at": "2035-03-30T01:00:00.000Z",
+                        "api_code": "CODE1"
+                    },
+                    {
+                        "item_name": "Conference Room A",
+                        "scheduled_label": "2035-03-30 10:00-11:00",
+                        "number": 1,
+                        "scheduled_at": "2035-03-30T02:00:00.000Z",
+                        "api_code": "CODE2"
+                    }
+                ],
+                "field_8": "Backend Meeting",
+                "user_field_name": "Test User",
+                "department_field_name": "Test Department",
+                "reservation_status_fsf_field": status
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_webhook_creates_and_books_meeting_with_backend() {
+        let (mock_client, data_store) = setup_mock_client();
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("backend_token", "已预约"))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(body["meetings_count"], json!(1));
+        assert_eq!(body["meetings"][0]["merged"], json!(true));
+
+        // The meeting was created and the form's room booked in the backend
+        let meeting_id = body["meetings"][0]["meeting_id"].as_str().unwrap().to_string();
+        assert!(data_store.get_meeting(&meeting_id).is_some());
+        assert_eq!(data_store.booked_rooms(&meeting_id), Some(vec!["room1".to_string()]));
+        assert_eq!(body["meetings"][0]["room_booking"], json!("booked"));
+
+        // And the real meeting ID was recorded in the database
+        let records = db_service.find_all_meetings_by_token("backend_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].meeting_id, meeting_id);
+        assert_eq!(records[0].scheduled_label, "2035-03-30 09:00-11:00");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_cancellation_releases_and_cancels_with_backend() {
+        let (mock_client, data_store) = setup_mock_client();
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("cancel_backend_token", "已预约"))
+            .await;
+        let body: serde_json::Value = response.json();
+        let meeting_id = body["meetings"][0]["meeting_id"].as_str().unwrap().to_string();
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("cancel_backend_token", "已取消"))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+
+        assert!(data_store.get_meeting(&meeting_id).is_none());
+        assert!(data_store.booked_rooms(&meeting_id).is_none());
+        assert!(db_service
+            .find_meeting_by_token("cancel_backend_token")
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_reports_backend_rate_limit() {
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id_by_name()
+            .returning(|_| "test_operator".to_string());
+        mock_client.expect_create_meeting().returning(|_| {
+            Err(TencentApiError::from_response(
+                429,
+                r#"{"error_info":{"error_code":190310,"message":"too many requests"}}"#,
+                None,
+            ))
+        });
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("rate_limited_token", "已预约"))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(db_service
+            .find_all_meetings_by_token("rate_limited_token")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_meeting_endpoint() {
+        let (mock_client, _data_store) = setup_mock_client();
+        let (server, _db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("get_meeting_token", "已预约"))
+            .await;
+        let body: serde_json::Value = response.json();
+        let meeting_id = body["meetings"][0]["meeting_id"].as_str().unwrap().to_string();
+
+        let response = server.get(&format!("/meetings/{}", meeting_id)).await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let meeting: serde_json::Value = response.json();
+        assert_eq!(meeting["meeting_id"], json!(meeting_id));
+        assert_eq!(meeting["subject"], json!("Backend Meeting"));
+        assert_eq!(meeting["status"], json!("MEETING_STATE_INIT"));
+    }
+
+    #[tokio::test]
+    async fn test_get_meeting_endpoint_not_found() {
+        let (mock_client, _data_store) = setup_mock_client();
+        let (server, _db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .get("/meetings/unknown_meeting")
+            .add_query_param("userid", "someone_else")
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_update_meeting_endpoint_reschedules_meeting() {
+        let (mock_client, data_store) = setup_mock_client();
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("update_meeting_token", "已预约"))
+            .await;
+        let body: serde_json::Value = response.json();
+        let meeting_id = body["meetings"][0]["meeting_id"].as_str().unwrap().to_string();
+        let meeting_code = data_store.get_meeting(&meeting_id).unwrap().meeting_code;
+
+        // Move the meeting to 2035-03-31 14:00-15:30 (Asia/Shanghai)
+        let response = server
+            .put(&format!("/meetings/{}", meeting_id))
+            .json(&json!({
+                "userid": "test_operator",
+                "instanceid": 32,
+                "subject": "Moved Meeting",
+                "start_time": "2058933600",
+                "end_time": "2058939000"
+            }))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["meeting_info_list"][0]["meeting_id"], json!(meeting_id));
+        // The meeting code attendees already have is kept
+        assert_eq!(body["meeting_info_list"][0]["meeting_code"], json!(meeting_code));
+
+        let records = db_service.find_all_meetings_by_token("update_meeting_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].scheduled_at, "2035-03-31T06:00:00+00:00");
+        assert_eq!(records[0].scheduled_label, "2035-03-31 14:00-15:30");
+        assert_eq!(records[0].subject, "Moved Meeting");
+    }
+
+    #[tokio::test]
+    async fn test_update_meeting_endpoint_rejects_invalid_time_range() {
+        let (mock_client, _data_store) = setup_mock_client();
+        let (server, _db_service, _dir) = setup_backend_test_server(mock_client);
+
+        // End before start
+        let response = server
+            .put("/meetings/some_meeting")
+            .json(&json!({
+                "userid": "test_operator",
+                "instanceid": 32,
+                "start_time": "2058939000",
+                "end_time": "2058933600"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        // Unknown meeting
+        let response = server
+            .put("/meetings/unknown_meeting")
+            .json(&json!({
+                "userid": "test_operator",
+                "instanceid": 32,
+                "subject": "Renamed"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_form_edit_reconciles_meetings() {
+        let (mock_client, data_store) = setup_mock_client();
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        // Initial booking: Conference Room A 09:00-11:00 (merged)
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("edit_token", "已预约"))
+            .await;
+        let body: serde_json::Value = response.json();
+        let meeting_id = body["meetings"][0]["meeting_id"].as_str().unwrap().to_string();
+        let meeting_code = data_store.get_meeting(&meeting_id).unwrap().meeting_code;
+
+        // Re-posting the same form changes nothing and creates no duplicates
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("edit_token", "已预约"))
+            .await;
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(body["meetings_count"], json!(1));
+        assert_eq!(body["meetings"][0]["action"], json!("unchanged"));
+        assert_eq!(body["meetings"][0]["meeting_id"], json!(meeting_id));
+        assert_eq!(body["meetings"][0]["room_booking"], json!("booked"));
+        assert!(body["meetings"][0]["join_url"].as_str().unwrap().contains(&meeting_id));
+        assert_eq!(db_service.find_all_meetings_by_token("edit_token").unwrap().len(), 1);
+
+        // Edit: Room A moves to 14:00-15:00 and a Room B slot is added
+        let mut payload = backend_form_payload("edit_token", "已预约");
+        payload["entry"]["field_1"] = json!([
+            {
+                "item_name": "Conference Room A",
+                "scheduled_label": "2035-03-30 14:00-15:00",
+                "number": 1,
+                "scheduled_at": "2035-03-30T06:00:00.000Z",
+                "api_code": "CODE1"
+            },
+            {
+                "item_name": "Conference Room B",
+                "scheduled_label": "2035-03-30 16:00-17:00",
+                "number": 1,
+                "scheduled_at": "2035-03-30T08:00:00.000Z",
+                "api_code": "CODE3"
+            }
+        ]);
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(body["meetings_count"], json!(2));
+        assert_eq!(body["meetings"][0]["action"], json!("rescheduled"));
+        assert_eq!(body["meetings"][0]["meeting_id"], json!(meeting_id));
+        assert_eq!(body["meetings"][1]["action"], json!("created"));
+        let room_b_meeting = body["meetings"][1]["meeting_id"].as_str().unwrap().to_string();
+
+        // The moved meeting kept its code and has the new time in Tencent and the database
+        let moved = data_store.get_meeting(&meeting_id).unwrap();
+        assert_eq!(moved.meeting_code, meeting_code);
+        assert_eq!(moved.start_time, "2058847200");
+        let records = db_service.find_all_meetings_by_token("edit_token").unwrap();
+        assert_eq!(records.len(), 2);
+        let moved_record = records.iter().find(|r| r.meeting_id == meeting_id).unwrap();
+        assert_eq!(moved_record.scheduled_label, "2035-03-30 14:00-15:00");
+
+        // Edit: the Room B slot is removed again and the subject changes
+        let mut payload = backend_form_payload("edit_token", "已预约");
+        payload["entry"]["field_1"] = json!([
+            {
+                "item_name": "Conference Room A",
+                "scheduled_label": "2035-03-30 14:00-15:00",
+                "number": 1,
+                "scheduled_at": "2035-03-30T06:00:00.000Z",
+                "api_code": "CODE1"
+            }
+        ]);
+        payload["entry"]["field_8"] = json!("Renamed Meeting");
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(body["meetings"][0]["action"], json!("cancelled"));
+        assert_eq!(body["meetings"][0]["meeting_id"], json!(room_b_meeting));
+        assert_eq!(body["meetings"][1]["action"], json!("updated"));
+
+        assert!(data_store.get_meeting(&room_b_meeting).is_none());
+        assert_eq!(data_store.get_meeting(&meeting_id).unwrap().subject, "Renamed Meeting");
+        let active = db_service.find_meeting_by_token("edit_token").unwrap().unwrap();
+        assert_eq!(active.meeting_id, meeting_id);
+        assert_eq!(active.subject, "Renamed Meeting");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retry_does_not_create_duplicate_meetings() {
+        // The backend allows exactly one meeting to be created and booked
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client
+            .expect_get_operator_id_by_name()
+            .returning(|_| "test_operator".to_string());
+        mock_client.expect_create_meeting().times(1).returning(|request| {
+            Ok(CreateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![MeetingInfo {
+                    subject: request.subject.clone(),
+                    meeting_id: "retry_meeting".to_string(),
+                    meeting_code: "123456".to_string(),
+                    password: None,
+                    participants: None,
+                    start_time: request.start_time.clone(),
+                    end_time: request.end_time.clone(),
+                    join_url: Some("https://meeting.tencent.com/dm/retry".to_string()),
+                    status: None,
+                    hosts: None,
+                    location: None,
+                    settings: None,
+                }],
+            })
+        });
+        mock_client.expect_book_rooms().times(1).returning(|_, _| Ok(()));
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let first = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("retry_token", "已预约"))
+            .await;
+        let first: serde_json::Value = first.json();
+        assert_eq!(first["meetings"][0]["meeting_id"], json!("retry_meeting"));
+
+        // The form service retries the same submission
+        let retry = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("retry_token", "已预约"))
+            .await;
+
+        assert_eq!(retry.status_code(), StatusCode::OK);
+        let retry: serde_json::Value = retry.json();
+        assert_eq!(retry["success"], json!(true));
+        assert!(retry["message"].as_str().unwrap().contains("already processed"));
+        assert_eq!(retry["meetings_count"], json!(1));
+        assert_eq!(retry["meetings"][0]["meeting_id"], first["meetings"][0]["meeting_id"]);
+        assert_eq!(retry["meetings"][0]["merged"], first["meetings"][0]["merged"]);
+        assert_eq!(retry["meetings"][0]["time_slots"], first["meetings"][0]["time_slots"]);
+
+        // Reported from the stored meeting
+        assert_eq!(retry["meetings"][0]["room_booking"], json!("booked"));
+        assert_eq!(retry["meetings"][0]["join_url"], json!("https://meeting.tencent.com/dm/retry"));
+
+        assert_eq!(db_service.find_all_meetings_by_token("retry_token").unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_webhook_queues_and_processes_job() {
+        let (mock_client, data_store) = setup_mock_client();
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        let job_queue = Arc::new(
+            JobQueue::open(&dir.path().join("jobs"), 3, std::time::Duration::from_millis(10)).unwrap(),
+        );
+
+        let app_state = Arc::new(AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false,
+            skip_room_booking: false,
+            webhook_auth_token: Some("job_secret".to_string()),
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: Some(Arc::clone(&job_queue)),
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        });
+        spawn_job_worker(Arc::clone(&app_state)).unwrap();
+
+        let router = create_router(app_state, false, false);
+        let config = TestServerConfig::builder().mock_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        // Invalid payloads are still rejected up front
+        let mut invalid = backend_form_payload("async_token", "已预约");
+        invalid["entry"]["field_1"][0]["scheduled_at"] = json!("not a time");
+        let response = server.post("/webhook/form-submission").add_query_param("auth", "job_secret").json(&invalid).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .add_query_param("auth", "job_secret")
+            .json(&backend_form_payload("async_token", "已预约"))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+        let body: serde_json::Value = response.json();
+        let job_id = body["job_id"].as_str().unwrap().to_string();
+
+        // Wait for the worker to finish the job
+        let mut job = json!(null);
+        for _ in 0..100 {
+            job = server.get(&format!("/jobs/{}", job_id)).add_query_param("auth", "job_secret").await.json();
+            if job["status"] == json!("succeeded") {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(job["status"], json!("succeeded"));
+        assert_eq!(job["attempts"], json!(1));
+        assert_eq!(job["result"]["success"], json!(true));
+        let meeting_id = job["result"]["meetings"][0]["meeting_id"].as_str().unwrap();
+        assert!(data_store.get_meeting(meeting_id).is_some());
+        assert_eq!(db_service.find_all_meetings_by_token("async_token").unwrap().len(), 1);
+
+        // Join URLs are not exposed by the status endpoint
+        assert!(job["result"]["meetings"][0].get("join_url").is_none());
+
+        // Job status needs the webhook token too
+        let response = server.get(&format!("/jobs/{}", job_id)).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = server.get(&format!("/jobs/{}", job_id)).add_query_param("auth", "wrong").await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        let response = server.get("/jobs/unknown_job").add_query_param("auth", "job_secret").await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_async_job_retries_unsuccessful_submission() {
+        // The first meeting fails to be created, every later one succeeds
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client
+            .expect_get_operator_id_by_name()
+            .returning(|_| "test_operator".to_string());
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let create_calls = Arc::clone(&calls);
+        mock_client.expect_create_meeting().times(3).returning(move |request| {
+            let call = create_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if call == 0 {
+                return Err(TencentApiError::from_response(
+                    400,
+                    r#"{"error_info":{"error_code":9002,"message":"meeting service busy"}}"#,
+                    None,
+                ));
+            }
+            Ok(CreateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![MeetingInfo {
+                    subject: request.subject.clone(),
+                    meeting_id: format!("retried_meeting_{}", call),
+                    meeting_code: "654321".to_string(),
+                    password: None,
+                    participants: None,
+                    start_time: request.start_time.clone(),
+                    end_time: request.end_time.clone(),
+                    join_url: None,
+                    status: None,
+                    hosts: None,
+                    location: None,
+                    settings: None,
+                }],
+            })
+        });
+        mock_client.expect_book_rooms().returning(|_, _| Ok(()));
+
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        let job_queue = Arc::new(
+            JobQueue::open(&dir.path().join("jobs"), 3, std::time::Duration::from_millis(10)).unwrap(),
+        );
+
+        let app_state = Arc::new(AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false,
+            skip_room_booking: false,
+            webhook_auth_token: None,
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: Some(Arc::clone(&job_queue)),
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        });
+        spawn_job_worker(Arc::clone(&app_state)).unwrap();
+
+        let router = create_router(app_state, false, false);
+        let config = TestServerConfig::builder().mock_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        // Two slots that can't be merged, so each gets its own meeting
+        let mut payload = backend_form_payload("async_retry_token", "已预约");
+        payload["entry"]["field_1"][1]["scheduled_label"] = json!("2035-03-30 14:00-15:00");
+        payload["entry"]["field_1"][1]["scheduled_at"] = json!("2035-03-30T06:00:00.000Z");
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+        let body: serde_json::Value = response.json();
+        let job_id = body["job_id"].as_str().unwrap().to_string();
+
+        let mut job = json!(null);
+        for _ in 0..100 {
+            job = server.get(&format!("/jobs/{}", job_id)).await.json();
+            if job["status"] == json!("succeeded") || job["status"] == json!("failed") {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        // The second attempt only creates the meeting the first one missed
+        assert_eq!(job["status"], json!("succeeded"));
+        assert_eq!(job["attempts"], json!(2));
+        assert_eq!(job["result"]["success"], json!(true));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(db_service.find_all_meetings_by_token("async_retry_token").unwrap().len(), 2);
+    }
+
+    // A backend that creates meetings but refuses to book any room
+    fn room_unavailable_mock_client(expected_cancellations: usize) -> MockTencentMeetingClient {
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client
+            .expect_get_operator_id_by_name()
+            .returning(|_| "test_operator".to_string());
+        mock_client.expect_create_meeting().times(1).returning(|request| {
+            Ok(CreateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![MeetingInfo {
+                    subject: request.subject.clone(),
+                    meeting_id: "unbooked_meeting".to_string(),
+                    meeting_code: "654321".to_string(),
+                    password: None,
+                    participants: None,
+                    start_time: request.start_time.clone(),
+                    end_time: request.end_time.clone(),
+                    join_url: None,
+                    status: None,
+                    hosts: None,
+                    location: None,
+                    settings: None,
+                }],
+            })
+        });
+        mock_client.expect_book_rooms().times(1).returning(|_, _| {
+            Err(TencentApiError::from_response(
+                400,
+                r#"{"error_info":{"error_code":9001,"message":"room already booked"}}"#,
+                None,
+            ))
+        });
+        mock_client
+            .expect_cancel_meeting()
+            .times(expected_cancellations)
+            .returning(|_, _| Ok(()));
+        mock_client
+    }
+
+    #[tokio::test]
+    async fn test_room_booking_failure_rolls_back_meeting() {
+        let mock_client = room_unavailable_mock_client(1);
+        let (server, db_service, _dir) = setup_backend_test_server(mock_client);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("rollback_token", "已预约"))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        assert!(body["message"].as_str().unwrap().contains("room booking failed"));
+        assert_eq!(body["meetings"][0]["success"], json!(false));
+        assert_eq!(body["meetings"][0]["room_booking"], json!("rolled_back"));
+        assert!(body["meetings"][0]["error"].as_str().unwrap().contains("room already booked"));
+
+        // The cancelled meeting was never stored, so a retry starts from scratch
+        assert!(db_service.find_all_meetings_by_token("rollback_token").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_room_booking_failure_keeps_meeting_when_configured() {
+        let mock_client = room_unavailable_mock_client(0);
+        let (server, db_service, _dir) =
+            setup_backend_test_server_with_policy(mock_client, BookingFailurePolicy::KeepMeeting);
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("keep_token", "已预约"))
+            .await;
+
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["meetings"][0]["meeting_id"], json!("unbooked_meeting"));
+        assert_eq!(body["meetings"][0]["room_booking"], json!("failed"));
+
+        // The meeting is recorded with a status that says its room is missing
+        let records = db_service.find_all_meetings_by_token("keep_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, ReservationStatus::RoomNotBooked);
+
+        // And it can still be cancelled; there is no room to release
+        let cancelled = db_service.request_cancellation("keep_token").unwrap();
+        assert_eq!(cancelled, vec![("unbooked_meeting".to_string(), String::new())]);
+    }
+
+    #[tokio::test]
+    async fn test_resubmission_retries_room_booking_of_kept_meeting() {
+        // The room can't be booked the first two times, but can on the third
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client
+            .expect_get_operator_id_by_name()
+            .returning(|_| "test_operator".to_string());
+        mock_client.expect_create_meeting().times(1).returning(|request| {
+            Ok(CreateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![MeetingInfo {
+                    subject: request.subject.clone(),
+                    meeting_id: "unbooked_meeting".to_string(),
+                    meeting_code: "654321".to_string(),
+                    password: None,
+                    participants: None,
+                    start_time: request.start_time.clone(),
+                    end_time: request.end_time.clone(),
+                    join_url: None,
+                    status: None,
+                    hosts: None,
+                    location: None,
+                    settings: None,
+                }],
+            })
+        });
+        let mut booking_attempts = 0;
+        mock_client.expect_book_rooms().times(3).returning(move |meeting_id, request| {
+            assert_eq!(meeting_id, "unbooked_meeting");
+            assert_eq!(request.operator_id, "test_operator");
+            booking_attempts += 1;
+            if booking_attempts < 3 {
+                return Err(TencentApiError::from_response(
+                    400,
+                    r#"{"error_info":{"error_code":9001,"message":"room already booked"}}"#,
+                    None,
+                ));
+            }
+            Ok(())
+        });
+        let (server, db_service, _dir) =
+            setup_backend_test_server_with_policy(mock_client, BookingFailurePolicy::KeepMeeting);
+
+        let payload = backend_form_payload("rebook_token", "已预约");
+        let first: serde_json::Value = server.post("/webhook/form-submission").json(&payload).await.json();
+        assert_eq!(first["success"], json!(false));
+
+        // A retry that still can't book the room reports it instead of the meeting as unchanged
+        let second: serde_json::Value = server.post("/webhook/form-submission").json(&payload).await.json();
+        assert_eq!(second["success"], json!(false));
+        assert_eq!(second["meetings"][0]["success"], json!(false));
+        assert_eq!(second["meetings"][0]["room_booking"], json!("failed"));
+        assert!(second["meetings"][0]["error"].as_str().unwrap().contains("room already booked"));
+        assert_eq!(db_service.find_all_meetings_by_token("rebook_token").unwrap()[0].status, ReservationStatus::RoomNotBooked);
+
+        // The next retry books the room of the stored meeting
+        let third: serde_json::Value = server.post("/webhook/form-submission").json(&payload).await.json();
+        assert_eq!(third["success"], json!(true));
+        assert_eq!(third["meetings"][0]["action"], json!("unchanged"));
+        assert_eq!(third["meetings"][0]["room_booking"], json!("booked"));
+
+        let records = db_service.find_all_meetings_by_token("rebook_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, ReservationStatus::Reserved);
+    }
+
+    #[tokio::test]
+    async fn test_failed_cancellation_is_retried_by_sweeper() {
+        // Releasing the room fails once; meetings are only cancelled on the second try
+        let release_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let release_counter = Arc::clone(&release_calls);
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client.expect_release_rooms().times(2).returning(move |_, _| {
+            if release_counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                Err(TencentApiError::from_response(503, "", None))
+            } else {
+                Ok(())
+            }
+        });
+        mock_client.expect_cancel_meeting().times(1).returning(|_, _| Ok(()));
+
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        let app_state = Arc::new(AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false,
+            skip_room_booking: false,
+            webhook_auth_token: None,
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        });
+
+        // An existing reservation for the token
+        let form: FormSubmission =
+            serde_json::from_value(backend_form_payload("sweep_token", "已预约")).unwrap();
+        let time_slot = parse_time_slot(&form.entry.field_1[0], DEFAULT_TIME_ZONE).unwrap();
+        db_service
+            .store_meeting_with_time_slot(&form, "sweep_meeting", "Conference Room A", "room1", &time_slot, "Test User", "test_operator")
+            .unwrap();
+
+        let router = create_router(Arc::clone(&app_state), false, false);
+        let config = TestServerConfig::builder().mock_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("sweep_token", "已取消"))
+            .await;
+
+        // The failure is reported and the meeting is not marked cancelled
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        let records = db_service.find_all_meetings_by_token("sweep_token").unwrap();
+        assert_eq!(records[0].status, ReservationStatus::CancelFailed);
+
+        // The sweeper releases the room and cancels the meeting
+        let mut retries = CancellationRetries::new(3);
+        assert_eq!(sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries).await, 1);
+        let records = db_service.find_all_meetings_by_token("sweep_token").unwrap();
+        assert_eq!(records[0].status, ReservationStatus::Cancelled);
+
+        // Nothing is left to retry
+        assert_eq!(sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_abandons_failing_cancellations() {
+        // Tencent refuses one cancellation outright and keeps failing the other
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client.expect_release_rooms().times(2).returning(|_, _| Ok(()));
+        mock_client.expect_cancel_meeting().times(3).returning(|meeting_id, _| {
+            if meeting_id == "refused_meeting" {
+                Err(TencentApiError::from_response(
+                    400,
+                    r#"{"error_info":{"error_code":9003,"message":"meeting can't be cancelled"}}"#,
+                    None,
+                ))
+            } else {
+                Err(TencentApiError::from_response(503, "", None))
+            }
+        });
+
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        let app_state = AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false,
+            skip_room_booking: false,
+            webhook_auth_token: None,
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        };
+
+        for (token, meeting_id) in [("refused_token", "refused_meeting"), ("flaky_token", "flaky_meeting")] {
+            let form: FormSubmission = serde_json::from_value(backend_form_payload(token, "已预约")).unwrap();
+            let time_slot = parse_time_slot(&form.entry.field_1[0], DEFAULT_TIME_ZONE).unwrap();
+            db_service
+                .store_meeting_with_time_slot(&form, meeting_id, "Conference Room A", "room1", &time_slot, "Test User", "test_operator")
+                .unwrap();
+            db_service.request_cancellation(token).unwrap();
+        }
+        let status = |token: &str| db_service.find_all_meetings_by_token(token).unwrap()[0].status;
+
+        // The refused cancellation is given up at once, the other one is retried
+        let mut retries = CancellationRetries::new(2);
+        assert_eq!(sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries).await, 0);
+        assert_eq!(status("refused_token"), ReservationStatus::CancelAbandoned);
+        assert_eq!(status("flaky_token"), ReservationStatus::RoomReleased);
+
+        // Until it runs out of attempts
+        assert_eq!(sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries).await, 0);
+        assert_eq!(status("flaky_token"), ReservationStatus::CancelAbandoned);
+        assert_eq!(sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries).await, 0);
+
+        // Requesting the cancellation again starts over
+        assert_eq!(db_service.request_cancellation("flaky_token").unwrap().len(), 1);
+        assert_eq!(status("flaky_token"), ReservationStatus::CancelRequested);
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_cancels_as_meeting_operator_and_accepts_gone_meetings() {
+        // The meeting is already gone from Tencent Meeting
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client.expect_release_rooms().times(1).returning(|_, request| {
+            assert_eq!(request.operator_id, "meeting_owner");
+            Ok(())
+        });
+        mock_client.expect_cancel_meeting().times(1).returning(|_, request| {
+            assert_eq!(request.userid, "meeting_owner");
+            Err(TencentApiError::from_response(404, "", None))
+        });
+
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        let app_state = AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false,
+            skip_room_booking: false,
+            webhook_auth_token: None,
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        };
+
+        let form: FormSubmission = serde_json::from_value(backend_form_payload("gone_token", "已预约")).unwrap();
+        let time_slot = parse_time_slot(&form.entry.field_1[0], DEFAULT_TIME_ZONE).unwrap();
+        db_service
+            .store_meeting_with_time_slot(&form, "gone_meeting", "Conference Room A", "room1", &time_slot, "Owner", "meeting_owner")
+            .unwrap();
+        db_service.request_cancellation("gone_token").unwrap();
+        let mut retries = CancellationRetries::new(3);
+
+        // A webhook working on the token holds the sweeper back
+        let guard = app_state.submission_locks.acquire("gone_token").await;
+        let sweep = sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries);
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(50), sweep).await.is_err());
+        drop(guard);
+
+        // A meeting Tencent no longer knows counts as cancelled
+        assert_eq!(sweep_pending_cancellations(&app_state, std::time::Duration::ZERO, &mut retries).await, 1);
+        let records = db_service.find_all_meetings_by_token("gone_token").unwrap();
+        assert_eq!(records[0].status, ReservationStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_form_status_mapping() {
+        let (mock_client, data_store) = setup_mock_client();
+        let status_mapping = StatusMapping::parse("待审核=pending,已退订=cancelled").unwrap();
+        let (server, db_service, _dir) = setup_backend_test_server_with_options(
+            mock_client,
+            BookingFailurePolicy::CancelMeeting,
+            status_mapping,
+            TimeZoneConfig::default(),
+            RoomConflictPolicy::default(),
+            BookingPolicyConfig::default(),
+            Arc::new(BlackoutCalendars::default()),
+        );
+
+        // A pending submission doesn't create a meeting yet
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("mapped_token", "待审核"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(body["meetings_count"], json!(0));
+        assert!(db_service.find_all_meetings_by_token("mapped_token").unwrap().is_empty());
+
+        // Once confirmed, the meeting is created
+        server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("mapped_token", "已预约"))
+            .await;
+        let records = db_service.find_all_meetings_by_token("mapped_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, ReservationStatus::Reserved);
+        assert!(data_store.get_meeting(&records[0].meeting_id).is_some());
+
+        // A custom cancellation string cancels it
+        let response = server
+            .post("/webhook/form-submission")
+            .json(&backend_form_payload("mapped_token", "已退订"))
+            .await;
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(true));
+        let records = db_service.find_all_meetings_by_token("mapped_token").unwrap();
+        assert_eq!(records[0].status, ReservationStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_room_time_zone_is_used_for_slots_and_meetings() {
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client
+            .expect_get_operator_id()
+            .return_const("test_operator".to_string());
+        mock_client
+            .expect_get_operator_id_by_name()
+            .returning(|_| "test_operator".to_string());
+        mock_client.expect_create_meeting().times(1).returning(|request| {
+            // 09:00-11:00 London summer time
+            assert_eq!(request.time_zone.as_deref(), Some("Europe/London"));
+            assert_eq!(request.start_time, Utc.with_ymd_and_hms(2035, 7, 2, 8, 0, 0).unwrap().timestamp().to_string());
+            assert_eq!(request.end_time, Utc.with_ymd_and_hms(2035, 7, 2, 10, 0, 0).unwrap().timestamp().to_string());
+            Ok(CreateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![MeetingInfo {
+                    subject: request.subject.clone(),
+                    meeting_id: "london_meeting".to_string(),
+                    meeting_code: "123456".to_string(),
+                    password: None,
+                    participants: None,
+                    start_time: request.start_time.clone(),
+                    end_time: request.end_time.clone(),
+                    join_url: None,
+                    status: None,
+                    hosts: None,
+                    location: None,
+                    settings: None,
+                }],
+            })
+        });
+        mock_client.expect_book_rooms().times(1).returning(|_, _| Ok(()));
+        mock_client.expect_update_meeting().times(1).returning(|meeting_id, request| {
+            // Moved to 14:00-15:00 London summer time
+            assert_eq!(request.time_zone.as_deref(), Some("Europe/London"));
+            assert_eq!(request.start_time, Some(Utc.with_ymd_and_hms(2035, 7, 2, 13, 0, 0).unwrap().timestamp().to_string()));
+            Ok(UpdateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![UpdatedMeetingInfo { meeting_id: meeting_id.to_string(), meeting_code: "123456".to_string() }],
+            })
+        });
+
+        let time_zones = TimeZoneConfig::parse(DEFAULT_TIME_ZONE, "Conference Room A=Europe/London").unwrap();
+        let (server, db_service, _dir) = setup_backend_test_server_with_options(
+            mock_client,
+            BookingFailurePolicy::CancelMeeting,
+            StatusMapping::default(),
+            time_zones,
+            RoomConflictPolicy::default(),
+            BookingPolicyConfig::default(),
+            Arc::new(BlackoutCalendars::default()),
+        );
+
+        // The form sends local times without an offset
+        let mut payload = backend_form_payload("london_token", "已预约");
+        payload["entry"]["field_1"][0]["scheduled_label"] = json!("2035-07-02 09:00-10:00");
+        payload["entry"]["field_1"][0]["scheduled_at"] = json!("2035-07-02T09:00:00");
+        payload["entry"]["field_1"][1]["scheduled_label"] = json!("2035-07-02 10:00-11:00");
+        payload["entry"]["field_1"][1]["scheduled_at"] = json!("2035-07-02T10:00:00");
+
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        // The record keeps UTC and London wall-clock times
+        let records = db_service.find_all_meetings_by_token("london_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].start_time, "2035-07-02T08:00:00+00:00");
+        assert_eq!(records[0].end_time, "2035-07-02T10:00:00+00:00");
+        assert_eq!(records[0].time_zone, "Europe/London");
+        assert_eq!(records[0].local_start_time, "2035-07-02T09:00:00+01:00");
+        assert_eq!(records[0].local_end_time, "2035-07-02T11:00:00+01:00");
+
+        // Rescheduling keeps the room's zone
+        let mut payload = backend_form_payload("london_token", "已预约");
+        payload["entry"]["field_1"] = json!([{
+            "item_name": "Conference Room A",
+            "scheduled_label": "2035-07-02 14:00-15:00",
+            "number": 1,
+            "scheduled_at": "2035-07-02T14:00:00",
+            "api_code": "CODE1"
+        }]);
+        let body: serde_json::Value = server.post("/webhook/form-submission").json(&payload).await.json();
+        assert_eq!(body["meetings"][0]["action"], json!("rescheduled"));
+        assert_eq!(db_service.find_all_meetings_by_token("london_token").unwrap()[0].local_start_time, "2035-07-02T14:00:00+01:00");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_rejects_invalid_scheduled_label() {
+        // No meeting is created, so the client is never called
+        let (server, db_service, _dir) = setup_backend_test_server(MockTencentMeetingClient::default());
+
+        let mut payload = backend_form_payload("bad_label_token", "已预约");
+        payload["entry"]["field_1"][1]["number"] = json!(2);
+        payload["entry"]["field_1"][1]["scheduled_label"] = json!("2035-03-30 10:00-25:00");
+
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["number"], json!(2));
+        assert_eq!(body["item_name"], json!("Conference Room A"));
+        assert_eq!(body["scheduled_label"], json!("2035-03-30 10:00-25:00"));
+        assert_eq!(body["error"], json!({"kind": "invalid_label", "reason": {"kind": "invalid_time", "value": "25:00"}}));
+        assert!(body["message"].as_str().unwrap().contains("'25:00' is not a valid time"));
+
+        assert!(db_service.find_all_meetings_by_token("bad_label_token").unwrap().is_empty());
+
+        // A scheduled_at that isn't the label's start is rejected the same way
+        let mut payload = backend_form_payload("bad_label_token", "已预约");
+        payload["entry"]["field_1"][0]["scheduled_at"] = json!("2035-03-30T02:00:00.000Z");
+
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"], json!({"kind": "scheduled_at_mismatch", "scheduled_at": "2035-03-30T02:00:00.000Z", "label_start": "2035-03-30 09:00"}));
+        assert!(db_service.find_all_meetings_by_token("bad_label_token").unwrap().is_empty());
+    }
+
+    // Server with a meeting booked by another entry, 09:00-11:00 in Conference Room A
+    async fn setup_conflict_test_server(room_conflict_policy: RoomConflictPolicy) -> (TestServer, Arc<DatabaseService>, tempfile::TempDir) {
+        let (mock_client, _) = setup_mock_client();
+        let (server, db_service, dir) = setup_backend_test_server_with_options(mock_client, BookingFailurePolicy::CancelMeeting, StatusMapping::default(), TimeZoneConfig::default(), room_conflict_policy, BookingPolicyConfig::default(), Arc::new(BlackoutCalendars::default()));
+
+        let response = server.post("/webhook/form-submission").json(&backend_form_payload("first_token", "已预约")).await;
+        assert_eq!(response.json::<serde_json::Value>()["success"], json!(true));
+
+        (server, db_service, dir)
+    }
+
+    // Another entry asking for 10:30-11:30 (overlapping) and 14:00-15:00 in the same room
+    fn conflicting_payload() -> serde_json::Value {
+        let mut payload = backend_form_payload("second_token", "已预约");
+        payload["entry"]["field_1"][0]["scheduled_label"] = json!("2035-03-30 10:30-11:30");
+        payload["entry"]["field_1"][0]["scheduled_at"] = json!("2035-03-30T02:30:00.000Z");
+        payload["entry"]["field_1"][1]["scheduled_label"] = json!("2035-03-30 14:00-15:00");
+        payload["entry"]["field_1"][1]["scheduled_at"] = json!("2035-03-30T06:00:00.000Z");
+        payload
+    }
+
+    #[tokio::test]
+    async fn test_room_conflict_rejects_submission() {
+        let (server, db_service, _dir) = setup_conflict_test_server(RoomConflictPolicy::RejectSubmission).await;
+        let first_meeting_id = db_service.find_all_meetings_by_token("first_token").unwrap()[0].meeting_id.clone();
+
+        let response = server.post("/webhook/form-submission").json(&conflicting_payload()).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["meetings_count"], json!(1));
+        assert_eq!(body["meetings"][0]["action"], json!("rejected"));
+        assert_eq!(body["meetings"][0]["time_slots"], json!(["2035-03-30 10:30-11:30"]));
+        assert_eq!(body["meetings"][0]["conflicts"][0]["meeting_id"], json!(first_meeting_id));
+        assert_eq!(body["meetings"][0]["conflicts"][0]["reserved_label"], json!("2035-03-30 09:00-11:00"));
+
+        // Nothing was booked, not even the free slot
+        assert!(db_service.find_all_meetings_by_token("second_token").unwrap().is_empty());
+
+        // A slot that only touches the booked range doesn't conflict
+        let mut payload = conflicting_payload();
+        payload["entry"]["field_1"][0]["scheduled_label"] = json!("2035-03-30 11:00-12:00");
+        payload["entry"]["field_1"][0]["scheduled_at"] = json!("2035-03-30T03:00:00.000Z");
+        let body: serde_json::Value = server.post("/webhook/form-submission").json(&payload).await.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(db_service.find_all_meetings_by_token("second_token").unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_room_conflict_with_unpadded_label() {
+        // The first entry's labels use single-digit months, days and hours
+        let (mock_client, _) = setup_mock_client();
+        let (server, db_service, _dir) = setup_backend_test_server_with_options(mock_client, BookingFailurePolicy::CancelMeeting, StatusMapping::default(), TimeZoneConfig::default(), RoomConflictPolicy::RejectSubmission, BookingPolicyConfig::default(), Arc::new(BlackoutCalendars::default()));
+        let mut payload = backend_form_payload("first_token", "已预约");
+        payload["entry"]["field_1"][0]["scheduled_label"] = json!("2035-3-30 9:00-10:00");
+        payload["entry"]["field_1"][1]["scheduled_label"] = json!("2035-3-30 10:00-11:00");
+        let response = server.post("/webhook/form-submission").json(&payload).await;
+        assert_eq!(response.json::<serde_json::Value>()["success"], json!(true));
+        assert_eq!(db_service.find_all_meetings_by_token("first_token").unwrap()[0].scheduled_label, "2035-03-30 09:00-11:00");
+
+        // It is still found when another entry asks for the room
+        let body: serde_json::Value = server.post("/webhook/form-submission").json(&conflicting_payload()).await.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["meetings"][0]["action"], json!("rejected"));
+        assert_eq!(body["meetings"][0]["conflicts"][0]["reserved_label"], json!("2035-03-30 09:00-11:00"));
+        assert!(db_service.find_all_meetings_by_token("second_token").unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_room_conflict_between_concurrent_entries() {
+        // Creating a meeting takes a while, so both submissions are in flight at once
+        let created = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut mock_client = MockTencentMeetingClient::default();
+        mock_client.expect_get_operator_id().return_const("test_operator".to_string());
+        mock_client.expect_get_operator_id_by_name().returning(|_| "test_operator".to_string());
+        let counter = Arc::clone(&created);
+        mock_client.expect_create_meeting().returning(move |request| {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(CreateMeetingResponse {
+                meeting_number: 1,
+                meeting_info_list: vec![MeetingInfo {
+                    subject: request.subject.clone(),
+                    meeting_id: format!("race_meeting_{}", n),
+                    meeting_code: format!("{:06}", n),
+                    password: None,
+                    participants: None,
+                    start_time: request.start_time.clone(),
+                    end_time: request.end_time.clone(),
+                    join_url: None,
+                    status: None,
+                    hosts: None,
+                    location: None,
+                    settings: None,
+                }],
+            })
+        });
+        mock_client.expect_book_rooms().returning(|_, _| Ok(()));
+
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        let app_state = Arc::new(AppState {
+            client: mock_client,
+            database: db_service.clone().into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: false,
+            skip_room_booking: false,
+            webhook_auth_token: None,
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: RoomConflictPolicy::RejectSubmission,
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        });
+
+        // Two entries ask for the same room and time
+        let submit = |token: &str| {
+            let state = Arc::clone(&app_state);
+            let form: FormSubmission = serde_json::from_value(backend_form_payload(token, "已预约")).unwrap();
+            tokio::spawn(async move { process_form_submission(&state, &form).await.unwrap() })
+        };
+        let (first, second) = tokio::join!(submit("race_a"), submit("race_b"));
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        // Only one of them gets the room
+        assert!(first.success != second.success);
+        assert_eq!(created.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let booked = db_service.find_all_meetings_by_token("race_a").unwrap().len() + db_service.find_all_meetings_by_token("race_b").unwrap().len();
+        assert_eq!(booked, 1);
+        let rejected = if first.success { second } else { first };
+        assert_eq!(rejected.meetings[0].action, MeetingAction::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_room_conflict_skips_conflicting_slots() {
+        let (server, db_service, _dir) = setup_conflict_test_server(RoomConflictPolicy::SkipSlots).await;
+
+        let body: serde_json::Value = server.post("/webhook/form-submission").json(&conflicting_payload()).await.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["meetings_count"], json!(2));
+        assert_eq!(body["meetings"][0]["action"], json!("created"));
+        assert_eq!(body["meetings"][0]["time_slots"], json!(["2035-03-30 14:00-15:00"]));
+        assert!(body["meetings"][0].get("conflicts").is_none());
+        assert_eq!(body["meetings"][1]["action"], json!("rejected"));
+        assert_eq!(body["meetings"][1]["time_slots"], json!(["2035-03-30 10:30-11:30"]));
+
+        let records = db_service.find_all_meetings_by_token("second_token").unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].scheduled_label, "2035-03-30 14:00-15:00");
+    }
+
+    #[tokio::test]
+    async fn test_room_conflict_allowed_and_flagged() {
+        let (server, db_service, _dir) = setup_conflict_test_server(RoomConflictPolicy::Allow).await;
+
+        let body: serde_json::Value = server.post("/webhook/form-submission").json(&conflicting_payload()).await.json();
+        assert_eq!(body["success"], json!(true));
+        assert_eq!(body["meetings_count"], json!(2));
+
+        let flagged: Vec<&serde_json::Value> = body["meetings"].as_array().unwrap().iter().filter(|meeting| meeting.get("conflicts").is_some()).collect();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0]["action"], json!("created"));
+        assert_eq!(flagged[0]["time_slots"], json!(["2035-03-30 10:30-11:30"]));
+        assert_eq!(flagged[0]["conflicts"][0]["scheduled_label"], json!("2035-03-30 10:30-11:30"));
+
+        assert_eq!(db_service.find_all_meetings_by_token("second_token").unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_rejects_slots_breaking_booking_policy() {
+        // No meeting is created, so the client is never called
+        let booking_policies = BookingPolicyConfig::parse(r#"{"policies": {"short": {"max_duration_minutes": 90}}, "rooms": {"Conference Room A": "short"}}"#).unwrap();
+        let (server, db_service, _dir) = setup_backend_test_server_with_options(
+            MockTencentMeetingClient::default(),
+            BookingFailurePolicy::CancelMeeting,
+            StatusMapping::default(),
+            TimeZoneConfig::default(),
+            RoomConflictPolicy::default(),
+            booking_policies,
+            Arc::new(BlackoutCalendars::default()),
+        );
+
+        // The two slots merge into a two-hour meeting
+        let response = server.post("/webhook/form-submission").json(&backend_form_payload("policy_token", "已预约")).await;
+        assert_eq!(response.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["slots"].as_array().unwrap().len(), 2);
+        assert_eq!(body["slots"][1]["scheduled_label"], json!("2035-03-30 10:00-11:00"));
+        assert_eq!(body["slots"][1]["policy"], json!("short"));
+        assert_eq!(body["slots"][1]["violations"], json!([{"rule": "too_long", "minutes": 120, "max_minutes": 90}]));
+        assert!(db_service.find_all_meetings_by_token("policy_token").unwrap().is_empty());
+
+        // Cancellations are not checked
+        let response = server.post("/webhook/form-submission").json(&backend_form_payload("policy_token", "已取消")).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_rejects_slots_in_blackout_windows() {
+        // No meeting is created, so the client is never called
+        let calendar = BlackoutCalendar::parse(r#"{"blackouts": [{"id": "repairs", "start": "2035-03-30T10:30:00", "end": "2035-03-30T12:00:00", "reason": "投影仪维修"}]}"#).unwrap();
+        let blackout_calendars = Arc::new(BlackoutCalendars::from_calendars(BTreeMap::from([("Conference Room A".to_string(), calendar)])));
+        let (server, db_service, _dir) = setup_backend_test_server_with_options(
+            MockTencentMeetingClient::default(),
+            BookingFailurePolicy::CancelMeeting,
+            StatusMapping::default(),
+            TimeZoneConfig::default(),
+            RoomConflictPolicy::default(),
+            BookingPolicyConfig::default(),
+            blackout_calendars,
+        );
+
+        // Only the 10:00-11:00 slot overlaps the window
+        let response = server.post("/webhook/form-submission").json(&backend_form_payload("blackout_token", "已预约")).await;
+        assert_eq!(response.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["success"], json!(false));
+        assert_eq!(body["slots"].as_array().unwrap().len(), 1);
+        assert_eq!(body["slots"][0]["scheduled_label"], json!("2035-03-30 10:00-11:00"));
+        assert_eq!(body["slots"][0]["unavailable"][0]["kind"], json!("blackout"));
+        assert_eq!(body["slots"][0]["unavailable"][0]["window"]["id"], json!("repairs"));
+        assert!(db_service.find_all_meetings_by_token("blackout_token").unwrap().is_empty());
+
+        // Moving the window through the management API lets the submission through to the backend
+        let response = server.get("/blackouts/Conference Room A").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = server.put("/blackouts/Conference Room A/repairs").json(&json!({"start": "2035-03-31T10:30:00", "end": "2035-03-31T12:00:00"})).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = server.put("/blackouts/Conference Room A/repairs").json(&json!({"start": "2035-03-31T12:00:00", "end": "2035-03-31T10:30:00"})).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = server.get("/blackouts").await.json();
+        assert_eq!(body["Conference Room A"]["blackouts"][0]["start"], json!("2035-03-31T10:30:00"));
+
+        let response = server.post("/blackouts/Conference Room A").json(&json!({"start": "2035-04-02T08:00:00", "end": "2035-04-02T09:00:00", "reason": "清洁"})).await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let window: serde_json::Value = response.json();
+        let response = server.delete(&format!("/blackouts/Conference Room A/{}", window["id"].as_str().unwrap())).await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+        assert_eq!(server.delete("/blackouts/Conference Room A/missing").await.status_code(), StatusCode::NOT_FOUND);
+
+        // Cancellations are not checked
+        let response = server.post("/webhook/form-submission").json(&backend_form_payload("blackout_token", "已取消")).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+    }
+}
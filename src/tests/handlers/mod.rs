@@ -1 +1,2 @@
-pub mod api_test;
\ No newline at end of file
+pub mod api_test;
+pub mod reservations_test;
\ No newline at end of file
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+use axum_test::{TestServer, TestServerConfig};
+use chrono::{TimeZone, Utc};
+use tempfile::{tempdir, TempDir};
+
+use crate::handlers::api::AppState;
+use crate::models::reservation::ReservationStatus;
+use crate::routes::create_router;
+use crate::services::database::{DatabaseService, MeetingRecord, MeetingStore};
+use crate::services::reservations::ReservationPage;
+use crate::tests::common::fixtures::generate_test_meeting;
+use crate::tests::common::mocks::setup_mock_client;
+
+/// Reservation query endpoint tests
+#[cfg(test)]
+mod reservations_tests {
+    use super::*;
+
+    // Test server over a database holding the given records
+    fn setup_server(records: &[MeetingRecord]) -> (TestServer, TempDir) {
+        setup_server_with_routes(records, false, false, None)
+    }
+
+    fn setup_server_with_routes(records: &[MeetingRecord], is_production: bool, calendar_feeds: bool, calendar_feed_token: Option<&str>) -> (TestServer, TempDir) {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db_service = Arc::new(DatabaseService::new(csv_path.to_str().unwrap()));
+        for record in records {
+            db_service.insert_meeting(record).unwrap();
+        }
+
+        let (mock_client, _) = setup_mock_client();
+        let app_state = Arc::new(AppState {
+            client: Arc::new(mock_client),
+            database: db_service.into(),
+            user_field_name: "user_field_name".to_string(),
+            dept_field_name: "department_field_name".to_string(),
+            xa_room_id: "room1".to_string(),
+            cd_room_id: "room2".to_string(),
+            skip_meeting_creation: true,
+            skip_room_booking: true,
+            webhook_auth_token: None,
+            calendar_feed_token: calendar_feed_token.map(str::to_string),
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
+        });
+
+        let config = TestServerConfig::builder().mock_transport().build();
+        let server = TestServer::new_with_config(create_router(app_state, is_production, calendar_feeds), config).unwrap();
+        (server, dir)
+    }
+
+    fn meeting(id: &str, token: &str, room: &str, label: &str, operator: &str, status: ReservationStatus) -> MeetingRecord {
+        let start_time = Utc.with_ymd_and_hms(2035, 3, 30, 1, 0, 0).unwrap();
+        let mut record = generate_test_meeting(id, token, status, start_time, start_time + chrono::Duration::hours(1));
+        record.room_name = room.to_string();
+        record.scheduled_label = label.to_string();
+        record.operator_name = operator.to_string();
+        record
+    }
+
+    fn meeting_ids(page: &ReservationPage) -> Vec<&str> {
+        page.reservations.iter().map(|r| r.meeting_id.as_str()).collect()
+    }
+
+    fn sample_records() -> Vec<MeetingRecord> {
+        vec![
+            meeting("m1", "token_a", "Room A", "2035-03-30 09:00-10:00", "Alice", ReservationStatus::Reserved),
+            meeting("m2", "token_a", "Room B", "2035-03-31 09:00-10:00", "Alice", ReservationStatus::Reserved),
+            meeting("m3", "token_b", "Room A", "2035-04-01 09:00-10:00", "Bob", ReservationStatus::Cancelled),
+            meeting("m4", "token_c", "Room A", "2035-04-02 09:00-10:00", "Bob", ReservationStatus::Rescheduled),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_list_reservations_with_filters() {
+        let (server, _dir) = setup_server(&sample_records());
+
+        let page: ReservationPage = server.get("/reservations").await.json();
+        assert_eq!(page.total, 4);
+        assert_eq!(meeting_ids(&page), vec!["m1", "m2", "m3", "m4"]);
+
+        let page: ReservationPage = server.get("/reservations").add_query_params([("room", "Room A"), ("from", "2035-03-31")]).await.json();
+        assert_eq!(meeting_ids(&page), vec!["m3", "m4"]);
+
+        let page: ReservationPage = server.get("/reservations").add_query_params([("room", "Room A"), ("from", "2035-04-01"), ("to", "2035-04-01")]).await.json();
+        assert_eq!(meeting_ids(&page), vec!["m3"]);
+
+        // Statuses can be given by name or label
+        let page: ReservationPage = server.get("/reservations").add_query_params([("operator", "Bob"), ("status", "cancelled")]).await.json();
+        assert_eq!(meeting_ids(&page), vec!["m3"]);
+        let page: ReservationPage = server.get("/reservations").add_query_params([("status", "已改期")]).await.json();
+        assert_eq!(meeting_ids(&page), vec!["m4"]);
+
+        let page: ReservationPage = server.get("/reservations").add_query_params([("form", "Test Form"), ("to", "2035-03-30")]).await.json();
+        assert_eq!(meeting_ids(&page), vec!["m1"]);
+
+        // Invalid filters are rejected
+        server.get("/reservations").add_query_params([("status", "unknown")]).expect_failure().await.assert_status_bad_request();
+        server.get("/reservations").add_query_params([("from", "yesterday")]).expect_failure().await.assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_list_reservations_paging() {
+        let (server, _dir) = setup_server(&sample_records());
+
+        let page: ReservationPage = server.get("/reservations").add_query_params([("page", "2"), ("page_size", "3")]).await.json();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.page, 2);
+        assert_eq!(page.page_size, 3);
+        assert_eq!(meeting_ids(&page), vec!["m4"]);
+
+        let page: ReservationPage = server.get("/reservations").add_query_params([("page", "3"), ("page_size", "3")]).await.json();
+        assert!(page.reservations.is_empty());
+
+        // Page sizes are capped
+        let page: ReservationPage = server.get("/reservations").add_query_params([("page_size", "1000")]).await.json();
+        assert_eq!(page.page_size, 100);
+    }
+
+    #[tokio::test]
+    async fn test_get_reservations_by_token() {
+        let (server, _dir) = setup_server(&sample_records());
+
+        let records: Vec<MeetingRecord> = server.get("/reservations/token_a").await.json();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].meeting_id, "m1");
+        assert_eq!(records[1].room_name, "Room B");
+
+        server.get("/reservations/unknown").expect_failure().await.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_export_reservations() {
+        let (server, _dir) = setup_server(&sample_records());
+
+        let response = server.get("/reservations/export").add_query_params([("room", "Room A"), ("to", "2035-03-31")]).await;
+        response.assert_status_ok();
+        assert_eq!(response.header("content-type"), "text/csv; charset=utf-8");
+        assert_eq!(response.header("content-disposition"), "attachment; filename=\"reservations.csv\"");
+        let text = response.text();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains(",m1,"));
+
+        let response = server.get("/reservations/export").add_query_params([("format", "jsonl"), ("status", "reserved")]).await;
+        assert_eq!(response.header("content-type"), "application/x-ndjson");
+        let records: Vec<MeetingRecord> = response.text().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records.len(), 2);
+
+        let response = server.get("/reservations/export").add_query_param("format", "xlsx").await;
+        assert!(response.as_bytes().starts_with(b"PK"));
+
+        server.get("/reservations/export").add_query_param("format", "pdf").expect_failure().await.assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_calendar_feeds() {
+        let (server, _dir) = setup_server(&sample_records());
+
+        let response = server.get("/calendar/Room A.ics").await;
+        response.assert_status_ok();
+        assert_eq!(response.header("content-type"), "text/calendar; charset=utf-8");
+        let calendar = response.text();
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 3);
+        assert!(calendar.contains("UID:token_b-m3@"));
+        assert_eq!(calendar.matches("STATUS:CANCELLED").count(), 1);
+
+        // Operators match by name or ID
+        let calendar = server.get("/calendar/operators/Alice.ics").await.text();
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        let calendar = server.get("/calendar/operators/test_operator.ics").await.text();
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 4);
+
+        // Unknown rooms get an empty calendar; other file names are not found
+        let calendar = server.get("/calendar/Room C.ics").await.text();
+        assert!(calendar.contains("X-WR-CALNAME:Room C\r\n"));
+        assert!(!calendar.contains("BEGIN:VEVENT"));
+        server.get("/calendar/Room A.json").expect_failure().await.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_calendar_feeds_in_production() {
+        // Off by default, like the rest of the management API
+        let (server, _dir) = setup_server_with_routes(&sample_records(), true, false, Some("feed_secret"));
+        server.get("/calendar/Room A.ics").add_query_param("auth", "feed_secret").expect_failure().await.assert_status_not_found();
+
+        // The switch only adds the feeds, which need the feed token
+        let (server, _dir) = setup_server_with_routes(&sample_records(), true, true, Some("feed_secret"));
+        server.get("/calendar/Room A.ics").expect_failure().await.assert_status_unauthorized();
+        server.get("/calendar/Room A.ics").add_query_param("auth", "wrong").expect_failure().await.assert_status_unauthorized();
+        server.get("/calendar/operators/Alice.ics").expect_failure().await.assert_status_unauthorized();
+        server.get("/calendar/Room A.ics").add_query_param("auth", "feed_secret").await.assert_status_ok();
+        server.get("/calendar/operators/Alice.ics").add_query_param("auth", "feed_secret").await.assert_status_ok();
+        server.get("/reservations").expect_failure().await.assert_status_not_found();
+        server.get("/health").await.assert_status_ok();
+    }
+}
\ No newline at end of file
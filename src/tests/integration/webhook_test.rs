@@ -4,10 +4,10 @@ use serde_json::{json, Value};
 use std::sync::Arc;
 use tempfile::tempdir;
 
-use crate::client::TencentMeetingClient;
 use crate::handlers::api::AppState;
 use crate::routes::create_router;
 use crate::services::database::DatabaseService;
+use crate::tests::common::mocks::setup_mock_client;
 
 /// Webhook integration tests
 #[cfg(test)]
@@ -24,13 +24,13 @@ mod webhook_tests {
         // Initialize database service
         let db_service = Arc::new(DatabaseService::new(&csv_path_str));
 
-        // Create a real client - but don't worry, we'll use simulation mode
-        let client = TencentMeetingClient::default();
+        // Mock backend - no real API calls are made
+        let (client, _) = setup_mock_client();
 
         // Set up app state - using simulation mode so no real API calls are made
         let app_state = Arc::new(AppState {
             client,
-            database: Arc::clone(&db_service),
+            database: db_service.clone().into(),
             user_field_name: "user_field_name".to_string(),
             dept_field_name: "department_field_name".to_string(),
             xa_room_id: "room1".to_string(), // Xi'an room ID
@@ -38,10 +38,20 @@ mod webhook_tests {
             skip_meeting_creation: true,     // SIMULATION MODE
             skip_room_booking: true,         // SIMULATION MODE
             webhook_auth_token: None,        // No auth token for integration tests
+            calendar_feed_token: None,
+            submission_locks: Default::default(),
+            room_locks: Default::default(),
+            job_queue: None,
+            booking_failure_policy: Default::default(),
+            status_mapping: Default::default(),
+            time_zones: Default::default(),
+            room_conflict_policy: Default::default(),
+            booking_policies: Default::default(),
+            blackout_calendars: Default::default(),
         });
 
         // Create router - always use development mode in tests
-        let app = create_router(app_state, false);
+        let app = create_router(app_state, false, false);
 
         // Create test server
         let config = TestServerConfig::builder().mock_transport().build();
@@ -1,5 +1,6 @@
 pub mod common;
 pub mod integration;
+pub mod models;
 pub mod services;
 pub mod handlers;
 pub mod client_test;
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod reservation_test;
+pub mod scheduled_label_test;
+pub mod time_zone_test;
\ No newline at end of file
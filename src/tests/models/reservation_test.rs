@@ -0,0 +1,74 @@
+use crate::models::reservation::{ReservationStatus, StatusMapping};
+
+/// Reservation status tests
+#[cfg(test)]
+mod reservation_tests {
+    use super::*;
+
+    #[test]
+    fn test_status_labels_round_trip() {
+        for status in ReservationStatus::ALL {
+            assert_eq!(ReservationStatus::parse(status.as_str()), Some(status));
+            assert_eq!(ReservationStatus::parse(status.name()), Some(status));
+        }
+
+        // Labels written by older versions
+        assert_eq!(ReservationStatus::parse("Reserved"), Some(ReservationStatus::Reserved));
+        assert_eq!(ReservationStatus::parse("Cancelled"), Some(ReservationStatus::Cancelled));
+        assert_eq!(ReservationStatus::parse("unknown"), None);
+
+        // Raw form statuses stored by older versions stay readable
+        assert_eq!(ReservationStatus::parse_stored("取消中"), ReservationStatus::CancelRequested);
+        assert_eq!(ReservationStatus::parse_stored("预约成功"), ReservationStatus::Reserved);
+        assert_eq!(ReservationStatus::parse_stored("用户取消"), ReservationStatus::Cancelled);
+
+        // Serialized as the stored label
+        let json = serde_json::to_string(&ReservationStatus::CancelRequested).unwrap();
+        assert_eq!(json, "\"取消中\"");
+        let status: ReservationStatus = serde_json::from_str("\"已改期\"").unwrap();
+        assert_eq!(status, ReservationStatus::Rescheduled);
+        assert!(serde_json::from_str::<ReservationStatus>("\"unknown\"").is_err());
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use ReservationStatus::*;
+
+        assert_eq!(Reserved.cancellation_request(), Some(CancelRequested));
+        assert_eq!(Rescheduled.cancellation_request(), Some(CancelRequested));
+        assert_eq!(CancelFailed.cancellation_request(), Some(CancelRequested));
+        assert_eq!(CancelAbandoned.cancellation_request(), Some(CancelRequested));
+        assert_eq!(RoomNotBooked.cancellation_request(), Some(RoomReleased));
+        assert_eq!(RoomReleased.cancellation_request(), Some(RoomReleased));
+        assert_eq!(CancelRequested.cancellation_request(), None);
+        assert_eq!(Cancelled.cancellation_request(), None);
+        assert_eq!(Failed.cancellation_request(), None);
+
+        assert!(Reserved.is_active() && Rescheduled.is_active() && RoomNotBooked.is_active());
+        assert!(!Failed.is_active() && !Cancelled.is_active() && !CancelRequested.is_active());
+        assert!(CancelFailed.is_cancellation_pending() && !Cancelled.is_cancellation_pending());
+        assert!(!CancelAbandoned.is_cancellation_pending() && CancelAbandoned.is_cancellation());
+        assert!(Cancelled.is_cancellation() && RoomReleased.is_cancellation() && !Reserved.is_cancellation());
+    }
+
+    #[test]
+    fn test_status_mapping() {
+        let mapping = StatusMapping::default();
+        assert_eq!(mapping.map("已预约"), ReservationStatus::Reserved);
+        assert_eq!(mapping.map("Cancelled"), ReservationStatus::Cancelled);
+
+        // Unmapped strings fall back on whether they mention a cancellation
+        assert_eq!(mapping.map("用户取消"), ReservationStatus::Cancelled);
+        assert_eq!(mapping.map("预约成功"), ReservationStatus::Reserved);
+
+        let mapping = StatusMapping::parse("待审核=pending, 已退订=cancelled,预约成功=已预约").unwrap();
+        assert_eq!(mapping.map("待审核"), ReservationStatus::Pending);
+        assert_eq!(mapping.map("已退订"), ReservationStatus::Cancelled);
+        assert_eq!(mapping.map("预约成功"), ReservationStatus::Reserved);
+        assert_eq!(mapping.map("已预约"), ReservationStatus::Reserved);
+
+        assert!(StatusMapping::parse("待审核").is_err());
+        assert!(StatusMapping::parse("待审核=maybe").is_err());
+        assert!(StatusMapping::parse("").is_ok());
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::models::scheduled_label::{ScheduledLabel, ScheduledLabelError};
+
+/// Scheduled label grammar tests
+#[cfg(test)]
+mod scheduled_label_tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 4, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_scheduled_labels() {
+        let label = ScheduledLabel::parse("2025-04-01 09:00-10:30").unwrap();
+        assert_eq!(label, ScheduledLabel { start: at(1, 9, 0), end: at(1, 10, 30) });
+        assert_eq!(label.duration(), Duration::minutes(90));
+        assert_eq!(label.to_string(), "2025-04-01 09:00-10:30");
+
+        // Single-digit hours, months and days, spaces around the separator
+        let label = ScheduledLabel::parse(" 2025-4-1 9:00 - 10:00 ").unwrap();
+        assert_eq!(label, ScheduledLabel { start: at(1, 9, 0), end: at(1, 10, 0) });
+        assert_eq!(label.to_string(), "2025-04-01 09:00-10:00");
+
+        // Full-width digits and punctuation
+        assert_eq!(ScheduledLabel::parse("２０２５－０４－０１　０９：００－１０：００").unwrap(), ScheduledLabel { start: at(1, 9, 0), end: at(1, 10, 0) });
+        assert_eq!(ScheduledLabel::parse("2025-04-01 09:00～10:00").unwrap().end, at(1, 10, 0));
+        assert_eq!(ScheduledLabel::parse("2025-04-01 09:00至10:00").unwrap().end, at(1, 10, 0));
+
+        // Across midnight, with or without the end date
+        let label = ScheduledLabel::parse("2025-04-01 23:00-01:00").unwrap();
+        assert_eq!(label, ScheduledLabel { start: at(1, 23, 0), end: at(2, 1, 0) });
+        assert_eq!(label.to_string(), "2025-04-01 23:00-2025-04-02 01:00");
+        assert_eq!(ScheduledLabel::parse("2025-04-01 23:00-2025-04-02 01:00").unwrap(), label);
+        let label = ScheduledLabel::parse("2025-04-01 23:00-24:00").unwrap();
+        assert_eq!(label.end, at(2, 0, 0));
+        assert_eq!(label.to_string(), "2025-04-01 23:00-00:00");
+    }
+
+    #[test]
+    fn test_scheduled_label_errors() {
+        let error = |label: &str| ScheduledLabel::parse(label).unwrap_err();
+
+        assert_eq!(error("  "), ScheduledLabelError::Empty);
+        assert_eq!(error("2025-02-30 09:00-10:00"), ScheduledLabelError::InvalidDate { value: "2025-02-30".to_string() });
+        assert_eq!(error("04/01 09:00-10:00"), ScheduledLabelError::InvalidDate { value: "04/01".to_string() });
+        assert_eq!(error("2025-04-01"), ScheduledLabelError::MissingTimeRange);
+        assert_eq!(error("2025-04-01 9:0-10:00"), ScheduledLabelError::InvalidTime { value: "9:0".to_string() });
+        assert_eq!(error("2025-04-01 09:00-25:00"), ScheduledLabelError::InvalidTime { value: "25:00".to_string() });
+        assert_eq!(error("2025-04-01 24:00-01:00"), ScheduledLabelError::InvalidTime { value: "24:00".to_string() });
+        assert_eq!(error("2025-04-01 09:00-1O:00"), ScheduledLabelError::InvalidTime { value: "1O:00".to_string() });
+        assert_eq!(error("2025-04-01 09:00"), ScheduledLabelError::MissingEndTime);
+        assert_eq!(error("2025-04-01 09:00-"), ScheduledLabelError::MissingEndTime);
+        assert_eq!(error("2025-04-01 09:00-10:00 (2h)"), ScheduledLabelError::UnexpectedText { value: "(2h)".to_string() });
+        assert_eq!(error("2025-04-01 09:00-09:00"), ScheduledLabelError::EmptyRange);
+        assert_eq!(error("2025-04-02 09:00-2025-04-01 10:00"), ScheduledLabelError::EndBeforeStart);
+
+        // Errors serialize with their kind, for webhook responses
+        assert_eq!(serde_json::to_value(error("2025-04-01 09:00-25:00")).unwrap(), serde_json::json!({"kind": "invalid_time", "value": "25:00"}));
+    }
+}
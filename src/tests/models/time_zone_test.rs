@@ -0,0 +1,39 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use chrono_tz::{America::New_York, Asia::Shanghai, Europe::London};
+
+use crate::models::time_zone::{parse_time_zone, resolve_local_time, TimeZoneConfig, DEFAULT_TIME_ZONE};
+
+/// Time zone configuration tests
+#[cfg(test)]
+mod time_zone_tests {
+    use super::*;
+
+    #[test]
+    fn test_time_zone_mapping() {
+        let config = TimeZoneConfig::parse(New_York, "London Room=Europe/London, 成都会议室预约 = Asia/Shanghai,").unwrap();
+        assert_eq!(config.default_zone(), New_York);
+
+        // Rooms take precedence over forms, unknown names get the default
+        assert_eq!(config.zone_for("成都会议室预约", "London Room"), London);
+        assert_eq!(config.zone_for("成都会议室预约", "Other Room"), Shanghai);
+        assert_eq!(config.zone_for("Other Form", "Other Room"), New_York);
+
+        assert_eq!(TimeZoneConfig::default().zone_for("Form", "Room"), DEFAULT_TIME_ZONE);
+
+        assert!(TimeZoneConfig::parse(DEFAULT_TIME_ZONE, "London Room").is_err());
+        assert!(TimeZoneConfig::parse(DEFAULT_TIME_ZONE, "London Room=Mars/Olympus").is_err());
+        assert!(parse_time_zone("UTC+8").is_err());
+        assert_eq!(parse_time_zone(" Asia/Shanghai ").unwrap(), Shanghai);
+    }
+
+    #[test]
+    fn test_resolve_local_time() {
+        let local = |day: u32, hour: u32, minute: u32| NaiveDate::from_ymd_opt(2035, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+
+        assert_eq!(resolve_local_time(local(1, 9, 0), Shanghai), Some(Utc.with_ymd_and_hms(2035, 3, 1, 1, 0, 0).unwrap()));
+
+        // London skips 01:00-02:00 on 2035-03-25
+        assert_eq!(resolve_local_time(local(25, 1, 30), London), Some(Utc.with_ymd_and_hms(2035, 3, 25, 1, 30, 0).unwrap()));
+        assert_eq!(resolve_local_time(local(25, 2, 0), London), Some(Utc.with_ymd_and_hms(2035, 3, 25, 1, 0, 0).unwrap()));
+    }
+}
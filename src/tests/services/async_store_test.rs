@@ -0,0 +1,109 @@
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+use crate::models::form::{FormEntry, FormField1Item, FormSubmission};
+use crate::models::meeting::TimeSlot;
+use crate::services::async_store::AsyncMeetingStore;
+use crate::models::reservation::ReservationStatus;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::database::{DatabaseService, MeetingFilter, MeetingStore};
+
+/// Async meeting store tests
+#[cfg(test)]
+mod async_store_tests {
+    use super::*;
+
+    fn create_test_form(token: &str) -> FormSubmission {
+        FormSubmission {
+            form: "test_form".to_string(),
+            form_name: "Test Form".to_string(),
+            entry: FormEntry {
+                token: token.to_string(),
+                field_1: vec![FormField1Item {
+                    item_name: "Test Room".to_string(),
+                    scheduled_label: "2025-04-01 09:00-10:00".to_string(),
+                    number: 1,
+                    scheduled_at: "2025-04-01T01:00:00.000Z".to_string(),
+                    api_code: "CODE1".to_string(),
+                }],
+                field_8: "Test Meeting".to_string(),
+                extra_fields: HashMap::new(),
+                reservation_status_fsf_field: "已预约".to_string(),
+            },
+        }
+    }
+
+    fn create_time_slot() -> TimeSlot {
+        let start_time = Utc::now() + chrono::Duration::hours(1);
+        TimeSlot {
+            item_name: "Test Room".to_string(),
+            scheduled_label: "2025-04-01 09:00-10:00".to_string(),
+            number: 1,
+            start_time,
+            end_time: start_time + chrono::Duration::hours(1),
+            api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_and_reads() {
+        let dir = tempdir().unwrap();
+        let db = Arc::new(DatabaseService::new(dir.path().join("meetings.csv").to_str().unwrap()));
+        let store: AsyncMeetingStore = db.clone().into();
+
+        let form = create_test_form("async_token");
+        store.store_meeting_with_time_slot(&form, "meeting1", "Test Room", "room1", &create_time_slot(), "op", "op_id").await.unwrap();
+
+        // A write is visible as soon as it has been acknowledged
+        let record = store.find_meeting_by_token("async_token").await.unwrap().unwrap();
+        assert_eq!(record.meeting_id, "meeting1");
+        assert_eq!(db.find_all_meetings_by_token("async_token").unwrap().len(), 1);
+
+        let requested = store.request_cancellation("async_token").await.unwrap();
+        assert_eq!(requested, vec![("meeting1".to_string(), "room1".to_string())]);
+        let pending = store.find_pending_cancellations(Utc::now()).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].status, ReservationStatus::CancelRequested);
+
+        assert_eq!(store.finalize_cancellation("meeting1").await.unwrap(), 1);
+        assert!(store.find_meeting_by_token("async_token").await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_concurrent_writes_are_serialized() {
+        let dir = tempdir().unwrap();
+        let db = Arc::new(DatabaseService::new(dir.path().join("meetings.csv").to_str().unwrap()));
+        let store: AsyncMeetingStore = db.clone().into();
+
+        // Ten distinct meetings, plus the same meeting submitted five times
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                let form = create_test_form(&format!("token_{}", i));
+                store.store_meeting_with_time_slot(&form, &format!("meeting_{}", i), "Test Room", "room1", &create_time_slot(), "op", "op_id").await
+            }));
+        }
+        for _ in 0..5 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                let form = create_test_form("repeated_token");
+                store.store_meeting_with_time_slot(&form, "repeated_meeting", "Test Room", "room1", &create_time_slot(), "op", "op_id").await
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let all = store.read(|db| db.find_meetings(MeetingFilter::All)).await.unwrap();
+        assert_eq!(all.len(), 11);
+        assert_eq!(store.find_all_meetings_by_token("repeated_token").await.unwrap().len(), 1);
+
+        // The file holds the same records
+        let reloaded = DatabaseService::new(dir.path().join("meetings.csv").to_str().unwrap());
+        assert_eq!(reloaded.find_meetings(MeetingFilter::All).unwrap(), all);
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use std::collections::BTreeMap;
+use tempfile::tempdir;
+
+use crate::models::blackout::{BlackoutCalendar, BlackoutWindowRequest, Unavailability};
+use crate::models::booking_policy::{BookingPolicyConfig, PolicyViolation};
+use crate::models::meeting::TimeSlot;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::blackouts::{is_valid_calendar_name, BlackoutCalendars};
+use crate::services::booking_policy::check_booking_policies;
+
+/// Blackout calendar tests
+#[cfg(test)]
+mod blackouts_tests {
+    use super::*;
+
+    // 2035-04-05 (Thursday) is 清明节; Sunday 2035-04-08 is worked instead
+    const XIAN_CALENDAR: &str = r#"{
+        "holidays": [{ "date": "2035-04-05", "name": "清明节" }],
+        "adjusted_workdays": [{ "date": "2035-04-08", "name": "清明节调休" }]
+    }"#;
+
+    const ROOM_CALENDAR: &str = r#"{
+        "blackouts": [{ "id": "aircon", "start": "2035-04-02T08:00:00", "end": "2035-04-02T12:00:00", "reason": "空调检修" }]
+    }"#;
+
+    // Slot in Shanghai time on a day in April 2035, e.g. slot("Room", 2, 9, 10)
+    fn slot(room: &str, day: u32, start_hour: u32, end_hour: u32) -> TimeSlot {
+        TimeSlot {
+            item_name: room.to_string(),
+            scheduled_label: format!("2035-04-{:02} {:02}:00-{:02}:00", day, start_hour, end_hour),
+            number: 1,
+            start_time: Utc.with_ymd_and_hms(2035, 4, day, start_hour - 8, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2035, 4, day, end_hour - 8, 0, 0).unwrap(),
+            api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
+        }
+    }
+
+    fn calendars() -> BlackoutCalendars {
+        BlackoutCalendars::from_calendars(BTreeMap::from([
+            ("西安-大会议室".to_string(), BlackoutCalendar::parse(XIAN_CALENDAR).unwrap()),
+            ("Room A".to_string(), BlackoutCalendar::parse(ROOM_CALENDAR).unwrap()),
+        ]))
+    }
+
+    #[test]
+    fn test_blackout_calendar_parse() {
+        let calendar = BlackoutCalendar::parse(XIAN_CALENDAR).unwrap();
+        assert_eq!(calendar.working_day(NaiveDate::from_ymd_opt(2035, 4, 5).unwrap()), Some(false));
+        assert_eq!(calendar.working_day(NaiveDate::from_ymd_opt(2035, 4, 8).unwrap()), Some(true));
+        assert_eq!(calendar.working_day(NaiveDate::from_ymd_opt(2035, 4, 9).unwrap()), None);
+
+        // Adjusted workdays are weekend days that aren't also holidays
+        assert!(BlackoutCalendar::parse(r#"{"adjusted_workdays": [{"date": "2035-04-09"}]}"#).is_err());
+        assert!(BlackoutCalendar::parse(r#"{"holidays": [{"date": "2035-04-08"}], "adjusted_workdays": [{"date": "2035-04-08"}]}"#).is_err());
+        assert!(BlackoutCalendar::parse(r#"{"blackouts": [{"id": "w", "start": "2035-04-02T12:00:00", "end": "2035-04-02T08:00:00"}]}"#).is_err());
+        assert!(BlackoutCalendar::parse(r#"{"closures": []}"#).is_err());
+
+        assert!(is_valid_calendar_name("西安-大会议室"));
+        assert!(!is_valid_calendar_name("../jobs"));
+        assert!(!is_valid_calendar_name(" "));
+    }
+
+    #[test]
+    fn test_find_blackouts() {
+        let calendars = calendars();
+
+        // The window touches 11:00-12:00 but not 12:00-13:00; the holiday is the location's
+        let slots = vec![slot("Room A", 2, 11, 12), slot("Room A", 2, 12, 13), slot("Room B", 5, 9, 10)];
+        let blackouts = calendars.find_blackouts("西安会议室预约", &slots);
+        assert_eq!(blackouts.len(), 2);
+        assert!(matches!(&blackouts[0].unavailable[..], [Unavailability::Blackout { calendar, window }] if calendar == "Room A" && window.id == "aircon"));
+        assert_eq!(blackouts[1].item_name, "Room B");
+        assert!(matches!(&blackouts[1].unavailable[..], [Unavailability::Holiday { calendar, name, .. }] if calendar == "西安-大会议室" && name == "清明节"));
+
+        // Other forms are in other locations
+        assert_eq!(calendars.find_blackouts("成都会议室预约", &slots).len(), 1);
+    }
+
+    #[test]
+    fn test_adjusted_workdays_in_booking_policies() {
+        let config = BookingPolicyConfig::parse(r#"{"policies": {"office": {"working_hours": {"start": "08:00", "end": "21:00"}}}, "default": "office"}"#).unwrap();
+        let calendars = calendars();
+        let now = Utc.with_ymd_and_hms(2035, 4, 1, 0, 0, 0).unwrap();
+
+        // Sunday 2035-04-08 is a working day in Xi'an, Saturday 2035-04-07 isn't
+        let slots = vec![slot("Room B", 8, 9, 10), slot("Room B", 7, 9, 10)];
+        let violations = check_booking_policies(&config, &calendars, "西安会议室预约", &slots, now);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].scheduled_label, "2035-04-07 09:00-10:00");
+        assert!(matches!(violations[0].violations[..], [PolicyViolation::OutsideWorkingHours { .. }]));
+        assert_eq!(check_booking_policies(&config, &calendars, "成都会议室预约", &slots, now).len(), 2);
+    }
+
+    #[test]
+    fn test_edit_blackout_windows() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("Room A.json"), ROOM_CALENDAR).unwrap();
+        let calendars = BlackoutCalendars::load(dir.path()).unwrap();
+        assert_eq!(calendars.list().len(), 1);
+
+        let request = |start_hour: u32, reason: &str| BlackoutWindowRequest {
+            start: NaiveDate::from_ymd_opt(2035, 4, 3).unwrap().and_hms_opt(start_hour, 0, 0).unwrap(),
+            end: NaiveDate::from_ymd_opt(2035, 4, 3).unwrap().and_hms_opt(18, 0, 0).unwrap(),
+            reason: reason.to_string(),
+        };
+
+        // Adding to a new calendar creates its file
+        let window = calendars.add_window("Room B", request(9, "装修")).unwrap();
+        assert!(dir.path().join("Room B.json").exists());
+        assert!(calendars.add_window("Room B", request(19, "装修")).is_err());
+
+        let updated = calendars.update_window("Room B", &window.id, request(13, "装修延期")).unwrap().unwrap();
+        assert_eq!(updated.id, window.id);
+        assert!(calendars.update_window("Room B", "missing", request(13, "")).unwrap().is_none());
+
+        // Edits survive a reload
+        let reloaded = BlackoutCalendars::load(dir.path()).unwrap();
+        assert_eq!(reloaded.get("Room B").unwrap().blackouts, vec![updated]);
+        assert_eq!(reloaded.get("Room A").unwrap().blackouts[0].id, "aircon");
+
+        assert!(calendars.remove_window("Room A", "aircon").unwrap());
+        assert!(!calendars.remove_window("Room A", "aircon").unwrap());
+        assert!(BlackoutCalendars::load(dir.path()).unwrap().get("Room A").unwrap().blackouts.is_empty());
+    }
+}
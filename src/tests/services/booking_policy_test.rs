@@ -0,0 +1,85 @@
+use chrono::{TimeZone, Utc};
+
+use crate::models::booking_policy::{BookingPolicyConfig, PolicyViolation};
+use crate::models::meeting::TimeSlot;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::blackouts::BlackoutCalendars;
+use crate::services::booking_policy::check_booking_policies;
+
+/// Booking policy tests
+#[cfg(test)]
+mod booking_policy_tests {
+    use super::*;
+
+    const POLICIES: &str = r#"{
+        "policies": {
+            "office_hours": {
+                "working_hours": { "days": ["mon", "tue", "wed", "thu", "fri"], "start": "08:00", "end": "21:00" },
+                "max_duration_minutes": 240,
+                "max_advance_days": 30
+            },
+            "unrestricted": {}
+        },
+        "default": "office_hours",
+        "rooms": { "Board Room": "unrestricted" },
+        "forms": { "Open Form": "unrestricted" }
+    }"#;
+
+    // Slot in Shanghai time on a day in March 2035, e.g. slot("Room", 30, 9, 10)
+    fn slot(room: &str, day: u32, start_hour: u32, end_hour: u32) -> TimeSlot {
+        TimeSlot {
+            item_name: room.to_string(),
+            scheduled_label: format!("2035-03-{:02} {:02}:00-{:02}:00", day, start_hour, end_hour),
+            number: 1,
+            start_time: Utc.with_ymd_and_hms(2035, 3, day, start_hour - 8, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2035, 3, day, end_hour - 8, 0, 0).unwrap(),
+            api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
+        }
+    }
+
+    #[test]
+    fn test_booking_policy_config() {
+        let config = BookingPolicyConfig::parse(POLICIES).unwrap();
+        assert_eq!(config.policy_for("Any Form", "Board Room").unwrap().0, "unrestricted");
+        assert_eq!(config.policy_for("Open Form", "Room A").unwrap().0, "unrestricted");
+        let (name, policy) = config.policy_for("Any Form", "Room A").unwrap();
+        assert_eq!(name, "office_hours");
+        assert_eq!(policy.max_duration_minutes, Some(240));
+        assert_eq!(policy.working_hours.as_ref().unwrap().to_string(), "Mon,Tue,Wed,Thu,Fri 08:00-21:00");
+
+        assert!(BookingPolicyConfig::default().policy_for("Any Form", "Room A").is_none());
+        assert!(BookingPolicyConfig::parse(r#"{"policies": {}, "rooms": {"Room A": "missing"}}"#).is_err());
+        assert!(BookingPolicyConfig::parse(r#"{"policies": {"p": {"max_hours": 4}}}"#).is_err());
+        assert!(BookingPolicyConfig::parse(r#"{"policies": {"p": {"working_hours": {"start": "21:00", "end": "08:00"}}}}"#).is_err());
+    }
+
+    #[test]
+    fn test_check_booking_policies() {
+        let config = BookingPolicyConfig::parse(POLICIES).unwrap();
+        let calendars = BlackoutCalendars::default();
+        let now = Utc.with_ymd_and_hms(2035, 3, 1, 0, 0, 0).unwrap();
+
+        // Friday office hours pass; Saturday, late evening and unrestricted rooms
+        let slots = vec![slot("Room A", 30, 9, 10), slot("Room A", 31, 9, 10), slot("Room B", 30, 20, 22), slot("Board Room", 31, 20, 22)];
+        let violations = check_booking_policies(&config, &calendars, "Any Form", &slots, now);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].scheduled_label, "2035-03-31 09:00-10:00");
+        assert_eq!(violations[0].policy, "office_hours");
+        assert!(matches!(violations[0].violations[..], [PolicyViolation::OutsideWorkingHours { .. }]));
+        assert_eq!(violations[1].item_name, "Room B");
+
+        // Merged slots count as one meeting: 09:00-14:00 is too long, 09:00-13:00 isn't
+        let merged: Vec<TimeSlot> = (9..14).map(|hour| slot("Room A", 30, hour, hour + 1)).collect();
+        let violations = check_booking_policies(&config, &calendars, "Any Form", &merged, now);
+        assert_eq!(violations.len(), 5);
+        assert_eq!(violations[4].violations, vec![PolicyViolation::TooLong { minutes: 300, max_minutes: 240 }]);
+        assert!(check_booking_policies(&config, &calendars, "Any Form", &merged[..4], now).is_empty());
+
+        // From 2035-02-27, 30 days ahead is the limit
+        let later = Utc.with_ymd_and_hms(2035, 2, 27, 0, 0, 0).unwrap();
+        let violations = check_booking_policies(&config, &calendars, "Any Form", &[slot("Room A", 30, 9, 10)], later);
+        assert_eq!(violations[0].violations, vec![PolicyViolation::TooFarAhead { days: 31, max_days: 30 }]);
+        assert!(check_booking_policies(&config, &calendars, "Any Form", &[slot("Room A", 29, 9, 10)], later).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{TimeZone, Utc};
+use tempfile::tempdir;
+
+use crate::models::reservation::ReservationStatus;
+use crate::services::calendar::render_calendar;
+use crate::services::database::{DatabaseService, MeetingRecord, MeetingStore};
+use crate::tests::common::fixtures::generate_test_meeting;
+
+/// iCalendar feed tests
+#[cfg(test)]
+mod calendar_tests {
+    use super::*;
+
+    fn meeting(id: &str, status: ReservationStatus) -> MeetingRecord {
+        let start_time = Utc.with_ymd_and_hms(2035, 3, 30, 1, 0, 0).unwrap();
+        generate_test_meeting(id, "token", status, start_time, start_time + chrono::Duration::minutes(90))
+    }
+
+    fn render(records: &[MeetingRecord]) -> String {
+        render_calendar("Test Room", records, Utc.with_ymd_and_hms(2035, 3, 1, 12, 0, 0).unwrap())
+    }
+
+    // Content lines with folded lines joined again
+    fn unfolded_lines(calendar: &str) -> Vec<String> {
+        calendar.replace("\r\n ", "").split("\r\n").filter(|line| !line.is_empty()).map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_calendar_has_required_properties() {
+        let mut record = meeting("m1", ReservationStatus::Reserved);
+        record.created_at = "2035-02-01T08:00:00+08:00".to_string();
+        let calendar = render(&[record]);
+
+        // Every line ends in CRLF, no bare line feeds
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches('\n').count(), calendar.matches("\r\n").count());
+
+        let lines = unfolded_lines(&calendar);
+        assert_eq!(lines[0], "BEGIN:VCALENDAR");
+        for property in ["VERSION:2.0", "PRODID:-//Tencent Meeting Service//Reservations//ZH", "METHOD:PUBLISH", "X-WR-CALNAME:Test Room"] {
+            assert!(lines.iter().any(|line| line == property), "missing {}", property);
+        }
+
+        // The event is in UTC and carries the meeting details
+        for property in [
+            "BEGIN:VEVENT",
+            "UID:token-m1@tencent-meeting-service",
+            "DTSTAMP:20350301T120000Z",
+            "SEQUENCE:0",
+            "LAST-MODIFIED:20350201T000000Z",
+            "DTSTART:20350330T010000Z",
+            "DTEND:20350330T023000Z",
+            "SUMMARY:Test Meeting",
+            "LOCATION:Test Room (Unknown Location)",
+            "URL:https://meeting.tencent.com/dm/m1",
+            "STATUS:CONFIRMED",
+            "END:VEVENT",
+        ] {
+            assert!(lines.iter().any(|line| line == property), "missing {}", property);
+        }
+        let description = lines.iter().find(|line| line.starts_with("DESCRIPTION:")).unwrap();
+        assert!(description.contains("会议号: m1\\n"));
+        assert!(description.contains("https://meeting.tencent.com/dm/m1"));
+    }
+
+    #[test]
+    fn test_calendar_event_statuses() {
+        let records = vec![
+            meeting("m1", ReservationStatus::Cancelled),
+            meeting("m2", ReservationStatus::CancelRequested),
+            meeting("m3", ReservationStatus::Pending),
+            meeting("m4", ReservationStatus::Failed),
+        ];
+        let lines = unfolded_lines(&render(&records));
+
+        let statuses: Vec<&str> = lines.iter().filter_map(|line| line.strip_prefix("STATUS:")).collect();
+        assert_eq!(statuses, vec!["CANCELLED", "CANCELLED", "TENTATIVE"]);
+        assert!(!lines.iter().any(|line| line.contains("-m4-")));
+
+        // Records without a valid start time are left out
+        let mut record = meeting("m5", ReservationStatus::Reserved);
+        record.start_time = String::new();
+        assert!(!render(&[record]).contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn test_rescheduled_meeting_keeps_its_uid() {
+        let dir = tempdir().unwrap();
+        let db = DatabaseService::new(dir.path().join("test_meetings.csv").to_str().unwrap());
+        db.insert_meeting(&meeting("m1", ReservationStatus::Reserved)).unwrap();
+        let before = unfolded_lines(&render(&db.find_all_meetings_by_token("token").unwrap()));
+
+        let start_time = Utc.with_ymd_and_hms(2035, 3, 31, 6, 0, 0).unwrap();
+        let labels = vec!["2035-03-31 14:00-15:00".to_string()];
+        db.reschedule_meeting("m1", start_time, start_time + chrono::Duration::hours(1), &labels[0], &labels, None).unwrap();
+        let after = unfolded_lines(&render(&db.find_all_meetings_by_token("token").unwrap()));
+
+        // Same event, moved, with a higher sequence and a new modification time
+        let property = |lines: &[String], name: &str| lines.iter().find(|line| line.starts_with(name)).cloned().unwrap();
+        assert_eq!(property(&before, "UID:"), property(&after, "UID:"));
+        assert_eq!(property(&after, "DTSTART:"), "DTSTART:20350331T060000Z");
+        assert_eq!(property(&before, "SEQUENCE:"), "SEQUENCE:0");
+        assert_eq!(property(&after, "SEQUENCE:"), "SEQUENCE:1");
+        assert!(property(&after, "LAST-MODIFIED:") >= property(&before, "LAST-MODIFIED:"));
+        assert_eq!(after.iter().filter(|line| *line == "BEGIN:VEVENT").count(), 1);
+    }
+
+    #[test]
+    fn test_calendar_escapes_and_folds_lines() {
+        let mut record = meeting("m1", ReservationStatus::Reserved);
+        record.subject = "周会; 项目A, 项目B\\备注\n第二行".to_string();
+        record.form_name = "西安会议室预约".to_string();
+        let long = format!("长主题{}", "会议".repeat(40));
+        let mut long_record = meeting("m2", ReservationStatus::Reserved);
+        long_record.subject = long.clone();
+
+        let calendar = render(&[record, long_record]);
+
+        // No physical line is longer than 75 octets, and folding keeps characters whole
+        for line in calendar.split("\r\n") {
+            assert!(line.len() <= 75, "line too long: {}", line);
+        }
+
+        let lines = unfolded_lines(&calendar);
+        assert!(lines.iter().any(|line| line == "SUMMARY:周会\\; 项目A\\, 项目B\\\\备注\\n第二行"));
+        assert!(lines.iter().any(|line| line == "LOCATION:西安-大会议室"));
+        assert!(lines.iter().any(|line| *line == format!("SUMMARY:{}", long)));
+    }
+
+    #[test]
+    fn test_empty_calendar() {
+        let calendar = render_calendar("空\\会议室", &[], Utc::now());
+        assert_eq!(unfolded_lines(&calendar), vec![
+            "BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//Tencent Meeting Service//Reservations//ZH", "CALSCALE:GREGORIAN", "METHOD:PUBLISH", "X-WR-CALNAME:空\\\\会议室", "END:VCALENDAR",
+        ]);
+    }
+}
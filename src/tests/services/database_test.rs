@@ -1,11 +1,14 @@
 use std::path::Path;
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use tempfile::tempdir;
 use std::collections::HashMap;
     
-use crate::services::database::DatabaseService;
+use crate::services::database::{DatabaseService, MeetingStore};
+use crate::models::reservation::ReservationStatus;
 use crate::models::form::{FormSubmission, FormEntry, FormField1Item};
 use crate::models::meeting::TimeSlot;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::time_slots::parse_time_slot;
 
 /// Database test module
 #[cfg(test)]
@@ -49,6 +52,7 @@ mod database_tests {
             start_time,
             end_time,
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         }
     }
     
@@ -105,7 +109,7 @@ mod database_tests {
         assert_eq!(meeting.entry_token, "test_token");
         assert_eq!(meeting.meeting_id, "meeting123");
         assert_eq!(meeting.scheduled_label, "2025-04-01 09:00-10:00");
-        assert_eq!(meeting.status, "已预约");
+        assert_eq!(meeting.status, ReservationStatus::Reserved);
         
         // Clean up
         dir.close().unwrap();
@@ -134,6 +138,7 @@ mod database_tests {
             start_time: start_time1,
             end_time: end_time1,
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let start_time2 = end_time1;
@@ -145,6 +150,7 @@ mod database_tests {
             start_time: start_time2,
             end_time: end_time2,
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         time_slots.push(slot1);
@@ -174,12 +180,59 @@ mod database_tests {
         assert_eq!(meeting.entry_token, "test_token");
         assert_eq!(meeting.meeting_id, "meeting123");
         assert_eq!(meeting.scheduled_label, "2025-04-01 09:00-11:00"); // Combined label
-        assert_eq!(meeting.status, "已预约");
+        assert_eq!(meeting.status, ReservationStatus::Reserved);
+        
+        // The record keeps the full time range and the original slots
+        assert_eq!(meeting.start_time, start_time1.to_rfc3339());
+        assert_eq!(meeting.end_time, end_time2.to_rfc3339());
+        assert_eq!(meeting.slot_labels, vec!["2025-04-01 09:00-10:00".to_string(), "2025-04-01 10:00-11:00".to_string()]);
+        
+        // The same values come back after a reload
+        let reloaded = DatabaseService::new(csv_path_str);
+        assert_eq!(reloaded.find_meeting_by_token(&form.entry.token).unwrap().unwrap(), meeting);
         
         // Clean up
         dir.close().unwrap();
     }
     
+    #[test]
+    fn test_store_created_meeting_keeps_join_url() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let csv_path_str = csv_path.to_str().unwrap();
+        let db = DatabaseService::new(csv_path_str);
+        let form = create_test_form();
+        
+        let start_time = Utc::now();
+        let slot = |label: &str, number: i32, start: chrono::DateTime<Utc>| TimeSlot {
+            item_name: "Test Room".to_string(),
+            scheduled_label: label.to_string(),
+            number,
+            start_time: start,
+            end_time: start + chrono::Duration::hours(1),
+            api_code: format!("CODE{}", number),
+            time_zone: DEFAULT_TIME_ZONE,
+        };
+        
+        // One slot is stored as it is
+        let single = vec![slot("2025-04-01 09:00-10:00", 1, start_time)];
+        db.store_created_meeting(&form, "meeting1", "Test Room", "room123", &single, "https://meeting.tencent.com/dm/abc", "operator1", "op123").unwrap();
+        
+        // Several slots become one merged record
+        let merged = vec![slot("2025-04-02 09:00-10:00", 1, start_time + chrono::Duration::days(1)), slot("2025-04-02 10:00-11:00", 2, start_time + chrono::Duration::days(1) + chrono::Duration::hours(1))];
+        db.store_created_meeting(&form, "meeting2", "Test Room", "room123", &merged, "", "operator1", "op123").unwrap();
+        
+        let records = DatabaseService::new(csv_path_str).find_all_meetings_by_token(&form.entry.token).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].scheduled_label, "2025-04-01 09:00-10:00");
+        assert_eq!(records[0].join_url, "https://meeting.tencent.com/dm/abc");
+        assert_eq!(records[1].scheduled_label, "2025-04-02 09:00-11:00");
+        assert_eq!(records[1].slot_labels.len(), 2);
+        assert_eq!(records[1].join_url, "");
+        
+        dir.close().unwrap();
+    }
+    
     #[test]
     fn test_cancel_meeting() {
         let dir = tempdir().unwrap();
@@ -206,20 +259,26 @@ mod database_tests {
         
         assert!(result.is_ok());
         
-        // Cancel the meeting
-        let cancelled = db.cancel_meeting(&form.entry.token);
+        // Request cancellation of the meeting
+        let cancelled = db.request_cancellation(&form.entry.token);
         assert!(cancelled.is_ok());
         let cancelled_ids = cancelled.unwrap();
         assert_eq!(cancelled_ids.len(), 1);
         assert_eq!(cancelled_ids[0].0, "meeting123"); // meeting_id
         assert_eq!(cancelled_ids[0].1, "room123"); // room_id
         
-        // Check that status was updated
+        // Nothing is cancelled until Tencent has confirmed it
+        let meetings = db.find_all_meetings_by_token(&form.entry.token).unwrap();
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].status, ReservationStatus::CancelRequested);
+        
+        // Check that status was updated once the cancellation is final
+        assert_eq!(db.finalize_cancellation("meeting123").unwrap(), 1);
         let retrieved = db.find_all_meetings_by_token(&form.entry.token);
         assert!(retrieved.is_ok());
         let meetings = retrieved.unwrap();
         assert_eq!(meetings.len(), 1);
-        assert_eq!(meetings[0].status, "已取消");
+        assert_eq!(meetings[0].status, ReservationStatus::Cancelled);
         assert!(!meetings[0].cancelled_at.is_empty());
         
         // Clean up
@@ -266,6 +325,7 @@ mod database_tests {
             start_time: Utc::now(),
             end_time: Utc::now() + chrono::Duration::hours(1),
             api_code: slot1.api_code.clone(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let time_slot2 = TimeSlot {
@@ -275,6 +335,7 @@ mod database_tests {
             start_time: Utc::now() + chrono::Duration::hours(2),
             end_time: Utc::now() + chrono::Duration::hours(3),
             api_code: slot2.api_code.clone(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Store two meetings with the same token but different times
@@ -299,7 +360,7 @@ mod database_tests {
         assert!(meeting_infos.contains(&("Room B".to_string(), "2025-04-01 11:00-12:00".to_string())));
         
         // Cancel all meetings
-        let cancelled = db.cancel_meeting(&form.entry.token);
+        let cancelled = db.request_cancellation(&form.entry.token);
         assert!(cancelled.is_ok());
         let cancelled_ids = cancelled.unwrap();
         
@@ -338,4 +399,427 @@ mod database_tests {
         // Clean up
         dir.close().unwrap();
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_reschedule_meeting() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        
+        let form = create_test_form();
+        let time_slot = create_time_slot();
+        db.store_meeting_with_time_slot(
+            &form, "meeting123", "Test Room", "room123", &time_slot, "Test User", "user123"
+        ).unwrap();
+        
+        // Move the meeting and change its subject
+        let start_time = Utc.with_ymd_and_hms(2025, 4, 2, 2, 0, 0).unwrap();
+        let end_time = Utc.with_ymd_and_hms(2025, 4, 2, 3, 0, 0).unwrap();
+        let updated = db.reschedule_meeting(
+            "meeting123",
+            start_time,
+            end_time,
+            "2025-04-02 10:00-11:00",
+            &["2025-04-02 10:00-11:00".to_string()],
+            Some("Moved Meeting"),
+        ).unwrap();
+        assert_eq!(updated, 1);
+        
+        let meetings = db.find_all_meetings_by_token(&form.entry.token).unwrap();
+        assert_eq!(meetings[0].scheduled_at, "2025-04-02T02:00:00+00:00");
+        assert_eq!(meetings[0].start_time, "2025-04-02T02:00:00+00:00");
+        assert_eq!(meetings[0].end_time, "2025-04-02T03:00:00+00:00");
+        assert_eq!(meetings[0].slot_labels, vec!["2025-04-02 10:00-11:00".to_string()]);
+        assert_eq!(meetings[0].scheduled_label, "2025-04-02 10:00-11:00");
+        assert_eq!(meetings[0].subject, "Moved Meeting");
+        assert_eq!(meetings[0].meeting_id, "meeting123");
+        assert_eq!(meetings[0].status, ReservationStatus::Rescheduled);
+        
+        // Unknown and cancelled meetings are not touched
+        assert_eq!(db.reschedule_meeting("unknown", start_time, end_time, "y", &[], None).unwrap(), 0);
+        db.request_cancellation(&form.entry.token).unwrap();
+        assert_eq!(db.reschedule_meeting("meeting123", start_time, end_time, "y", &[], None).unwrap(), 0);
+        let meetings = db.find_all_meetings_by_token(&form.entry.token).unwrap();
+        assert_eq!(meetings[0].scheduled_label, "2025-04-02 10:00-11:00");
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_two_phase_cancellation() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        
+        let form = create_test_form();
+        let time_slot = create_time_slot();
+        db.store_meeting_with_time_slot(
+            &form, "meeting123", "Test Room", "room123", &time_slot, "Test User", "user123"
+        ).unwrap();
+        let status = || db.find_all_meetings_by_token(&form.entry.token).unwrap()[0].status;
+        
+        // Requested: the room still has to be released
+        let requested = db.request_cancellation(&form.entry.token).unwrap();
+        assert_eq!(requested, vec![("meeting123".to_string(), "room123".to_string())]);
+        assert_eq!(status(), ReservationStatus::CancelRequested);
+        
+        // Only requests older than the cut-off are handed to the sweeper
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        let earlier = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(db.find_pending_cancellations(later).unwrap().len(), 1);
+        assert!(db.find_pending_cancellations(earlier).unwrap().is_empty());
+        
+        // A failed release is retried from the start
+        db.mark_cancellation_failed("meeting123").unwrap();
+        assert_eq!(status(), ReservationStatus::CancelFailed);
+        assert_eq!(db.find_pending_cancellations(later).unwrap().len(), 1);
+        let requested = db.request_cancellation(&form.entry.token).unwrap();
+        assert_eq!(requested[0].1, "room123");
+        assert_eq!(status(), ReservationStatus::CancelRequested);
+        
+        // Once the room is released only the meeting is left to cancel
+        db.mark_room_released("meeting123").unwrap();
+        assert_eq!(status(), ReservationStatus::RoomReleased);
+        let requested = db.request_cancellation(&form.entry.token).unwrap();
+        assert_eq!(requested, vec![("meeting123".to_string(), String::new())]);
+        
+        // Pending cancellations are not active meetings
+        assert_eq!(db.update_meeting_subject("meeting123", "New Subject").unwrap(), 0);
+        
+        db.finalize_cancellation("meeting123").unwrap();
+        assert_eq!(status(), ReservationStatus::Cancelled);
+        assert!(db.find_pending_cancellations(later).unwrap().is_empty());
+        assert!(db.request_cancellation(&form.entry.token).unwrap().is_empty());
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_migrates_old_schema() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let csv_path_str = csv_path.to_str().unwrap();
+        
+        // Version 1 layout: no operator columns
+        let old_contents = "entry_token,form_id,form_name,subject,room_name,scheduled_at,scheduled_label,status,meeting_id,room_id,created_at,cancelled_at\n\
+            old_token,form1,Form,Old Meeting,Room A,2025-04-01T01:00:00Z,2025-04-01 09:00-10:00,已预约,meeting_old,room_1,2025-03-01T00:00:00Z,\n";
+        std::fs::write(&csv_path, old_contents).unwrap();
+        
+        let db = DatabaseService::new(csv_path_str);
+        
+        // The original file is kept as a backup
+        let backup = std::fs::read_to_string(format!("{}.v1.bak", csv_path_str)).unwrap();
+        assert_eq!(backup, old_contents);
+        
+        // The header now has every column
+        let migrated = std::fs::read_to_string(&csv_path).unwrap();
+        assert!(migrated.starts_with(
+            "entry_token,form_id,form_name,subject,room_name,scheduled_at,scheduled_label,status,meeting_id,room_id,created_at,cancelled_at,operator_name,operator_id,start_time,end_time,slot_labels,join_url,time_zone,local_start_time,local_end_time,sequence,updated_at\n"
+        ));
+        
+        // Old rows can be read and updated, with empty operator fields and
+        // a time range derived from the scheduled time and label
+        let record = db.find_meeting_by_token("old_token").unwrap().unwrap();
+        assert_eq!(record.meeting_id, "meeting_old");
+        assert_eq!(record.operator_name, "");
+        assert_eq!(record.operator_id, "");
+        assert_eq!(record.start_time, "2025-04-01T01:00:00Z");
+        assert_eq!(record.end_time, "2025-04-01T02:00:00+00:00");
+        assert!(record.slot_labels.is_empty());
+        assert_eq!(record.join_url, "");
+        
+        // Older rows were booked in the default zone
+        assert_eq!(record.time_zone, "Asia/Shanghai");
+        assert_eq!(record.local_start_time, "2025-04-01T09:00:00+08:00");
+        assert_eq!(record.local_end_time, "2025-04-01T10:00:00+08:00");
+        
+        // And count as unchanged
+        assert_eq!(record.sequence, 0);
+        assert_eq!(record.updated_at, "");
+        
+        let requested = db.request_cancellation("old_token").unwrap();
+        assert_eq!(requested, vec![("meeting_old".to_string(), "room_1".to_string())]);
+        assert_eq!(db.find_all_meetings_by_token("old_token").unwrap()[0].sequence, 1);
+        
+        // Opening the migrated file again leaves it alone
+        DatabaseService::new(csv_path_str);
+        assert!(!Path::new(&format!("{}.v6.bak", csv_path_str)).exists());
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    #[should_panic(expected = "Unrecognized meetings.csv header")]
+    fn test_rejects_unknown_schema() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        
+        std::fs::write(&csv_path, "token,something_else\n").unwrap();
+        
+        DatabaseService::new(csv_path.to_str().unwrap());
+    }
+    
+    #[test]
+    fn test_recovers_torn_append() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let csv_path_str = csv_path.to_str().unwrap();
+        
+        // Store a meeting, then simulate a crash in the middle of the next append
+        {
+            let db = DatabaseService::new(csv_path_str);
+            let form = create_test_form();
+            let time_slot = create_time_slot();
+            db.store_meeting_with_time_slot(&form, "meeting_1", "Test Room", "room_1", &time_slot, "Operator", "op_1").unwrap();
+        }
+        let complete = std::fs::read_to_string(&csv_path).unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(&csv_path).unwrap();
+        std::io::Write::write_all(&mut file, b"torn_token,form1,\"Half a").unwrap();
+        drop(file);
+        
+        // Reopening drops the torn row and keeps everything else, with a backup of the original
+        let db = DatabaseService::new(csv_path_str);
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), complete);
+        let backup = std::fs::read_to_string(format!("{}.torn.bak", csv_path_str)).unwrap();
+        assert!(backup.ends_with("torn_token,form1,\"Half a"));
+        
+        let record = db.find_meeting_by_token("test_token").unwrap().unwrap();
+        assert_eq!(record.meeting_id, "meeting_1");
+        assert!(db.find_meeting_by_token("torn_token").unwrap().is_none());
+        
+        // New rows are appended cleanly after recovery
+        let mut form = create_test_form();
+        form.entry.token = "next_token".to_string();
+        db.store_meeting_with_time_slot(&form, "meeting_2", "Test Room", "room_1", &create_time_slot(), "Operator", "op_1").unwrap();
+        assert_eq!(db.find_meeting_by_token("next_token").unwrap().unwrap().meeting_id, "meeting_2");
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_keeps_complete_last_row_without_newline() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let csv_path_str = csv_path.to_str().unwrap();
+        
+        {
+            let db = DatabaseService::new(csv_path_str);
+            let form = create_test_form();
+            let time_slot = create_time_slot();
+            db.store_meeting_with_time_slot(&form, "meeting_1", "Test Room", "room_1", &time_slot, "Operator", "op_1").unwrap();
+        }
+        
+        // A file edited by hand may end without a newline
+        let complete = std::fs::read_to_string(&csv_path).unwrap();
+        std::fs::write(&csv_path, complete.trim_end_matches('\n')).unwrap();
+        
+        // The row is kept and terminated, so new rows still start on their own line
+        let db = DatabaseService::new(csv_path_str);
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), complete);
+        assert!(!std::path::Path::new(&format!("{}.torn.bak", csv_path_str)).exists());
+        assert_eq!(db.find_meeting_by_token("test_token").unwrap().unwrap().meeting_id, "meeting_1");
+        
+        let mut form = create_test_form();
+        form.entry.token = "next_token".to_string();
+        db.store_meeting_with_time_slot(&form, "meeting_2", "Test Room", "room_1", &create_time_slot(), "Operator", "op_1").unwrap();
+        let reloaded = DatabaseService::new(csv_path_str);
+        assert_eq!(reloaded.find_meeting_by_token("test_token").unwrap().unwrap().meeting_id, "meeting_1");
+        assert_eq!(reloaded.find_meeting_by_token("next_token").unwrap().unwrap().meeting_id, "meeting_2");
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_interrupted_rewrite_keeps_original() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let csv_path_str = csv_path.to_str().unwrap();
+        let temp_path = format!("{}.tmp", csv_path_str);
+        
+        {
+            let db = DatabaseService::new(csv_path_str);
+            let form = create_test_form();
+            let time_slot = create_time_slot();
+            db.store_meeting_with_time_slot(&form, "meeting_1", "Test Room", "room_1", &time_slot, "Operator", "op_1").unwrap();
+            
+            // Updates go through the temp file, which is gone once they finish
+            db.request_cancellation("test_token").unwrap();
+            assert!(!Path::new(&temp_path).exists());
+        }
+        let complete = std::fs::read_to_string(&csv_path).unwrap();
+        
+        // Simulate a crash before the rename: a partial temp file is left behind
+        std::fs::write(&temp_path, "entry_token,form_id\nhalf").unwrap();
+        
+        let db = DatabaseService::new(csv_path_str);
+        assert!(!Path::new(&temp_path).exists());
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), complete);
+        
+        let record = db.find_meeting_by_token("test_token").unwrap().unwrap();
+        assert_eq!(record.status, ReservationStatus::CancelRequested);
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_index_stays_in_sync_with_file() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let csv_path_str = csv_path.to_str().unwrap();
+        
+        let db = DatabaseService::new(csv_path_str);
+        let form = create_test_form();
+        let time_slot = create_time_slot();
+        db.store_meeting_with_time_slot(&form, "meeting_1", "Test Room", "room_1", &time_slot, "Operator", "op_1").unwrap();
+        
+        let on_date = db.find_meetings_by_room_and_date("Test Room", "2025-04-01").unwrap();
+        assert_eq!(on_date.len(), 1);
+        assert_eq!(on_date[0].meeting_id, "meeting_1");
+        
+        // Rescheduling moves the meeting to its new date
+        let start_time = Utc.with_ymd_and_hms(2025, 4, 2, 1, 0, 0).unwrap();
+        let end_time = start_time + chrono::Duration::hours(1);
+        db.reschedule_meeting("meeting_1", start_time, end_time, "2025-04-02 09:00-10:00", &[], None).unwrap();
+        assert!(db.find_meetings_by_room_and_date("Test Room", "2025-04-01").unwrap().is_empty());
+        assert_eq!(db.find_meetings_by_room_and_date("Test Room", "2025-04-02").unwrap().len(), 1);
+        
+        db.request_cancellation("test_token").unwrap();
+        
+        // A fresh load from the file sees the same records
+        let reloaded = DatabaseService::new(csv_path_str);
+        assert_eq!(
+            reloaded.find_all_meetings_by_token("test_token").unwrap(),
+            db.find_all_meetings_by_token("test_token").unwrap()
+        );
+        let record = reloaded.find_meetings_by_room_and_date("Test Room", "2025-04-02").unwrap();
+        assert_eq!(record[0].status, ReservationStatus::CancelRequested);
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_find_overlapping_meetings() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        let form = create_test_form();
+        
+        // 23:00-01:00 on 2025-04-01 (Shanghai), indexed under the first day
+        let overnight = TimeSlot {
+            scheduled_label: "2025-04-01 23:00-01:00".to_string(),
+            start_time: Utc.with_ymd_and_hms(2025, 4, 1, 15, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2025, 4, 1, 17, 0, 0).unwrap(),
+            ..create_time_slot()
+        };
+        db.store_meeting_with_time_slot(&form, "overnight", "Test Room", "room_1", &overnight, "Operator", "op_1").unwrap();
+        
+        let at = |hour: u32| Utc.with_ymd_and_hms(2025, 4, 1, hour, 0, 0).unwrap();
+        let found = |room: &str, start, end| -> Vec<String> {
+            db.find_overlapping_meetings(room, start, end, DEFAULT_TIME_ZONE).unwrap().into_iter().map(|record| record.meeting_id).collect()
+        };
+        
+        // 00:00-02:00 on 2025-04-02 overlaps; ranges that only touch it or are in another room don't
+        assert_eq!(found("Test Room", at(16), at(18)), vec!["overnight".to_string()]);
+        assert!(found("Test Room", at(17), at(18)).is_empty());
+        assert!(found("Test Room", at(14), at(15)).is_empty());
+        assert!(found("Other Room", at(16), at(18)).is_empty());
+        
+        // Cancelled meetings don't hold the room
+        db.request_cancellation("test_token").unwrap();
+        assert!(found("Test Room", at(16), at(18)).is_empty());
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_legacy_status_labels() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        
+        // Rows written with the English labels of older versions
+        std::fs::write(&csv_path, "entry_token,form_id,form_name,subject,room_name,scheduled_at,scheduled_label,status,meeting_id,room_id,created_at,cancelled_at,operator_name,operator_id\n\
+            token_a,form1,Form,Meeting,Room A,2025-04-01T01:00:00Z,2025-04-01 09:00-10:00,Reserved,meeting_a,room_1,2025-03-01T00:00:00Z,,,\n\
+            token_b,form1,Form,Meeting,Room A,2025-04-01T02:00:00Z,2025-04-01 10:00-11:00,Cancelled,meeting_b,room_1,2025-03-01T00:00:00Z,2025-03-02T00:00:00Z,,\n\
+            token_c,form1,Form,Meeting,Room A,2025-04-01T03:00:00Z,2025-04-01 11:00-12:00,预约成功,meeting_c,room_1,2025-03-01T00:00:00Z,,,\n\
+            token_d,form1,Form,Meeting,Room A,2025-04-01T04:00:00Z,2025-04-01 12:00-13:00,用户取消,meeting_d,room_1,2025-03-01T00:00:00Z,2025-03-02T00:00:00Z,,\n").unwrap();
+        
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        assert_eq!(db.find_meeting_by_token("token_a").unwrap().unwrap().status, ReservationStatus::Reserved);
+        assert!(db.find_meeting_by_token("token_b").unwrap().is_none());
+        
+        // Raw form statuses stored by older versions are kept, mapped like unmapped form statuses
+        assert_eq!(db.find_meeting_by_token("token_c").unwrap().unwrap().status, ReservationStatus::Reserved);
+        let cancelled = db.find_all_meetings_by_token("token_d").unwrap();
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].status, ReservationStatus::Cancelled);
+        
+        // Updated rows are written with the current labels
+        db.request_cancellation("token_a").unwrap();
+        let contents = std::fs::read_to_string(&csv_path).unwrap();
+        assert!(contents.contains(",取消中,meeting_a,"));
+        assert!(contents.contains(",Cancelled,meeting_b,"));
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_unpadded_label_is_stored_in_canonical_form() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        let form = create_test_form();
+        
+        // The form may send single-digit months, days and hours
+        let item = FormField1Item {
+            item_name: "Test Room".to_string(),
+            scheduled_label: "2035-4-1 9:00-10:00".to_string(),
+            number: 1,
+            scheduled_at: "2035-04-01T01:00:00Z".to_string(),
+            api_code: "CODE1".to_string(),
+        };
+        let time_slot = parse_time_slot(&item, DEFAULT_TIME_ZONE).unwrap();
+        assert_eq!(time_slot.scheduled_label, "2035-04-01 09:00-10:00");
+        db.store_meeting_with_time_slot(&form, "meeting_1", "Test Room", "room_1", &time_slot, "Operator", "op_1").unwrap();
+        
+        let on_date = db.find_meetings_by_room_and_date("Test Room", "2035-04-01").unwrap();
+        assert_eq!(on_date.len(), 1);
+        assert_eq!(on_date[0].scheduled_label, "2035-04-01 09:00-10:00");
+        
+        // A fresh load indexes it the same way
+        let reloaded = DatabaseService::new(csv_path.to_str().unwrap());
+        assert_eq!(reloaded.find_meetings_by_room_and_date("Test Room", "2035-04-01").unwrap().len(), 1);
+        
+        dir.close().unwrap();
+    }
+    
+    #[test]
+    fn test_unpadded_labels_of_older_versions_are_migrated() {
+        let dir = tempdir().unwrap();
+        let csv_path = dir.path().join("test_meetings.csv");
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        let form = create_test_form();
+        let item = FormField1Item {
+            item_name: "Test Room".to_string(),
+            scheduled_label: "2035-04-01 09:00-10:00".to_string(),
+            number: 1,
+            scheduled_at: "2035-04-01T01:00:00Z".to_string(),
+            api_code: "CODE1".to_string(),
+        };
+        let time_slot = parse_time_slot(&item, DEFAULT_TIME_ZONE).unwrap();
+        db.store_meeting_with_time_slot(&form, "meeting_1", "Test Room", "room_1", &time_slot, "Operator", "op_1").unwrap();
+        drop(db);
+        
+        // Older versions stored the label as the form sent it
+        let contents = std::fs::read_to_string(&csv_path).unwrap();
+        std::fs::write(&csv_path, contents.replace("2035-04-01 09:00-10:00", "2035-4-1 9:00-10:00")).unwrap();
+        
+        let db = DatabaseService::new(csv_path.to_str().unwrap());
+        let on_date = db.find_meetings_by_room_and_date("Test Room", "2035-04-01").unwrap();
+        assert_eq!(on_date.len(), 1);
+        assert_eq!(on_date[0].scheduled_label, "2035-04-01 09:00-10:00");
+        assert_eq!(on_date[0].slot_labels, vec!["2035-04-01 09:00-10:00".to_string()]);
+        assert!(!std::fs::read_to_string(&csv_path).unwrap().contains("2035-4-1"));
+        
+        dir.close().unwrap();
+    }
+}
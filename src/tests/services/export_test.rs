@@ -0,0 +1,81 @@
+use std::io::{Cursor, Read};
+use chrono::{TimeZone, Utc};
+
+use crate::models::reservation::ReservationStatus;
+use crate::services::database::MeetingRecord;
+use crate::services::export::{render_export, ExportFormat};
+use crate::tests::common::fixtures::generate_test_meeting;
+
+/// Reservation export tests
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    fn meeting(id: &str, room: &str) -> MeetingRecord {
+        let start_time = Utc.with_ymd_and_hms(2035, 3, 30, 1, 0, 0).unwrap();
+        let mut record = generate_test_meeting(id, "token", ReservationStatus::Reserved, start_time, start_time + chrono::Duration::minutes(90));
+        record.room_name = room.to_string();
+        record.slot_labels = vec!["2035-03-30 09:00-10:00".to_string(), "2035-03-30 10:00-10:30".to_string()];
+        record
+    }
+
+    // Contents of a file inside the xlsx archive
+    fn xlsx_file(data: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut contents = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let data = render_export(&[meeting("m1", "Room A")], ExportFormat::Csv).unwrap();
+        let text = String::from_utf8(data).unwrap();
+
+        // Byte order mark, then Chinese headers
+        let text = text.strip_prefix('\u{feff}').unwrap();
+        let mut lines = text.lines();
+        assert!(lines.next().unwrap().starts_with("会议室,预约时段,开始时间,结束时间,时长(分钟),会议主题"));
+
+        // Times are local, merged slots are listed
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("Room A,2035-03-30 09:00-10:00,2035-03-30 09:00,2035-03-30 10:30,90,Test Meeting,Test Operator,已预约,m1,"));
+        assert!(row.contains("2035-03-30 09:00-10:00、2035-03-30 10:00-10:30"));
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_json_lines_export() {
+        let records = vec![meeting("m1", "Room A"), meeting("m2", "Room B")];
+        let data = render_export(&records, ExportFormat::Jsonl).unwrap();
+        let text = String::from_utf8(data).unwrap();
+
+        let parsed: Vec<MeetingRecord> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(parsed, records);
+    }
+
+    #[test]
+    fn test_xlsx_export_has_a_sheet_per_room() {
+        let records = vec![
+            meeting("m1", "Room A"),
+            meeting("m2", "西安/大会议室"),
+            meeting("m3", "Room A"),
+        ];
+        let data = render_export(&records, ExportFormat::Xlsx).unwrap();
+
+        let workbook = xlsx_file(&data, "xl/workbook.xml");
+        assert!(workbook.contains(r#"name="Room A""#));
+        assert!(workbook.contains(r#"name="西安_大会议室""#));
+
+        // Headers and both Room A meetings are on the first sheet
+        let strings = xlsx_file(&data, "xl/sharedStrings.xml");
+        assert!(strings.contains("会议室"));
+        let first_sheet = xlsx_file(&data, "xl/worksheets/sheet1.xml");
+        assert!(first_sheet.contains(r#"<row r="3""#));
+        assert!(!first_sheet.contains(r#"<row r="4""#));
+
+        // An empty export is still a valid workbook
+        let data = render_export(&[], ExportFormat::Xlsx).unwrap();
+        assert!(xlsx_file(&data, "xl/workbook.xml").contains(r#"name="预约""#));
+    }
+}
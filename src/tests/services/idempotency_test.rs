@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::services::idempotency::SubmissionLocks;
+
+/// Submission lock test module
+#[cfg(test)]
+mod idempotency_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_same_token_is_serialized() {
+        let locks = Arc::new(SubmissionLocks::new());
+        let guard = locks.acquire("token_a").await;
+
+        let waiting_locks = Arc::clone(&locks);
+        let waiter = tokio::spawn(async move {
+            let _guard = waiting_locks.acquire("token_a").await;
+        });
+
+        // The second submission waits for the first
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should acquire the lock once it is released")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_different_tokens_do_not_block() {
+        let locks = SubmissionLocks::new();
+        let _guard_a = locks.acquire("token_a").await;
+
+        let guard_b = tokio::time::timeout(Duration::from_millis(100), locks.acquire("token_b")).await;
+        assert!(guard_b.is_ok());
+        assert_eq!(locks.in_flight(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_locks_are_removed_when_released() {
+        let locks = Arc::new(SubmissionLocks::new());
+
+        let guard = locks.acquire("token_a").await;
+        let waiting_locks = Arc::clone(&locks);
+        let waiter = tokio::spawn(async move {
+            let _guard = waiting_locks.acquire("token_a").await;
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Still needed by the waiter after the first guard is dropped
+        drop(guard);
+        waiter.await.unwrap();
+
+        assert_eq!(locks.in_flight(), 0);
+    }
+}
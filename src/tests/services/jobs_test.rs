@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use tempfile::tempdir;
+
+use crate::models::form::{FormEntry, FormField1Item, FormSubmission};
+use crate::models::meeting::WebhookResponse;
+use crate::services::jobs::{JobQueue, JobStatus};
+
+/// Job queue test module
+#[cfg(test)]
+mod jobs_tests {
+    use super::*;
+
+    fn create_test_form(token: &str) -> FormSubmission {
+        FormSubmission {
+            form: "test_form".to_string(),
+            form_name: "Test Form".to_string(),
+            entry: FormEntry {
+                token: token.to_string(),
+                field_1: vec![FormField1Item {
+                    item_name: "Test Room".to_string(),
+                    scheduled_label: "2035-04-01 09:00-10:00".to_string(),
+                    number: 1,
+                    scheduled_at: "2035-04-01T01:00:00.000Z".to_string(),
+                    api_code: "CODE1".to_string(),
+                }],
+                field_8: "Test Meeting".to_string(),
+                extra_fields: HashMap::new(),
+                reservation_status_fsf_field: "已预约".to_string(),
+            },
+        }
+    }
+
+    fn test_response() -> WebhookResponse {
+        WebhookResponse {
+            success: true,
+            message: "Created 1 meetings from 1 time slots".to_string(),
+            meetings_count: 0,
+            meetings: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_persists_and_delivers_job() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::open(dir.path(), 3, Duration::from_millis(10)).unwrap();
+        let mut receiver = queue.take_receiver().unwrap();
+        assert!(queue.take_receiver().is_none());
+
+        let job = queue.enqueue(create_test_form("job_token")).unwrap();
+
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.entry_token, "job_token");
+        assert!(dir.path().join(format!("{}.json", job.id)).exists());
+        assert_eq!(receiver.recv().await.unwrap(), job.id);
+
+        let running = queue.start(&job.id).unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert_eq!(running.attempts, 1);
+        // A job that is already running is not started twice
+        assert!(queue.start(&job.id).is_none());
+
+        queue.succeed(&job.id, test_response());
+        let finished = queue.get(&job.id).unwrap();
+        assert_eq!(finished.status, JobStatus::Succeeded);
+        assert!(finished.result.unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_unfinished_jobs_survive_restart() {
+        let dir = tempdir().unwrap();
+
+        let (queued_id, running_id, done_id) = {
+            let queue = JobQueue::open(dir.path(), 3, Duration::from_millis(10)).unwrap();
+            let queued = queue.enqueue(create_test_form("queued_token")).unwrap();
+            let running = queue.enqueue(create_test_form("running_token")).unwrap();
+            let done = queue.enqueue(create_test_form("done_token")).unwrap();
+
+            queue.start(&running.id);
+            queue.start(&done.id);
+            queue.succeed(&done.id, test_response());
+
+            (queued.id, running.id, done.id)
+        };
+
+        // Reopen the queue as if the service restarted
+        let queue = JobQueue::open(dir.path(), 3, Duration::from_millis(10)).unwrap();
+        let mut receiver = queue.take_receiver().unwrap();
+
+        let mut resumed = vec![receiver.recv().await.unwrap(), receiver.recv().await.unwrap()];
+        resumed.sort();
+        let mut expected = vec![queued_id.clone(), running_id.clone()];
+        expected.sort();
+        assert_eq!(resumed, expected);
+        assert!(receiver.try_recv().is_err());
+
+        // The interrupted job is queued again and keeps its attempt count
+        let interrupted = queue.get(&running_id).unwrap();
+        assert_eq!(interrupted.status, JobStatus::Queued);
+        assert_eq!(interrupted.attempts, 1);
+        assert_eq!(queue.get(&done_id).unwrap().status, JobStatus::Succeeded);
+    }
+
+    #[tokio::test]
+    async fn test_failed_jobs_retry_until_attempts_run_out() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::open(dir.path(), 2, Duration::from_millis(10)).unwrap();
+        let job = queue.enqueue(create_test_form("failing_token")).unwrap();
+
+        queue.start(&job.id);
+        let delay = queue.fail(&job.id, "rate limited", true);
+        assert_eq!(delay, Some(Duration::from_millis(10)));
+        assert_eq!(queue.get(&job.id).unwrap().status, JobStatus::Queued);
+
+        queue.start(&job.id);
+        assert_eq!(queue.fail(&job.id, "rate limited", true), None);
+        let failed = queue.get(&job.id).unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.attempts, 2);
+        assert_eq!(failed.last_error.as_deref(), Some("rate limited"));
+
+        // Non-retryable failures give up immediately
+        let other = queue.enqueue(create_test_form("bad_token")).unwrap();
+        queue.start(&other.id);
+        assert_eq!(queue.fail(&other.id, "bad request", false), None);
+        assert_eq!(queue.get(&other.id).unwrap().status, JobStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_unsuccessful_responses_are_retried_and_kept() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::open(dir.path(), 2, Duration::from_millis(10)).unwrap();
+        let job = queue.enqueue(create_test_form("unbooked_token")).unwrap();
+        let response = WebhookResponse {
+            success: false,
+            message: "Created 0 meetings from 1 time slots; 1 failed: room already booked".to_string(),
+            ..test_response()
+        };
+
+        queue.start(&job.id);
+        assert_eq!(queue.fail_with_response(&job.id, response.clone()), Some(Duration::from_millis(10)));
+        assert_eq!(queue.get(&job.id).unwrap().status, JobStatus::Queued);
+
+        // Out of attempts, the job fails with the last response
+        queue.start(&job.id);
+        assert_eq!(queue.fail_with_response(&job.id, response.clone()), None);
+        let failed = queue.get(&job.id).unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.last_error.as_deref(), Some(response.message.as_str()));
+        assert_eq!(failed.result.unwrap().message, response.message);
+    }
+}
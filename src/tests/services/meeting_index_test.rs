@@ -0,0 +1,91 @@
+use crate::models::reservation::ReservationStatus;
+use crate::services::database::{MeetingFilter, MeetingRecord};
+use crate::services::meeting_index::{scheduled_date, MeetingIndex};
+
+/// Meeting index tests
+#[cfg(test)]
+mod meeting_index_tests {
+    use super::*;
+
+    fn record(token: &str, meeting_id: &str, room_name: &str, label: &str) -> MeetingRecord {
+        MeetingRecord {
+            entry_token: token.to_string(),
+            form_id: "form1".to_string(),
+            form_name: "Form".to_string(),
+            subject: "Meeting".to_string(),
+            room_name: room_name.to_string(),
+            scheduled_at: "2025-04-01T01:00:00Z".to_string(),
+            scheduled_label: label.to_string(),
+            status: ReservationStatus::Reserved,
+            start_time: "2025-04-01T01:00:00Z".to_string(),
+            end_time: "2025-04-01T02:00:00Z".to_string(),
+            slot_labels: vec![label.to_string()],
+            meeting_id: meeting_id.to_string(),
+            room_id: "room1".to_string(),
+            created_at: "2025-03-01T00:00:00Z".to_string(),
+            cancelled_at: String::new(),
+            operator_name: String::new(),
+            operator_id: String::new(),
+            join_url: String::new(),
+            time_zone: "Asia/Shanghai".to_string(),
+            local_start_time: "2025-04-01T09:00:00+08:00".to_string(),
+            local_end_time: "2025-04-01T10:00:00+08:00".to_string(),
+            sequence: 0,
+            updated_at: String::new(),
+        }
+    }
+
+    fn meeting_ids(records: Vec<MeetingRecord>) -> Vec<String> {
+        records.into_iter().map(|r| r.meeting_id).collect()
+    }
+
+    #[test]
+    fn test_scheduled_date() {
+        assert_eq!(scheduled_date("2025-04-01 09:00-10:00"), "2025-04-01");
+        assert_eq!(scheduled_date("2025-04-01"), "2025-04-01");
+        assert_eq!(scheduled_date(""), "");
+    }
+
+    #[test]
+    fn test_lookups_by_key() {
+        let mut index = MeetingIndex::new();
+        index.push(Some(record("token_a", "m1", "Room A", "2025-04-01 09:00-10:00")));
+        index.push(None); // Unreadable row
+        index.push(Some(record("token_b", "m2", "Room A", "2025-04-01 10:00-11:00")));
+        index.push(Some(record("token_a", "m3", "Room B", "2025-04-02 09:00-10:00")));
+
+        assert_eq!(meeting_ids(index.find(MeetingFilter::Token("token_a"))), vec!["m1", "m3"]);
+        assert_eq!(meeting_ids(index.find(MeetingFilter::MeetingId("m2"))), vec!["m2"]);
+        assert_eq!(
+            meeting_ids(index.find(MeetingFilter::RoomOnDate { room_name: "Room A", date: "2025-04-01" })),
+            vec!["m1", "m2"]
+        );
+        assert!(index.find(MeetingFilter::Token("missing")).is_empty());
+
+        // All skips the unreadable row but keeps positions
+        assert_eq!(meeting_ids(index.find(MeetingFilter::All)), vec!["m1", "m2", "m3"]);
+        assert_eq!(index.positions(MeetingFilter::All), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_replace_moves_keys() {
+        let mut index = MeetingIndex::new();
+        index.push(Some(record("token_a", "m1", "Room A", "2025-04-01 09:00-10:00")));
+        index.push(Some(record("token_a", "m2", "Room A", "2025-04-02 09:00-10:00")));
+
+        // Reschedule m1 to the next day
+        index.replace(0, record("token_a", "m1", "Room A", "2025-04-02 14:00-15:00"));
+
+        assert!(index
+            .find(MeetingFilter::RoomOnDate { room_name: "Room A", date: "2025-04-01" })
+            .is_empty());
+        assert_eq!(
+            meeting_ids(index.find(MeetingFilter::RoomOnDate { room_name: "Room A", date: "2025-04-02" })),
+            vec!["m1", "m2"]
+        );
+
+        // Rows keep their insertion order
+        assert_eq!(meeting_ids(index.find(MeetingFilter::Token("token_a"))), vec!["m1", "m2"]);
+        assert_eq!(index.get(0).unwrap().scheduled_label, "2025-04-02 14:00-15:00");
+    }
+}
@@ -1,2 +1,12 @@
+pub mod async_store_test;
+pub mod blackouts_test;
+pub mod booking_policy_test;
+pub mod calendar_test;
 pub mod database_test;
+pub mod export_test;
+pub mod idempotency_test;
+pub mod jobs_test;
+pub mod meeting_index_test;
+pub mod reconcile_test;
+pub mod sqlite_store_test;
 pub mod time_slots_test;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use chrono::{TimeZone, Utc};
+
+use crate::models::meeting::TimeSlot;
+use crate::models::reservation::ReservationStatus;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::database::MeetingRecord;
+use crate::services::reconcile::{plan_reconciliation, DesiredMeeting, ReconcileStep};
+
+/// Reconciliation test module
+#[cfg(test)]
+mod reconcile_tests {
+    use super::*;
+
+    // Slot on 2025-04-01 starting at the given local (UTC+8) hour
+    fn slot(room: &str, hour: u32) -> TimeSlot {
+        let start_time = Utc.with_ymd_and_hms(2025, 4, 1, hour - 8, 0, 0).unwrap();
+        TimeSlot {
+            item_name: room.to_string(),
+            scheduled_label: format!("2025-04-01 {:02}:00-{:02}:00", hour, hour + 1),
+            number: 1,
+            start_time,
+            end_time: start_time + chrono::Duration::hours(1),
+            api_code: "CODE".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
+        }
+    }
+
+    fn record(meeting_id: &str, room: &str, label: &str, subject: &str) -> MeetingRecord {
+        MeetingRecord {
+            entry_token: "token".to_string(),
+            form_id: "form".to_string(),
+            form_name: "Test Form".to_string(),
+            subject: subject.to_string(),
+            room_name: room.to_string(),
+            scheduled_at: format!("{}T00:00:00+00:00", &label[..10]),
+            scheduled_label: label.to_string(),
+            status: ReservationStatus::Reserved,
+            start_time: format!("{}T00:00:00+00:00", &label[..10]),
+            end_time: String::new(),
+            slot_labels: vec![label.to_string()],
+            meeting_id: meeting_id.to_string(),
+            room_id: "room1".to_string(),
+            created_at: "".to_string(),
+            cancelled_at: "".to_string(),
+            operator_name: "Test User".to_string(),
+            operator_id: "user1".to_string(),
+            join_url: String::new(),
+            time_zone: "Asia/Shanghai".to_string(),
+            local_start_time: format!("{}T08:00:00+08:00", &label[..10]),
+            local_end_time: String::new(),
+            sequence: 0,
+            updated_at: String::new(),
+        }
+    }
+
+    fn desired(groups: &[&[TimeSlot]]) -> Vec<DesiredMeeting> {
+        groups.iter().map(|group| DesiredMeeting::from_group(group).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_desired_meeting_uses_stored_label_format() {
+        let merged = DesiredMeeting::from_group(&[slot("Room A", 10), slot("Room A", 9)]).unwrap();
+        assert_eq!(merged.scheduled_label, "2025-04-01 09:00-11:00");
+        assert!(merged.is_merged());
+        assert_eq!(merged.slot_labels(), vec!["2025-04-01 09:00-10:00", "2025-04-01 10:00-11:00"]);
+
+        let single = DesiredMeeting::from_group(&[slot("Room A", 14)]).unwrap();
+        assert_eq!(single.scheduled_label, "2025-04-01 14:00-15:00");
+        assert!(!single.is_merged());
+
+        assert!(DesiredMeeting::from_group(&[]).is_none());
+    }
+
+    #[test]
+    fn test_identical_submission_keeps_meetings() {
+        let existing = vec![record("m1", "Room A", "2025-04-01 09:00-11:00", "Sync")];
+        let steps = plan_reconciliation(&existing, desired(&[&[slot("Room A", 9), slot("Room A", 10)]]), "Sync");
+
+        assert_eq!(steps.len(), 1);
+        assert!(matches!(&steps[0], ReconcileStep::Keep { record, .. } if record.meeting_id == "m1"));
+    }
+
+    #[test]
+    fn test_subject_change_updates_meeting() {
+        let existing = vec![record("m1", "Room A", "2025-04-01 09:00-10:00", "Sync")];
+        let steps = plan_reconciliation(&existing, desired(&[&[slot("Room A", 9)]]), "Planning");
+
+        assert_eq!(steps.len(), 1);
+        assert!(matches!(&steps[0], ReconcileStep::UpdateSubject { record, .. } if record.meeting_id == "m1"));
+    }
+
+    #[test]
+    fn test_moved_slot_reschedules_meeting_in_same_room() {
+        let existing = vec![record("m1", "Room A", "2025-04-01 09:00-10:00", "Sync")];
+        let steps = plan_reconciliation(&existing, desired(&[&[slot("Room A", 14)]]), "Sync");
+
+        assert_eq!(steps.len(), 1);
+        match &steps[0] {
+            ReconcileStep::Reschedule { record, desired } => {
+                assert_eq!(record.meeting_id, "m1");
+                assert_eq!(desired.scheduled_label, "2025-04-01 14:00-15:00");
+            }
+            other => panic!("expected reschedule, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_added_and_removed_slots() {
+        let existing = vec![
+            record("m1", "Room A", "2025-04-01 09:00-10:00", "Sync"),
+            record("m2", "Room B", "2025-04-01 11:00-12:00", "Sync"),
+        ];
+        // Room B slot removed, Room C slot added, Room A kept
+        let steps = plan_reconciliation(
+            &existing,
+            desired(&[&[slot("Room A", 9)], &[slot("Room C", 15)]]),
+            "Sync",
+        );
+
+        assert_eq!(steps.len(), 3);
+        // Cancellations come first so they free rooms before anything else runs
+        assert!(matches!(&steps[0], ReconcileStep::Cancel { record } if record.meeting_id == "m2"));
+        assert!(matches!(&steps[1], ReconcileStep::Keep { record, .. } if record.meeting_id == "m1"));
+        assert!(matches!(&steps[2], ReconcileStep::Create { desired } if desired.room_name == "Room C"));
+    }
+
+    #[test]
+    fn test_exact_matches_win_over_reschedules() {
+        let existing = vec![
+            record("m1", "Room A", "2025-04-01 09:00-10:00", "Sync"),
+            record("m2", "Room A", "2025-04-01 14:00-15:00", "Sync"),
+        ];
+        // The 09:00 slot moved to 16:00, the 14:00 slot is unchanged
+        let steps = plan_reconciliation(
+            &existing,
+            desired(&[&[slot("Room A", 14)], &[slot("Room A", 16)]]),
+            "Sync",
+        );
+
+        assert_eq!(steps.len(), 2);
+        assert!(matches!(&steps[0], ReconcileStep::Keep { record, .. } if record.meeting_id == "m2"));
+        assert!(matches!(&steps[1], ReconcileStep::Reschedule { record, .. } if record.meeting_id == "m1"));
+    }
+}
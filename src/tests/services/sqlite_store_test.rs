@@ -0,0 +1,222 @@
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+use crate::models::form::{FormEntry, FormField1Item, FormSubmission};
+use crate::models::meeting::TimeSlot;
+use crate::models::reservation::ReservationStatus;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
+use crate::services::database::MeetingStore;
+use crate::services::sqlite_store::SqliteMeetingStore;
+
+/// SQLite meeting store tests
+#[cfg(test)]
+mod sqlite_store_tests {
+    use super::*;
+
+    fn create_test_form(token: &str) -> FormSubmission {
+        let mut extra_fields = HashMap::new();
+        extra_fields.insert("user_field_name".to_string(), "Test User".into());
+
+        FormSubmission {
+            form: "test_form".to_string(),
+            form_name: "Test Form".to_string(),
+            entry: FormEntry {
+                token: token.to_string(),
+                field_1: vec![FormField1Item {
+                    item_name: "Test Room".to_string(),
+                    scheduled_label: "2025-04-01 09:00-10:00".to_string(),
+                    number: 1,
+                    scheduled_at: "2025-04-01T01:00:00.000Z".to_string(),
+                    api_code: "CODE1".to_string(),
+                }],
+                field_8: "Test Meeting".to_string(),
+                extra_fields,
+                reservation_status_fsf_field: "已预约".to_string(),
+            },
+        }
+    }
+
+    fn create_time_slot(label: &str, hours_from_now: i64) -> TimeSlot {
+        let start_time = Utc::now() + chrono::Duration::hours(hours_from_now);
+        TimeSlot {
+            item_name: "Test Room".to_string(),
+            scheduled_label: label.to_string(),
+            number: 1,
+            start_time,
+            end_time: start_time + chrono::Duration::hours(1),
+            api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
+        }
+    }
+
+    #[test]
+    fn test_store_and_find_meetings() {
+        let dir = tempdir().unwrap();
+        let store = SqliteMeetingStore::open(dir.path().join("meetings.db").to_str().unwrap()).unwrap();
+        let form = create_test_form("sqlite_token");
+
+        let slot1 = create_time_slot("2025-04-01 09:00-10:00", 1);
+        let slot2 = create_time_slot("2025-04-01 10:00-11:00", 2);
+        store.store_meeting_with_time_slot(&form, "meeting1", "Test Room", "room1", &slot1, "op", "op_id").unwrap();
+        store.store_merged_meeting(&form, "meeting2", "Test Room", "room1", &[slot2.clone(), slot1.clone()], "op", "op_id").unwrap();
+
+        // Identical entries are stored once
+        store.store_meeting_with_time_slot(&form, "meeting3", "Test Room", "room1", &slot1, "op", "op_id").unwrap();
+
+        let meetings = store.find_all_meetings_by_token("sqlite_token").unwrap();
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[0].meeting_id, "meeting1");
+        assert_eq!(meetings[1].meeting_id, "meeting2");
+        assert_eq!(meetings[1].scheduled_label, "2025-04-01 09:00-11:00");
+        assert_eq!(meetings[1].operator_id, "op_id");
+        assert_eq!(meetings[1].start_time, slot1.start_time.to_rfc3339());
+        assert_eq!(meetings[1].end_time, slot2.end_time.to_rfc3339());
+        assert_eq!(meetings[1].slot_labels, vec![slot1.scheduled_label.clone(), slot2.scheduled_label.clone()]);
+
+        assert_eq!(store.find_meeting_by_token("sqlite_token").unwrap().unwrap().meeting_id, "meeting1");
+        assert!(store.find_meeting_by_token_and_status("sqlite_token", ReservationStatus::Cancelled).unwrap().is_none());
+        assert!(store.find_all_meetings_by_token("other_token").unwrap().is_empty());
+
+        let on_date = store.find_meetings_by_room_and_date("Test Room", "2025-04-01").unwrap();
+        assert_eq!(on_date.len(), 2);
+        assert!(store.find_meetings_by_room_and_date("Test Room", "2025-04-02").unwrap().is_empty());
+        assert!(store.find_meetings_by_room_and_date("Other Room", "2025-04-01").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cancellation_and_updates() {
+        let dir = tempdir().unwrap();
+        let store = SqliteMeetingStore::open(dir.path().join("meetings.db").to_str().unwrap()).unwrap();
+        let form = create_test_form("cancel_token");
+        let slot = create_time_slot("2025-04-01 09:00-10:00", 1);
+        store.store_meeting_with_time_slot(&form, "meeting1", "Test Room", "room1", &slot, "op", "op_id").unwrap();
+
+        assert_eq!(store.update_meeting_subject("meeting1", "New Subject").unwrap(), 1);
+        let start_time = Utc.with_ymd_and_hms(2025, 4, 2, 1, 0, 0).unwrap();
+        let end_time = start_time + chrono::Duration::hours(1);
+        let slot_labels = ["2025-04-02 09:00-10:00".to_string()];
+        assert_eq!(store.reschedule_meeting("meeting1", start_time, end_time, "2025-04-02 09:00-10:00", &slot_labels, None).unwrap(), 1);
+
+        let requested = store.request_cancellation("cancel_token").unwrap();
+        assert_eq!(requested, vec![("meeting1".to_string(), "room1".to_string())]);
+        let record = store.find_meeting_by_token("cancel_token").unwrap().unwrap();
+        assert_eq!(record.status, ReservationStatus::CancelRequested);
+        assert_eq!(record.subject, "New Subject");
+        assert_eq!(record.scheduled_label, "2025-04-02 09:00-10:00");
+        assert_eq!(record.end_time, end_time.to_rfc3339());
+        assert_eq!(record.slot_labels, slot_labels);
+
+        store.mark_room_released("meeting1").unwrap();
+        let pending = store.find_pending_cancellations(Utc::now()).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].status, ReservationStatus::RoomReleased);
+
+        store.finalize_cancellation("meeting1").unwrap();
+        assert!(store.find_meeting_by_token("cancel_token").unwrap().is_none());
+        assert!(store.find_pending_cancellations(Utc::now()).unwrap().is_empty());
+        assert!(store.request_cancellation("cancel_token").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_database_is_shared_between_stores() {
+        // Two stores on the same file stand in for two service processes
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("meetings.db");
+        let first = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+        let second = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+
+        let form = create_test_form("shared_token");
+        let slot = create_time_slot("2025-04-01 09:00-10:00", 1);
+        first.store_meeting_with_time_slot(&form, "meeting1", "Test Room", "room1", &slot, "op", "op_id").unwrap();
+
+        // The second process sees the record and does not store a duplicate
+        assert_eq!(second.find_all_meetings_by_token("shared_token").unwrap().len(), 1);
+        second.store_meeting_with_time_slot(&form, "meeting2", "Test Room", "room1", &slot, "op", "op_id").unwrap();
+        assert_eq!(first.find_all_meetings_by_token("shared_token").unwrap().len(), 1);
+
+        // Only one of them gets to request the cancellation
+        assert_eq!(second.request_cancellation("shared_token").unwrap().len(), 1);
+        assert!(first.request_cancellation("shared_token").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_adds_time_range_to_old_table() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("meetings.db");
+
+        // Table as created before records had a time range
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE meetings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entry_token TEXT NOT NULL, form_id TEXT NOT NULL, form_name TEXT NOT NULL,
+                subject TEXT NOT NULL, room_name TEXT NOT NULL, scheduled_at TEXT NOT NULL,
+                scheduled_label TEXT NOT NULL, status TEXT NOT NULL, meeting_id TEXT NOT NULL,
+                room_id TEXT NOT NULL, created_at TEXT NOT NULL, cancelled_at TEXT NOT NULL,
+                operator_name TEXT NOT NULL, operator_id TEXT NOT NULL
+            );
+            INSERT INTO meetings (entry_token, form_id, form_name, subject, room_name, scheduled_at,
+                scheduled_label, status, meeting_id, room_id, created_at, cancelled_at, operator_name, operator_id)
+            VALUES ('old_token', 'form1', 'Form', 'Old Meeting', 'Room A', '2025-04-01T15:00:00+00:00',
+                '2025-04-01 23:00-00:30', '已预约', 'meeting_old', 'room_1', '', '', '', '');",
+        ).unwrap();
+        drop(conn);
+
+        let store = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+        let record = store.find_meeting_by_token("old_token").unwrap().unwrap();
+        assert_eq!(record.start_time, "2025-04-01T15:00:00+00:00");
+        assert_eq!(record.end_time, "2025-04-01T16:30:00+00:00");
+        assert!(record.slot_labels.is_empty());
+        assert!(record.join_url.is_empty());
+        assert_eq!(record.time_zone, "Asia/Shanghai");
+        assert_eq!(record.local_start_time, "2025-04-01T23:00:00+08:00");
+        assert_eq!(record.local_end_time, "2025-04-02T00:30:00+08:00");
+
+        // Opening it again leaves the data alone
+        drop(store);
+        let store = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(store.find_meeting_by_token("old_token").unwrap().unwrap(), record);
+    }
+
+    #[test]
+    fn test_reads_raw_form_statuses_of_old_rows() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("meetings.db");
+        let store = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+        let slot = create_time_slot("2025-04-01 09:00-10:00", 1);
+        store.store_meeting_with_time_slot(&create_test_form("legacy_token"), "meeting_legacy", "Test Room", "room_1", &slot, "op", "op_id").unwrap();
+
+        // Older versions stored the form's status string as it was
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute("UPDATE meetings SET status = '预约成功' WHERE entry_token = 'legacy_token'", []).unwrap();
+        drop(conn);
+
+        // The row stays readable, and so does every other query
+        let record = store.find_meeting_by_token("legacy_token").unwrap().unwrap();
+        assert_eq!(record.status, ReservationStatus::Reserved);
+        assert_eq!(store.request_cancellation("legacy_token").unwrap().len(), 1);
+        assert_eq!(store.find_all_meetings_by_token("legacy_token").unwrap()[0].status, ReservationStatus::CancelRequested);
+    }
+
+    #[test]
+    fn test_migrates_unpadded_labels_of_old_rows() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("meetings.db");
+        let store = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+        let slot = create_time_slot("2025-04-01 09:00-10:00", 1);
+        store.store_meeting_with_time_slot(&create_test_form("unpadded_token"), "meeting_unpadded", "Test Room", "room_1", &slot, "op", "op_id").unwrap();
+        drop(store);
+
+        // Older versions stored the label as the form sent it
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute("UPDATE meetings SET scheduled_label = '2025-4-1 9:00-10:00', slot_labels = '2025-4-1 9:00-10:00'", []).unwrap();
+        drop(conn);
+
+        let store = SqliteMeetingStore::open(path.to_str().unwrap()).unwrap();
+        let on_date = store.find_meetings_by_room_and_date("Test Room", "2025-04-01").unwrap();
+        assert_eq!(on_date.len(), 1);
+        assert_eq!(on_date[0].scheduled_label, "2025-04-01 09:00-10:00");
+        assert_eq!(on_date[0].slot_labels, vec!["2025-04-01 09:00-10:00".to_string()]);
+    }
+}
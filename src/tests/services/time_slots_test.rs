@@ -1,8 +1,11 @@
-use chrono::{TimeZone, Utc};
+use chrono::{TimeZone, Timelike, Utc};
     
-use crate::services::time_slots::{parse_time_slot, find_mergeable_groups};
+use crate::services::time_slots::{parse_time_slot, find_mergeable_groups, format_scheduled_label, merged_scheduled_label};
+use chrono_tz::{America::New_York, Europe::London};
 use crate::models::form::FormField1Item;
-use crate::models::meeting::TimeSlot;
+use crate::models::meeting::{TimeSlot, TimeSlotError};
+use crate::models::scheduled_label::ScheduledLabelError;
+use crate::models::time_zone::DEFAULT_TIME_ZONE;
 
 /// Time slots test module
 #[cfg(test)]
@@ -20,7 +23,7 @@ mod time_slots_tests {
             api_code: "CODE1".to_string(),
         };
         
-        let result = parse_time_slot(&item);
+        let result = parse_time_slot(&item, DEFAULT_TIME_ZONE);
         assert!(result.is_ok());
         
         let time_slot = result.unwrap();
@@ -40,7 +43,7 @@ mod time_slots_tests {
             api_code: "CODE1".to_string(),
         };
         
-        let result = parse_time_slot(&item);
+        let result = parse_time_slot(&item, DEFAULT_TIME_ZONE);
         assert!(result.is_ok());
         
         let time_slot = result.unwrap();
@@ -69,6 +72,7 @@ mod time_slots_tests {
             start_time,
             end_time,
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let slots = vec![slot];
@@ -92,6 +96,7 @@ mod time_slots_tests {
             start_time: start_time1,
             end_time: end_time1,
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let start_time2 = Utc.with_ymd_and_hms(2025, 4, 1, 10, 0, 0).unwrap();
@@ -104,6 +109,7 @@ mod time_slots_tests {
             start_time: start_time2,
             end_time: end_time2,
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let slots = vec![slot1, slot2];
@@ -126,6 +132,7 @@ mod time_slots_tests {
             start_time: start_time1,
             end_time: end_time1,
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Gap between meetings
@@ -139,6 +146,7 @@ mod time_slots_tests {
             start_time: start_time2,
             end_time: end_time2,
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let slots = vec![slot1, slot2];
@@ -162,6 +170,7 @@ mod time_slots_tests {
             start_time: start_time1,
             end_time: end_time1,
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let start_time2 = Utc.with_ymd_and_hms(2025, 4, 1, 10, 0, 0).unwrap();
@@ -174,6 +183,7 @@ mod time_slots_tests {
             start_time: start_time2,
             end_time: end_time2,
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let slots = vec![slot1, slot2];
@@ -197,6 +207,7 @@ mod time_slots_tests {
             start_time: Utc.with_ymd_and_hms(2035, 4, 1, 9, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2035, 4, 1, 10, 0, 0).unwrap(),
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Room A, 10:00-11:00 (consecutive with slot1)
@@ -207,6 +218,7 @@ mod time_slots_tests {
             start_time: Utc.with_ymd_and_hms(2035, 4, 1, 10, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2035, 4, 1, 11, 0, 0).unwrap(),
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Room B, 9:00-10:00
@@ -217,6 +229,7 @@ mod time_slots_tests {
             start_time: Utc.with_ymd_and_hms(2035, 4, 1, 9, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2035, 4, 1, 10, 0, 0).unwrap(),
             api_code: "CODE3".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Room B, 10:30-11:30 (non-consecutive with slot3)
@@ -227,6 +240,7 @@ mod time_slots_tests {
             start_time: Utc.with_ymd_and_hms(2035, 4, 1, 10, 30, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2035, 4, 1, 11, 30, 0).unwrap(),
             api_code: "CODE4".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Clone the slots for the test
@@ -300,7 +314,7 @@ mod time_slots_tests {
             api_code: "CODE1".to_string(),
         };
         
-        let result = parse_time_slot(&item);
+        let result = parse_time_slot(&item, DEFAULT_TIME_ZONE);
         assert!(result.is_ok());
         
         let time_slot = result.unwrap();
@@ -318,7 +332,7 @@ mod time_slots_tests {
             api_code: "CODE2".to_string(),
         };
         
-        let result = parse_time_slot(&item);
+        let result = parse_time_slot(&item, DEFAULT_TIME_ZONE);
         assert!(result.is_ok());
         
         let time_slot = result.unwrap();
@@ -338,6 +352,7 @@ mod time_slots_tests {
             start_time: Utc.with_ymd_and_hms(2025, 4, 1, 14, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2025, 4, 1, 14, 30, 0).unwrap(),
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let slot2 = TimeSlot {
@@ -347,6 +362,7 @@ mod time_slots_tests {
             start_time: Utc.with_ymd_and_hms(2025, 4, 1, 14, 30, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2025, 4, 1, 15, 0, 0).unwrap(),
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         let slots = vec![slot1, slot2];
@@ -361,19 +377,24 @@ mod time_slots_tests {
         assert_eq!(result[0][1].scheduled_label, "2025-04-01 14:30-15:00");
     }
     
+    // Labels have minute precision, so scheduled_at has to be on a whole minute
+    fn whole_minute(time: chrono::DateTime<Utc>) -> chrono::DateTime<Utc> {
+        time.with_second(0).unwrap().with_nanosecond(0).unwrap()
+    }
+    
     #[test]
     fn test_past_time_adjustment() {
         // Create a time slot with a past time and future end time
         let now = Utc::now();
-        let past_time = now - chrono::Duration::hours(1); // 1 hour in the past
-        let future_time = now + chrono::Duration::hours(1); // 1 hour in the future
+        let past_time = whole_minute(now - chrono::Duration::hours(1)); // 1 hour in the past
+        let future_time = past_time + chrono::Duration::hours(2); // 1 hour in the future
         
         // Create a time string that would normally span 2 hours
         let past_rfc3339 = past_time.to_rfc3339();
         let time_label = format!(
             "{}-{}", 
-            past_time.format("%Y-%m-%d %H:%M"),
-            future_time.format("%H:%M")
+            past_time.with_timezone(&DEFAULT_TIME_ZONE).format("%Y-%m-%d %H:%M"),
+            future_time.with_timezone(&DEFAULT_TIME_ZONE).format("%H:%M")
         );
         
         let item = FormField1Item {
@@ -384,7 +405,7 @@ mod time_slots_tests {
             api_code: "CODE1".to_string(),
         };
         
-        let result = parse_time_slot(&item);
+        let result = parse_time_slot(&item, DEFAULT_TIME_ZONE);
         assert!(result.is_ok());
         
         let time_slot = result.unwrap();
@@ -393,7 +414,7 @@ mod time_slots_tests {
         assert!(time_slot.start_time > now);
         let start_diff = (time_slot.start_time - now).num_seconds();
         // Allow for a small margin of error in the test due to execution time
-        assert!(start_diff >= 115 && start_diff <= 125); // ~120 seconds (2 minutes)
+        assert!((115..=125).contains(&start_diff)); // ~120 seconds (2 minutes)
         
         // Check that end time is preserved from the original time
         // It should be approximately the same as our future_time
@@ -411,15 +432,15 @@ mod time_slots_tests {
     fn test_past_time_with_past_end_time() {
         // Test when both start and end times are in the past
         let now = Utc::now();
-        let past_start = now - chrono::Duration::minutes(30); // 30 minutes in the past
-        let past_end = now - chrono::Duration::minutes(15); // 15 minutes in the past
+        let past_start = whole_minute(now - chrono::Duration::minutes(30)); // 30 minutes in the past
+        let past_end = past_start + chrono::Duration::minutes(15); // 15 minutes in the past
         
         // Create a time string for a past time slot
         let past_rfc3339 = past_start.to_rfc3339();
         let time_label = format!(
             "{}-{}", 
-            past_start.format("%Y-%m-%d %H:%M"),
-            past_end.format("%H:%M")
+            past_start.with_timezone(&DEFAULT_TIME_ZONE).format("%Y-%m-%d %H:%M"),
+            past_end.with_timezone(&DEFAULT_TIME_ZONE).format("%H:%M")
         );
         
         let item = FormField1Item {
@@ -430,12 +451,13 @@ mod time_slots_tests {
             api_code: "CODE1".to_string(),
         };
         
-        let result = parse_time_slot(&item);
+        let result = parse_time_slot(&item, DEFAULT_TIME_ZONE);
         assert!(result.is_err());
         
         // Verify error message
         let error = result.unwrap_err();
-        assert!(error.contains("Time slot is entirely in the past"));
+        assert_eq!(error, TimeSlotError::InPast);
+        assert!(error.to_string().contains("Time slot is entirely in the past"));
     }
     
     #[test]
@@ -453,6 +475,7 @@ mod time_slots_tests {
             start_time: future_base - chrono::Duration::minutes(15),
             end_time: future_base + chrono::Duration::minutes(15),
             api_code: "CODE1".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Second time slot: consecutive with the first one
@@ -463,6 +486,7 @@ mod time_slots_tests {
             start_time: future_base + chrono::Duration::minutes(15),
             end_time: future_base + chrono::Duration::minutes(45),
             api_code: "CODE2".to_string(),
+            time_zone: DEFAULT_TIME_ZONE,
         };
         
         // Create mergeable groups
@@ -477,4 +501,119 @@ mod time_slots_tests {
         assert_eq!(result[0][0].number, slot1.number);
         assert_eq!(result[0][1].number, slot2.number);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_format_scheduled_label_uses_local_time() {
+        let start = Utc.with_ymd_and_hms(2025, 3, 30, 1, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 30, 2, 30, 0).unwrap();
+        assert_eq!(format_scheduled_label(start, end, DEFAULT_TIME_ZONE), "2025-03-30 09:00-10:30");
+        
+        // Late UTC times fall on the next local day
+        let start = Utc.with_ymd_and_hms(2025, 3, 30, 17, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 30, 18, 0, 0).unwrap();
+        assert_eq!(format_scheduled_label(start, end, DEFAULT_TIME_ZONE), "2025-03-31 01:00-02:00");
+        
+        // Ranges ending at the next midnight keep the short form, longer ones get the end date
+        let start = Utc.with_ymd_and_hms(2025, 3, 30, 15, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 30, 16, 0, 0).unwrap();
+        assert_eq!(format_scheduled_label(start, end, DEFAULT_TIME_ZONE), "2025-03-30 23:00-00:00");
+        let end = Utc.with_ymd_and_hms(2025, 3, 30, 17, 0, 0).unwrap();
+        assert_eq!(format_scheduled_label(start, end, DEFAULT_TIME_ZONE), "2025-03-30 23:00-2025-03-31 01:00");
+    }
+    
+    #[test]
+    fn test_merged_label_across_midnight() {
+        let slot = |label: &str, hour: u32| {
+            let start_time = Utc.with_ymd_and_hms(2025, 3, 30, hour, 0, 0).unwrap();
+            TimeSlot {
+                item_name: "Test Room".to_string(),
+                scheduled_label: label.to_string(),
+                number: 1,
+                start_time,
+                end_time: start_time + chrono::Duration::hours(1),
+                api_code: "CODE1".to_string(),
+                time_zone: DEFAULT_TIME_ZONE,
+            }
+        };
+        
+        let same_day = [slot("2025-03-30 09:00-10:00", 1), slot("2025-03-30 10:00-11:00", 2)];
+        assert_eq!(merged_scheduled_label(&same_day), "2025-03-30 09:00-11:00");
+        
+        let overnight = [slot("2025-03-31 00:00-01:00", 16), slot("2025-03-30 23:00-00:00", 15)];
+        assert_eq!(merged_scheduled_label(&overnight), "2025-03-30 23:00-2025-03-31 01:00");
+    }
+
+    fn item(label: &str, scheduled_at: &str) -> FormField1Item {
+        FormField1Item {
+            item_name: "Test Room".to_string(),
+            scheduled_label: label.to_string(),
+            number: 1,
+            scheduled_at: scheduled_at.to_string(),
+            api_code: "CODE1".to_string(),
+        }
+    }
+    
+    #[test]
+    fn test_parse_time_slot_label_grammar() {
+        // Full-width labels and labels across midnight give the end time
+        let slot = parse_time_slot(&item("２０３５-０４-０１ ９：００－１０：３０", "2035-04-01T01:00:00Z"), DEFAULT_TIME_ZONE).unwrap();
+        assert_eq!(slot.end_time, Utc.with_ymd_and_hms(2035, 4, 1, 2, 30, 0).unwrap());
+        assert_eq!(slot.scheduled_label, "2035-04-01 09:00-10:30");
+        let slot = parse_time_slot(&item("2035-04-01 23:00-01:00", "2035-04-01T15:00:00Z"), DEFAULT_TIME_ZONE).unwrap();
+        assert_eq!(slot.end_time, Utc.with_ymd_and_hms(2035, 4, 1, 17, 0, 0).unwrap());
+
+        // A label that doesn't parse is an error, not a one-hour meeting
+        let error = parse_time_slot(&item("2035-04-01 09:00-10", "2035-04-01T01:00:00Z"), DEFAULT_TIME_ZONE).unwrap_err();
+        assert_eq!(error, TimeSlotError::InvalidLabel { reason: ScheduledLabelError::InvalidTime { value: "10".to_string() } });
+        let error = parse_time_slot(&item("2035-04-01 09:00-10:00", "tomorrow"), DEFAULT_TIME_ZONE).unwrap_err();
+        assert!(matches!(error, TimeSlotError::InvalidScheduledAt { .. }));
+        
+        // scheduled_at has to be the label's start
+        let error = parse_time_slot(&item("2035-04-01 09:00-10:00", "2035-04-01T02:00:00Z"), DEFAULT_TIME_ZONE).unwrap_err();
+        assert_eq!(error, TimeSlotError::ScheduledAtMismatch { scheduled_at: "2035-04-01T02:00:00Z".to_string(), label_start: "2035-04-01 09:00".to_string() });
+    }
+    
+    #[test]
+    fn test_parse_time_slot_in_configured_zone() {
+        // Without an offset, scheduled_at is local time in the slot's zone
+        let slot = parse_time_slot(&item("2035-07-02 09:00-10:30", "2035-07-02T09:00:00"), London).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 7, 2, 8, 0, 0).unwrap());
+        assert_eq!(slot.end_time, Utc.with_ymd_and_hms(2035, 7, 2, 9, 30, 0).unwrap());
+        assert_eq!(slot.time_zone, London);
+        
+        let slot = parse_time_slot(&item("2035-07-02 09:00-10:00", "2035-07-02 09:00"), DEFAULT_TIME_ZONE).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 7, 2, 1, 0, 0).unwrap());
+        
+        // Any offset is honoured
+        let slot = parse_time_slot(&item("2035-07-02 09:00-10:00", "2035-07-02T09:00:00+01:00"), London).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 7, 2, 8, 0, 0).unwrap());
+        let slot = parse_time_slot(&item("2035-07-02 09:00-10:00", "2035-07-02T09:00:00+08:00"), DEFAULT_TIME_ZONE).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 7, 2, 1, 0, 0).unwrap());
+        
+        assert!(parse_time_slot(&item("2035-07-02 09:00-10:00", "next tuesday"), London).is_err());
+    }
+    
+    #[test]
+    fn test_parse_time_slot_across_daylight_saving_change() {
+        // Clocks in New York go forward at 02:00 on 2035-03-11, so 01:00-04:00 lasts two hours
+        let slot = parse_time_slot(&item("2035-03-11 01:00-04:00", "2035-03-11T01:00:00"), New_York).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 3, 11, 6, 0, 0).unwrap());
+        assert_eq!(slot.end_time, Utc.with_ymd_and_hms(2035, 3, 11, 8, 0, 0).unwrap());
+        
+        // A skipped local time moves past the gap
+        let slot = parse_time_slot(&item("2035-03-11 02:30-03:30", "2035-03-11T02:30:00"), New_York).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 3, 11, 7, 30, 0).unwrap());
+        
+        // A repeated local time (clocks go back on 2035-11-04) uses the first occurrence
+        let slot = parse_time_slot(&item("2035-11-04 01:30-02:00", "2035-11-04T01:30:00"), New_York).unwrap();
+        assert_eq!(slot.start_time, Utc.with_ymd_and_hms(2035, 11, 4, 5, 30, 0).unwrap());
+        assert_eq!(slot.end_time, Utc.with_ymd_and_hms(2035, 11, 4, 7, 0, 0).unwrap());
+    }
+    
+    #[test]
+    fn test_format_scheduled_label_in_other_zone() {
+        let start = Utc.with_ymd_and_hms(2025, 7, 1, 8, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 7, 1, 9, 30, 0).unwrap();
+        assert_eq!(format_scheduled_label(start, end, London), "2025-07-01 09:00-10:30");
+    }
+}